@@ -0,0 +1,300 @@
+;; Heap allocator backing the alloc, free and realloc builtins
+;;
+;; Every block starts with a 16 byte header, [block - 16] holds the capacity of the block and
+;; [block - 8] whether it's in use. Requests up to HEAP_LARGEST_CLASS bytes are rounded up to a
+;; power of two size class and carved out of HEAP_ARENA_SIZE byte arenas. Freed blocks go on the
+;; free list of their size class, the first 8 bytes of a free block point to the next one.
+;; Bigger requests get their own mapping which is unmapped on free
+;;
+;; heapDebug is defined by the generated code. When it's set, freeing a block twice exits with an
+;; error and _heapReportLeaks prints the blocks that were never freed
+
+SYS_MMAP equ 9
+SYS_MUNMAP equ 11
+
+PROT_READ_WRITE equ 3
+MAP_PRIVATE_ANONYMOUS equ 0x22
+
+HEAP_HEADER_SIZE equ 16
+HEAP_SMALLEST_CLASS equ 16
+HEAP_LARGEST_CLASS equ 4096
+HEAP_NUM_CLASSES equ 9
+HEAP_ARENA_SIZE equ 65536
+
+HEAP_BLOCK_USED equ 0x0a110c8d
+HEAP_BLOCK_FREE equ 0x0f4eeb1c
+
+section .bss
+    heapFreeLists resq HEAP_NUM_CLASSES
+    heapArenaNext resq 1
+    heapArenaEnd resq 1
+    heapLiveBlocks resq 1
+    heapLiveBytes resq 1
+
+section .data
+    heapInvalidFreeMessage db "Invalid free, the pointer was already freed or wasn't returned by alloc", 10
+    heapInvalidFreeMessageLen equ $ - heapInvalidFreeMessage
+    heapLeakMessage db "Leaked "
+    heapLeakMessageLen equ $ - heapLeakMessage
+    heapLeakBlocksMessage db " block(s), "
+    heapLeakBlocksMessageLen equ $ - heapLeakBlocksMessage
+    heapLeakBytesMessage db " byte(s)", 10
+    heapLeakBytesMessageLen equ $ - heapLeakBytesMessage
+
+section .text
+
+;; Maps rdi bytes of zeroed memory. Returns the address in rax, or 0 if the mapping failed
+_heapMmap:
+    mov rsi, rdi
+    xor rdi, rdi
+    mov rdx, PROT_READ_WRITE
+    mov r10, MAP_PRIVATE_ANONYMOUS
+    mov r8, -1
+    xor r9, r9
+    mov rax, SYS_MMAP
+    syscall
+
+    ;; errors are returned as -errno
+    cmp rax, -4096
+    jb _heapMmapDone
+
+    xor rax, rax
+
+_heapMmapDone:
+    ret
+
+;; Allocates rdi bytes. Returns the address in rax, or 0 if we're out of memory
+_heapAlloc:
+    cmp rdi, HEAP_LARGEST_CLASS
+    ja _heapAllocLarge
+
+    ;; rcx = size class index, rdx = size of the class
+    xor rcx, rcx
+    mov rdx, HEAP_SMALLEST_CLASS
+
+_heapAllocFindClass:
+    cmp rdx, rdi
+    jae _heapAllocFromList
+
+    shl rdx, 1
+    inc rcx
+    jmp _heapAllocFindClass
+
+_heapAllocFromList:
+    mov rax, [heapFreeLists + rcx * 8]
+    test rax, rax
+    jz _heapAllocFromArena
+
+    mov rsi, [rax]
+    mov [heapFreeLists + rcx * 8], rsi
+    jmp _heapAllocDone
+
+_heapAllocFromArena:
+    mov rax, [heapArenaNext]
+    lea r8, [rax + rdx + HEAP_HEADER_SIZE]
+    cmp r8, [heapArenaEnd]
+    jbe _heapAllocCarve
+
+    ;; whatever is left of the current arena is too small, start a new one
+    push rdx
+    mov rdi, HEAP_ARENA_SIZE
+    call _heapMmap
+    pop rdx
+
+    test rax, rax
+    jz _heapAllocFailed
+
+    lea r8, [rax + HEAP_ARENA_SIZE]
+    mov [heapArenaEnd], r8
+    lea r8, [rax + rdx + HEAP_HEADER_SIZE]
+
+_heapAllocCarve:
+    mov [heapArenaNext], r8
+    mov [rax], rdx
+    add rax, HEAP_HEADER_SIZE
+    jmp _heapAllocDone
+
+_heapAllocLarge:
+    push rdi
+    add rdi, HEAP_HEADER_SIZE
+    call _heapMmap
+    pop rdx
+
+    test rax, rax
+    jz _heapAllocFailed
+
+    mov [rax], rdx
+    add rax, HEAP_HEADER_SIZE
+
+_heapAllocDone:
+    mov QWORD [rax - 8], HEAP_BLOCK_USED
+    inc QWORD [heapLiveBlocks]
+    mov rdx, [rax - 16]
+    add [heapLiveBytes], rdx
+    ret
+
+_heapAllocFailed:
+    xor rax, rax
+    ret
+
+;; Frees the block at rdi. Freeing 0 does nothing
+_heapFree:
+    test rdi, rdi
+    jz _heapFreeDone
+
+    cmp QWORD [rdi - 8], HEAP_BLOCK_USED
+    je _heapFreeUsed
+
+    cmp BYTE [heapDebug], 0
+    jne _heapInvalidFree
+
+    ret
+
+_heapFreeUsed:
+    mov QWORD [rdi - 8], HEAP_BLOCK_FREE
+    dec QWORD [heapLiveBlocks]
+    mov rax, [rdi - 16]
+    sub [heapLiveBytes], rax
+
+    cmp rax, HEAP_LARGEST_CLASS
+    ja _heapFreeLarge
+
+    xor rcx, rcx
+    mov rdx, HEAP_SMALLEST_CLASS
+
+_heapFreeFindClass:
+    cmp rdx, rax
+    jae _heapFreePush
+
+    shl rdx, 1
+    inc rcx
+    jmp _heapFreeFindClass
+
+_heapFreePush:
+    mov rsi, [heapFreeLists + rcx * 8]
+    mov [rdi], rsi
+    mov [heapFreeLists + rcx * 8], rdi
+    ret
+
+_heapFreeLarge:
+    ;; keep the mapping around in debug mode so a second free can still read the header
+    cmp BYTE [heapDebug], 0
+    jne _heapFreeDone
+
+    lea rsi, [rax + HEAP_HEADER_SIZE]
+    sub rdi, HEAP_HEADER_SIZE
+    mov rax, SYS_MUNMAP
+    syscall
+
+_heapFreeDone:
+    ret
+
+_heapInvalidFree:
+    mov rax, 1
+    mov rdi, 2
+    mov rsi, heapInvalidFreeMessage
+    mov rdx, heapInvalidFreeMessageLen
+    syscall
+
+    exit 1
+
+;; Resizes the block at rdi to rsi bytes, moving it if it doesn't fit. Returns the address in rax,
+;; or 0 if we're out of memory, in which case the old block is left untouched
+_heapRealloc:
+    test rdi, rdi
+    jnz _heapReallocExisting
+
+    mov rdi, rsi
+    jmp _heapAlloc
+
+_heapReallocExisting:
+    cmp QWORD [rdi - 8], HEAP_BLOCK_USED
+    je _heapReallocUsed
+
+    cmp BYTE [heapDebug], 0
+    jne _heapInvalidFree
+
+_heapReallocUsed:
+    mov rax, rdi
+    cmp rsi, [rdi - 16]
+    jbe _heapReallocDone
+
+    push rdi
+    mov rdi, rsi
+    call _heapAlloc
+    pop rsi
+
+    test rax, rax
+    jz _heapReallocDone
+
+    push rax
+    push rsi
+
+    mov rdi, rax
+    mov rcx, [rsi - 16]
+    rep movsb
+
+    pop rdi
+    call _heapFree
+    pop rax
+
+_heapReallocDone:
+    ret
+
+;; Writes rdx bytes at rsi to stderr
+_heapWriteStderr:
+    mov rax, 1
+    mov rdi, 2
+    syscall
+    ret
+
+;; Writes rax to stderr as an unsigned number
+_heapWriteNumberStderr:
+    sub rsp, 32
+    lea rsi, [rsp + 32]
+    mov rcx, 10
+
+_heapWriteNumberStderrLoop:
+    xor rdx, rdx
+    div rcx
+    add dl, 48
+    dec rsi
+    mov [rsi], dl
+
+    test rax, rax
+    jnz _heapWriteNumberStderrLoop
+
+    mov rax, 1
+    mov rdi, 2
+    lea rdx, [rsp + 32]
+    sub rdx, rsi
+    syscall
+
+    add rsp, 32
+    ret
+
+;; Prints the number of blocks and bytes that were never freed to stderr
+_heapReportLeaks:
+    cmp QWORD [heapLiveBlocks], 0
+    je _heapReportLeaksDone
+
+    mov rsi, heapLeakMessage
+    mov rdx, heapLeakMessageLen
+    call _heapWriteStderr
+
+    mov rax, [heapLiveBlocks]
+    call _heapWriteNumberStderr
+
+    mov rsi, heapLeakBlocksMessage
+    mov rdx, heapLeakBlocksMessageLen
+    call _heapWriteStderr
+
+    mov rax, [heapLiveBytes]
+    call _heapWriteNumberStderr
+
+    mov rsi, heapLeakBytesMessage
+    mov rdx, heapLeakBytesMessageLen
+    call _heapWriteStderr
+
+_heapReportLeaksDone:
+    ret
@@ -0,0 +1,367 @@
+;; Runtime for the print and fprint builtins
+;;
+;; A print call appends everything it formats to formatBuffer and writes it to formatFd in one go
+;; at the end. The buffer is only written out early if it fills up
+;;
+;; Numbers are formatted right to left into a buffer on the stack, the digit routines take the
+;; address right after the last digit in rsi and leave rsi at the first one
+
+FORMAT_BUFFER_SIZE equ 4096
+
+;; Flags of a placeholder, see FormatSpec
+FORMAT_LEFT equ 1
+FORMAT_ZERO equ 2
+FORMAT_HEX equ 4
+FORMAT_UPPER equ 8
+
+section .bss
+    formatBuffer resb FORMAT_BUFFER_SIZE
+    formatBufferLen resq 1
+    formatFd resq 1
+
+section .text
+
+;; Starts formatting output for the file descriptor in rdi
+_formatStart:
+    mov [formatFd], rdi
+    mov QWORD [formatBufferLen], 0
+    ret
+
+;; Writes the rdx bytes at rsi to formatFd. Output to stdout goes through its buffer, so it stays
+;; in order with write
+_formatWrite:
+    cmp QWORD [formatFd], STDOUT_FD
+    je _stdoutWrite
+
+    mov rax, SYS_WRITE
+    mov rdi, [formatFd]
+    syscall
+    ret
+
+;; Writes out whatever has been formatted so far
+_formatFlush:
+    mov rsi, formatBuffer
+    mov rdx, [formatBufferLen]
+    call _formatWrite
+
+    mov QWORD [formatBufferLen], 0
+    ret
+
+;; Appends the rsi bytes at rdi. Bytes that don't fit in an empty buffer are written out directly
+_formatBytes:
+    mov rax, [formatBufferLen]
+    add rax, rsi
+    cmp rax, FORMAT_BUFFER_SIZE
+    jbe _formatBytesCopy
+
+    push rdi
+    push rsi
+    call _formatFlush
+    pop rsi
+    pop rdi
+
+    cmp rsi, FORMAT_BUFFER_SIZE
+    jbe _formatBytesCopy
+
+    mov rdx, rsi
+    mov rsi, rdi
+    jmp _formatWrite
+
+_formatBytesCopy:
+    mov rcx, rsi
+    mov rsi, rdi
+    mov rdi, formatBuffer
+    add rdi, [formatBufferLen]
+    add [formatBufferLen], rcx
+
+    rep movsb
+    ret
+
+;; Appends the byte in dil rsi times
+_formatRepeat:
+    test rsi, rsi
+    jz _formatRepeatDone
+
+    cmp QWORD [formatBufferLen], FORMAT_BUFFER_SIZE
+    jb _formatRepeatStore
+
+    push rdi
+    push rsi
+    call _formatFlush
+    pop rsi
+    pop rdi
+
+_formatRepeatStore:
+    mov rax, [formatBufferLen]
+    mov [formatBuffer + rax], dil
+    inc QWORD [formatBufferLen]
+
+    dec rsi
+    jmp _formatRepeat
+
+_formatRepeatDone:
+    ret
+
+;; Appends the rsi bytes at rdi padded to a width of rdx, rcx has the flags. Zeros go after the
+;; sign of a negative number
+_formatPadded:
+    ;; r8 = how much padding is needed
+    mov r8, rdx
+    sub r8, rsi
+    jg _formatPaddedAlign
+    xor r8, r8
+
+_formatPaddedAlign:
+    test rcx, FORMAT_LEFT
+    jnz _formatPaddedLeft
+
+    test rcx, FORMAT_ZERO
+    jnz _formatPaddedZero
+
+    push rdi
+    push rsi
+
+    mov rdi, 32 ; ' '
+    mov rsi, r8
+    call _formatRepeat
+
+    pop rsi
+    pop rdi
+    jmp _formatBytes
+
+_formatPaddedLeft:
+    push r8
+    call _formatBytes
+    pop rsi
+
+    mov rdi, 32 ; ' '
+    jmp _formatRepeat
+
+_formatPaddedZero:
+    test rsi, rsi
+    jz _formatPaddedZeros
+
+    cmp BYTE [rdi], 45 ; '-'
+    jne _formatPaddedZeros
+
+    push rdi
+    push rsi
+    push r8
+
+    mov rsi, 1
+    call _formatBytes
+
+    pop r8
+    pop rsi
+    pop rdi
+
+    inc rdi
+    dec rsi
+
+_formatPaddedZeros:
+    push rdi
+    push rsi
+
+    mov rdi, 48 ; '0'
+    mov rsi, r8
+    call _formatRepeat
+
+    pop rsi
+    pop rdi
+    jmp _formatBytes
+
+;; Writes rax as an unsigned number in base rbx. Digits above 9 start at the letter in cl
+_formatUnsignedDigits:
+    xor rdx, rdx
+    div rbx
+
+    cmp dl, 10
+    jb _formatDecimalDigit
+
+    add dl, cl
+    sub dl, 10
+    jmp _formatStoreDigit
+
+_formatDecimalDigit:
+    add dl, 48
+
+_formatStoreDigit:
+    dec rsi
+    mov [rsi], dl
+
+    test rax, rax
+    jnz _formatUnsignedDigits
+
+    ret
+
+;; Writes rdi as a signed number in base 10
+_formatSignedDigits:
+    mov rax, rdi
+    test rax, rax
+    jns _formatSignedDigitsAbs
+
+    neg rax
+
+_formatSignedDigitsAbs:
+    mov rbx, 10
+    call _formatUnsignedDigits
+
+    test rdi, rdi
+    jns _formatSignedDigitsDone
+
+    dec rsi
+    mov BYTE [rsi], 45 ; '-'
+
+_formatSignedDigitsDone:
+    ret
+
+;; Writes xmm0 with [float_precision] digits after the decimal point, the same way _printFloat
+;; prints it. The fraction goes in first
+_formatFloatDigits:
+    ;; r8 = sign bit
+    movq rax, xmm0
+    mov r8, rax
+    btr rax, 63
+    movq xmm0, rax
+
+    ;; integer part in rax, fractional part in xmm0
+    cvttsd2si rax, xmm0
+    cvtsi2sd xmm1, rax
+    subsd xmm0, xmm1
+
+    ;; r9 = 10 ^ precision
+    mov r9, 1
+    mov rcx, [float_precision]
+
+_formatFloatScale:
+    cmp rcx, 0
+    je _formatFloatRound
+    imul r9, 10
+    dec rcx
+    jmp _formatFloatScale
+
+_formatFloatRound:
+    cvtsi2sd xmm1, r9
+    mulsd xmm0, xmm1
+    cvtsd2si rdx, xmm0
+
+    ;; 0.9999999 rounds up to the next integer
+    cmp rdx, r9
+    jl _formatFloatFraction
+    sub rdx, r9
+    inc rax
+
+_formatFloatFraction:
+    mov r11, 10
+
+    mov rcx, [float_precision]
+    cmp rcx, 0
+    je _formatFloatInteger
+
+    mov r10, rax
+    mov rax, rdx
+
+_formatFloatFractionLoop:
+    xor rdx, rdx
+    div r11
+    add dl, 48
+    dec rsi
+    mov [rsi], dl
+
+    dec rcx
+    jnz _formatFloatFractionLoop
+
+    dec rsi
+    mov BYTE [rsi], 46 ; '.'
+
+    mov rax, r10
+
+_formatFloatInteger:
+    xor rdx, rdx
+    div r11
+    add dl, 48
+    dec rsi
+    mov [rsi], dl
+
+    test rax, rax
+    jnz _formatFloatInteger
+
+    bt r8, 63
+    jnc _formatFloatDigitsDone
+
+    dec rsi
+    mov BYTE [rsi], 45 ; '-'
+
+_formatFloatDigitsDone:
+    ret
+
+;; Appends the integer in rdi padded to a width of rsi, rdx has the flags. Hex numbers are
+;; formatted as unsigned
+_formatInt:
+    mov r8, rsi
+    mov r9, rdx
+
+    sub rsp, 32
+    lea rsi, [rsp + 32]
+
+    test r9, FORMAT_HEX
+    jnz _formatIntHex
+
+    call _formatSignedDigits
+    jmp _formatIntPad
+
+_formatIntHex:
+    mov rax, rdi
+    mov rbx, 16
+    mov rcx, 97 ; 'a'
+
+    test r9, FORMAT_UPPER
+    jz _formatIntHexDigits
+
+    mov rcx, 65 ; 'A'
+
+_formatIntHexDigits:
+    call _formatUnsignedDigits
+
+_formatIntPad:
+    mov rdi, rsi
+    lea rsi, [rsp + 32]
+    sub rsi, rdi
+    mov rdx, r8
+    mov rcx, r9
+    call _formatPadded
+
+    add rsp, 32
+    ret
+
+;; Appends the float in xmm0 padded to a width of rdi, rsi has the flags
+_formatFloat:
+    push rdi
+    push rsi
+
+    sub rsp, 64
+    lea rsi, [rsp + 64]
+    call _formatFloatDigits
+
+    mov rdi, rsi
+    lea rsi, [rsp + 64]
+    sub rsi, rdi
+    mov rdx, [rsp + 72]
+    mov rcx, [rsp + 64]
+    call _formatPadded
+
+    add rsp, 80
+    ret
+
+;; Appends the character in dil padded to a width of rsi, rdx has the flags
+_formatChar:
+    push rdi
+
+    mov rcx, rdx
+    mov rdx, rsi
+    mov rdi, rsp
+    mov rsi, 1
+    call _formatPadded
+
+    pop rdi
+    ret
@@ -0,0 +1,160 @@
+;; Buffered standard output and line reading
+;;
+;; Everything the program writes to stdout goes through stdoutBuffer. The buffer is written out
+;; once it's full, after a write that contains a newline, on flush() and before the program
+;; exits, so a line costs one syscall instead of one per write
+;;
+;; read_line reads IO_BUFFER_SIZE bytes at a time into readBuffer and hands them out a line at a
+;; time. The buffer belongs to one file descriptor, reading from another one drops what's left
+
+IO_BUFFER_SIZE equ 4096
+
+SYS_READ equ 0
+SYS_WRITE equ 1
+
+STDOUT_FD equ 1
+
+section .bss
+    stdoutBuffer resb IO_BUFFER_SIZE
+    stdoutBufferLen resq 1
+    readBuffer resb IO_BUFFER_SIZE
+    readBufferStart resq 1
+    readBufferEnd resq 1
+    readBufferFd resq 1
+
+section .text
+
+;; Writes out whatever is in the stdout buffer. Like a syscall, only rcx and r11 are clobbered
+_stdoutFlush:
+    cmp QWORD [stdoutBufferLen], 0
+    je _stdoutFlushDone
+
+    push rax
+    push rdi
+    push rsi
+    push rdx
+
+    mov rax, SYS_WRITE
+    mov rdi, STDOUT_FD
+    mov rsi, stdoutBuffer
+    mov rdx, [stdoutBufferLen]
+    syscall
+
+    mov QWORD [stdoutBufferLen], 0
+
+    pop rdx
+    pop rsi
+    pop rdi
+    pop rax
+
+_stdoutFlushDone:
+    ret
+
+;; Writes the rdx bytes at rsi to stdout through the buffer. Takes the same registers as the write
+;; syscall and clobbers the same ones, rax, rcx and r11
+_stdoutWrite:
+    push rdi
+    push rsi
+    push rdx
+
+    mov rax, [stdoutBufferLen]
+    add rax, rdx
+    cmp rax, IO_BUFFER_SIZE
+    jbe _stdoutWriteCopy
+
+    call _stdoutFlush
+
+    cmp rdx, IO_BUFFER_SIZE
+    jbe _stdoutWriteCopy
+
+    ;; too big for the buffer
+    mov rax, SYS_WRITE
+    mov rdi, STDOUT_FD
+    syscall
+    jmp _stdoutWriteDone
+
+_stdoutWriteCopy:
+    mov rcx, rdx
+    mov rdi, stdoutBuffer
+    add rdi, [stdoutBufferLen]
+    add [stdoutBufferLen], rdx
+
+    rep movsb
+
+    ;; flush if there's a newline in what was just written
+    mov rdi, [rsp + 8]
+    mov rcx, [rsp]
+    mov al, 10
+    repne scasb
+    jne _stdoutWriteDone
+
+    call _stdoutFlush
+
+_stdoutWriteDone:
+    pop rdx
+    pop rsi
+    pop rdi
+    ret
+
+;; Reads a line from the file descriptor in rdi and returns it as a String in rax. The newline is
+;; kept, so the String is only empty at the end of the file
+_readLine:
+    cmp rdi, [readBufferFd]
+    je _readLineStart
+
+    mov [readBufferFd], rdi
+    mov QWORD [readBufferStart], 0
+    mov QWORD [readBufferEnd], 0
+
+_readLineStart:
+    mov rdi, 64
+    call _stringNew
+    push rax
+
+_readLineLoop:
+    mov rsi, [readBufferStart]
+    mov rcx, [readBufferEnd]
+    sub rcx, rsi
+    jnz _readLineScan
+
+    mov rax, SYS_READ
+    mov rdi, [readBufferFd]
+    mov rsi, readBuffer
+    mov rdx, IO_BUFFER_SIZE
+    syscall
+
+    ;; end of file or an error
+    cmp rax, 0
+    jle _readLineDone
+
+    mov QWORD [readBufferStart], 0
+    mov [readBufferEnd], rax
+    jmp _readLineLoop
+
+_readLineScan:
+    ;; rsi = first unread byte, rdi = one past the newline or the end of what's buffered
+    add rsi, readBuffer
+    mov rdi, rsi
+    mov al, 10
+    repne scasb
+
+    ;; r8 = 1 if the line is complete
+    sete r8b
+    movzx r8, r8b
+    push r8
+
+    mov rdx, rdi
+    sub rdx, rsi
+    add [readBufferStart], rdx
+
+    mov rdi, [rsp + 8]
+    call _stringAppendBytes
+    mov [rsp + 8], rax
+
+    pop r8
+    test r8, r8
+    jz _readLineLoop
+
+_readLineDone:
+    pop rax
+    ret
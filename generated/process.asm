@@ -0,0 +1,112 @@
+;; Command line arguments and environment variables
+;;
+;; _start saves the stack pointer it was entered with in argc. The kernel leaves argc there,
+;; followed by the argv pointers, a NULL, the envp pointers and another NULL. All the strings are
+;; NULL terminated
+
+section .bss
+    ;; The value of the last env() call, the address followed by the length
+    envValue resq 2
+
+section .data
+    sliceIndexMessage db "Slice index out of range", 10
+    sliceIndexMessageLen equ $ - sliceIndexMessage
+
+section .text
+
+;; Returns the length of the NULL terminated string at rdi in rax
+_cStringLen:
+    xor rax, rax
+
+_cStringLenLoop:
+    cmp BYTE [rdi + rax], 0
+    je _cStringLenDone
+    inc rax
+    jmp _cStringLenLoop
+
+_cStringLenDone:
+    ret
+
+;; Fills the memory at rsi with a []str of the command line arguments and returns it in rax. rdi
+;; has the stack pointer _start was entered with. The header takes the first 16 bytes, the
+;; elements come right after it
+_argsSlice:
+    mov rcx, [rdi]
+    lea rdx, [rsi + 16]
+    mov [rsi], rdx
+    mov [rsi + 8], rcx
+
+    lea r8, [rdi + 8]
+
+_argsSliceLoop:
+    test rcx, rcx
+    jz _argsSliceDone
+
+    mov rdi, [r8]
+    mov [rdx], rdi
+    call _cStringLen
+    mov [rdx + 8], rax
+
+    add rdx, 16
+    add r8, 8
+    dec rcx
+    jmp _argsSliceLoop
+
+_argsSliceDone:
+    mov rax, rsi
+    ret
+
+;; Looks up the environment variable whose name is the rsi bytes at rdi and stores its value in
+;; envValue. A variable that isn't set has an empty value
+_env:
+    ;; r8 = the first envp pointer
+    mov r8, [argc]
+    mov rax, [r8]
+    lea r8, [r8 + rax * 8 + 16]
+
+_envLoop:
+    mov r9, [r8]
+    test r9, r9
+    jz _envNotFound
+
+    ;; does the entry start with the name followed by '='
+    xor rcx, rcx
+
+_envCompare:
+    cmp rcx, rsi
+    je _envCompareEquals
+
+    mov al, [r9 + rcx]
+    cmp al, [rdi + rcx]
+    jne _envNext
+
+    inc rcx
+    jmp _envCompare
+
+_envCompareEquals:
+    cmp BYTE [r9 + rcx], 61 ; '='
+    jne _envNext
+
+    lea rdi, [r9 + rcx + 1]
+    mov [envValue], rdi
+    call _cStringLen
+    mov [envValue + 8], rax
+    ret
+
+_envNext:
+    add r8, 8
+    jmp _envLoop
+
+_envNotFound:
+    mov [envValue], r8
+    mov QWORD [envValue + 8], 0
+    ret
+
+_sliceIndexOutOfRange:
+    mov rax, SYS_WRITE
+    mov rdi, 2
+    mov rsi, sliceIndexMessage
+    mov rdx, sliceIndexMessageLen
+    syscall
+
+    exit 1
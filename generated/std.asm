@@ -1,11 +1,19 @@
 SYS_EXIT equ 60
 
+;; Flushes stdout before exiting, see generated/io.asm
 %macro exit 1 ; 1 -> takes one argument
+    call _stdoutFlush
     mov rax, SYS_EXIT
     mov rdi, %1
     syscall
 %endmacro
 
+section .data
+    castOverflowMessage db "Cast lost information, value does not fit in the new type", 10
+    castOverflowMessageLen equ $ - castOverflowMessage
+
+section .text
+
 _printRAX:
     
     mov rcx, digitSpace
@@ -48,11 +56,9 @@ _printRAXLoop:
 _printRAXLoop2:
     mov rcx, [digitSpacePos]
 
-    mov rax, 1
-    mov rdi, 1
     mov rsi, rcx
     mov rdx, 1
-    syscall
+    call _stdoutWrite
 
     mov rcx, [digitSpacePos]
     dec rcx
@@ -63,11 +69,144 @@ _printRAXLoop2:
 
     ret
 
-_printString:
-    mov rax, 1
+;; Prints rax as an unsigned number with at least rdi digits, padding with zeros. Doesn't
+;; print a newline
+_printUnsigned:
+    sub rsp, 32
+    lea rsi, [rsp + 32]
+    mov rbx, 10
+
+_printUnsignedLoop:
+    xor rdx, rdx
+    div rbx
+    add dl, 48
+    dec rsi
+    mov [rsi], dl
+    dec rdi
+
+    test rax, rax
+    jnz _printUnsignedLoop
+
+    test rdi, rdi
+    jg _printUnsignedLoop
+
+    lea rdx, [rsp + 32]
+    sub rdx, rsi
+    call _stdoutWrite
+
+    add rsp, 32
+
+    ret
+
+;; Prints the character in al
+_printChar:
+    push rax
+
+    mov rsi, rsp
+    mov rdx, 1
+    call _stdoutWrite
+
+    pop rax
+
+    ret
+
+;; Prints rax as a signed number followed by a newline
+_printSignedRAX:
+    cmp rax, 0
+    jge _printSignedRAXAbs
+
+    push rax
+    mov rax, 45 ; '-'
+    call _printChar
+    pop rax
+
+    neg rax
+
+_printSignedRAXAbs:
+    mov rdi, 1
+    call _printUnsigned
+
+    mov rax, 10
+    call _printChar
+
+    ret
+
+;; Prints xmm0 with [float_precision] digits after the decimal point followed by a newline.
+;; Only values whose integer part fits in 63 bits are printed correctly
+_printFloat:
+    movq rax, xmm0
+    btr rax, 63
+    jnc _printFloatAbs
+
+    movq xmm0, rax
+    mov rax, 45 ; '-'
+    call _printChar
+
+_printFloatAbs:
+    ;; integer part in rax, fractional part in xmm0
+    cvttsd2si rax, xmm0
+    cvtsi2sd xmm1, rax
+    subsd xmm0, xmm1
+
+    ;; rbx = 10 ^ precision
+    mov rbx, 1
+    mov rcx, [float_precision]
+
+_printFloatScale:
+    cmp rcx, 0
+    je _printFloatRound
+    imul rbx, 10
+    dec rcx
+    jmp _printFloatScale
+
+_printFloatRound:
+    cvtsi2sd xmm1, rbx
+    mulsd xmm0, xmm1
+    cvtsd2si rdx, xmm0
+
+    ;; 0.9999999 rounds up to the next integer
+    cmp rdx, rbx
+    jl _printFloatInteger
+    sub rdx, rbx
+    inc rax
+
+_printFloatInteger:
+    push rdx
     mov rdi, 1
+    call _printUnsigned
+    pop rdx
+
+    mov rcx, [float_precision]
+    cmp rcx, 0
+    je _printFloatEnd
+
+    push rdx
+    mov rax, 46 ; '.'
+    call _printChar
+    pop rax
+
+    mov rdi, [float_precision]
+    call _printUnsigned
+
+_printFloatEnd:
+    mov rax, 10
+    call _printChar
+
+    ret
+
+;; Jumped to when a cast loses information with --checked-casts
+_castOverflow:
+    mov rax, 1
+    mov rdi, 2
+    mov rsi, castOverflowMessage
+    mov rdx, castOverflowMessageLen
+    syscall
+
+    exit 1
+
+_printString:
     mov rsi, r9
     mov rdx, r8
-    syscall
+    call _stdoutWrite
 
     ret
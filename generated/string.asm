@@ -0,0 +1,304 @@
+;; Runtime for the String type
+;;
+;; A String is a pointer to a block from _heapAlloc. [string] holds the length, [string + 8] the
+;; capacity and the bytes start at [string + STRING_HEADER_SIZE]. Functions that read a string take
+;; the address of its first byte and its length, so they work for both str and String
+;;
+;; Strings made by an expression, like a + b, aren't stored anywhere. Functions that read strings
+;; take a bit mask of which of them are such temporaries as their last argument and free those
+;; before returning, bit 0 for the first string and bit 1 for the second one
+
+STRING_HEADER_SIZE equ 16
+
+section .data
+    stringSliceMessage db "String slice out of range", 10
+    stringSliceMessageLen equ $ - stringSliceMessage
+    stringOutOfMemoryMessage db "Out of memory while allocating a String", 10
+    stringOutOfMemoryMessageLen equ $ - stringOutOfMemoryMessage
+
+section .text
+
+;; Allocates an empty String with room for at least rdi bytes. Returns it in rax
+_stringNew:
+    add rdi, STRING_HEADER_SIZE
+    call _heapAlloc
+
+    test rax, rax
+    jz _stringOutOfMemory
+
+    ;; the allocator rounds the size up, all of it can be used
+    mov rdx, [rax - 16]
+    sub rdx, STRING_HEADER_SIZE
+
+    mov QWORD [rax], 0
+    mov [rax + 8], rdx
+
+    ret
+
+;; Copies the rsi bytes at rdi to the end of the String in rax, which has to have room for them.
+;; Returns the String in rax
+_stringCopyInto:
+    mov rcx, rsi
+    mov rsi, rdi
+
+    lea rdi, [rax + STRING_HEADER_SIZE]
+    add rdi, [rax]
+    add [rax], rcx
+
+    rep movsb
+
+    ret
+
+;; Makes a String out of the rsi bytes at rdi
+_stringFromBytes:
+    push rdi
+    push rsi
+
+    mov rdi, rsi
+    call _stringNew
+
+    pop rsi
+    pop rdi
+    jmp _stringCopyInto
+
+;; Frees the temporaries among the strings whose bytes start at rdi and rsi, rdx has the bit mask.
+;; Leaves rax as is
+_stringDropTemporaries:
+    push rax
+    push rsi
+    push rdx
+
+    test rdx, 1
+    jz _stringDropSecond
+
+    sub rdi, STRING_HEADER_SIZE
+    call _heapFree
+
+_stringDropSecond:
+    pop rdx
+    pop rdi
+
+    test rdx, 2
+    jz _stringDropDone
+
+    sub rdi, STRING_HEADER_SIZE
+    call _heapFree
+
+_stringDropDone:
+    pop rax
+    ret
+
+;; Makes a String out of the rsi bytes at rdi followed by the rcx bytes at rdx. r8 has the
+;; temporaries
+_stringConcat:
+    push rdi
+    push rdx
+    push r8
+
+    call _stringConcatBytes
+
+    pop rdx
+    pop rsi
+    pop rdi
+    jmp _stringDropTemporaries
+
+_stringConcatBytes:
+    push rdx
+    push rcx
+    push rdi
+    push rsi
+
+    lea rdi, [rsi + rcx]
+    call _stringNew
+
+    pop rsi
+    pop rdi
+    call _stringCopyInto
+
+    pop rsi
+    pop rdi
+    jmp _stringCopyInto
+
+;; Appends the rdx bytes at rsi to the String in rdi. A full String is moved to a block twice the
+;; size it needs, so the String returned in rax might not be the one passed in. rcx has the
+;; temporaries
+_stringAppend:
+    push rsi
+    push rsi
+    push rcx
+
+    call _stringAppendBytes
+
+    pop rdx
+    pop rsi
+    pop rdi
+    jmp _stringDropTemporaries
+
+_stringAppendBytes:
+    mov rax, rdi
+
+    mov rcx, [rax]
+    add rcx, rdx
+    cmp rcx, [rax + 8]
+    jbe _stringAppendCopy
+
+    push rdi
+    push rsi
+    push rdx
+
+    lea rsi, [rcx * 2 + STRING_HEADER_SIZE]
+    call _heapRealloc
+
+    test rax, rax
+    jz _stringOutOfMemory
+
+    mov rdx, [rax - 16]
+    sub rdx, STRING_HEADER_SIZE
+    mov [rax + 8], rdx
+
+    pop rdx
+    pop rsi
+    pop rdi
+
+    ;; s += s, the bytes moved along with the String
+    mov r8, rsi
+    sub r8, rdi
+    mov r9, [rax]
+    add r9, STRING_HEADER_SIZE
+    cmp r8, r9
+    jae _stringAppendCopy
+
+    lea rsi, [rax + r8]
+
+_stringAppendCopy:
+    mov rdi, rsi
+    mov rsi, rdx
+    jmp _stringCopyInto
+
+;; Compares the rsi bytes at rdi with the rcx bytes at rdx. Returns -1, 0 or 1 in rax if the first
+;; one is less than, equal to or greater than the second one. r8 has the temporaries
+_stringCompare:
+    push rdi
+    push rdx
+    push r8
+
+    call _stringCompareBytes
+
+    pop rdx
+    pop rsi
+    pop rdi
+    jmp _stringDropTemporaries
+
+_stringCompareBytes:
+    ;; r8 = length of the shorter one
+    mov r8, rsi
+    cmp rcx, r8
+    cmovb r8, rcx
+
+    xor r9, r9
+
+_stringCompareLoop:
+    cmp r9, r8
+    je _stringCompareLengths
+
+    movzx eax, BYTE [rdi + r9]
+    movzx r10d, BYTE [rdx + r9]
+    cmp eax, r10d
+    jb _stringCompareLess
+    ja _stringCompareGreater
+
+    inc r9
+    jmp _stringCompareLoop
+
+    ;; one is a prefix of the other
+_stringCompareLengths:
+    cmp rsi, rcx
+    jb _stringCompareLess
+    ja _stringCompareGreater
+
+    xor rax, rax
+    ret
+
+_stringCompareLess:
+    mov rax, -1
+    ret
+
+_stringCompareGreater:
+    mov rax, 1
+    ret
+
+;; Makes a String out of the bytes from rdx up to, but not including, rcx of the rsi bytes at rdi.
+;; Exits if the range is out of bounds. r8 has the temporaries
+_stringSlice:
+    push rdi
+    push rdi
+    push r8
+
+    call _stringSliceBytes
+
+    pop rdx
+    pop rsi
+    pop rdi
+    jmp _stringDropTemporaries
+
+_stringSliceBytes:
+    cmp rdx, 0
+    jl _stringSliceOutOfRange
+
+    cmp rdx, rcx
+    jg _stringSliceOutOfRange
+
+    cmp rcx, rsi
+    jg _stringSliceOutOfRange
+
+    add rdi, rdx
+    mov rsi, rcx
+    sub rsi, rdx
+    jmp _stringFromBytes
+
+_stringSliceOutOfRange:
+    mov rax, 1
+    mov rdi, 2
+    mov rsi, stringSliceMessage
+    mov rdx, stringSliceMessageLen
+    syscall
+
+    exit 1
+
+_stringOutOfMemory:
+    mov rax, 1
+    mov rdi, 2
+    mov rsi, stringOutOfMemoryMessage
+    mov rdx, stringOutOfMemoryMessageLen
+    syscall
+
+    exit 1
+
+;; Formats rdi as a signed number
+_stringFromInt:
+    sub rsp, 32
+    lea rsi, [rsp + 32]
+    call _formatSignedDigits
+
+    mov rdi, rsi
+    lea rsi, [rsp + 32]
+    sub rsi, rdi
+    call _stringFromBytes
+
+    add rsp, 32
+    ret
+
+;; Formats xmm0 with [float_precision] digits after the decimal point, the same way _printFloat
+;; prints it
+_stringFromFloat:
+    sub rsp, 64
+    lea rsi, [rsp + 64]
+    call _formatFloatDigits
+
+    mov rdi, rsi
+    lea rsi, [rsp + 64]
+    sub rsi, rdi
+    call _stringFromBytes
+
+    add rsp, 64
+    ret
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use crate::lexer::lexer::Token;
+
+use super::asm::ASM;
+
+/// Source lines written as comments above the instructions they were compiled from, only with
+/// `--emit=asm`
+#[derive(Debug, Default)]
+pub struct Annotations {
+    /// The statements being compiled, the innermost one last. Instructions belong to the last one
+    statements: Vec<Token>,
+    /// Lines of every source file, read the first time one of its statements is compiled
+    files: HashMap<String, Vec<String>>,
+    /// (asm label, file, line) of the last comment written
+    last: Option<(String, String, usize)>,
+}
+
+impl Annotations {
+    fn source_text(&mut self, token: &Token) -> Option<&str> {
+        let lines = self.files.entry(token.file.clone()).or_insert_with(|| {
+            std::fs::read_to_string(&token.file)
+                .map(|file| file.lines().map(String::from).collect())
+                .unwrap_or_default()
+        });
+
+        lines.get(token.line_number.checked_sub(1)?).map(|line| line.trim())
+    }
+
+    /// The comment for the statement being compiled, if it's not the one the last comment in
+    /// `asm_label` was written for
+    fn comment(&mut self, asm_label: &str) -> Option<String> {
+        let token = self.statements.last()?.clone();
+
+        let current = (asm_label.to_string(), token.file.clone(), token.line_number);
+
+        if self.last.as_ref() == Some(&current) {
+            return None;
+        }
+
+        self.last = Some(current);
+
+        Some(match self.source_text(&token) {
+            Some(text) => format!(";; {}:{}: {text}", token.file, token.line_number),
+            None => format!(";; {}:{}", token.file, token.line_number),
+        })
+    }
+}
+
+impl ASM {
+    /// Instructions generated until `end_statement` is called come from the statement at `token`.
+    /// Statements nest, once the inner one ends the instructions belong to the outer one again
+    pub fn start_statement(&mut self, token: &Token) {
+        if let Some(annotations) = &mut self.annotations {
+            annotations.statements.push(token.clone());
+        }
+    }
+
+    pub fn end_statement(&mut self) {
+        if let Some(annotations) = &mut self.annotations {
+            annotations.statements.pop();
+        }
+    }
+
+    /// Called before adding code to the current label
+    pub(super) fn annotate(&mut self) -> Option<String> {
+        self.annotations.as_ref()?;
+
+        let current_label = self.current_label();
+
+        self.annotations.as_mut()?.comment(&current_label)
+    }
+}
@@ -1,7 +1,11 @@
 use core::panic;
+use std::collections::HashMap;
+
+use super::{annotate::Annotations, debug_info::DebugInfo};
 
 use crate::{
     lexer::registers::{Register, ALL_FP_REGISTERS, ALL_REGISTERS},
+    options::CompilerOptions,
     trace,
 };
 
@@ -16,12 +20,27 @@ pub struct ASM {
     pub include: Vec<&'static str>,
     pub text: Vec<String>,
     pub data: Vec<String>,
+    pub rodata: Vec<String>,
     pub bss: Vec<String>,
     pub labels: Vec<Label>,
     pub comparison_num: usize,
     pub num_strings: usize,
     pub num_floats: usize,
     pub num_ifs: usize,
+    pub num_matches: usize,
+    pub num_asserts: usize,
+    pub checked_casts: bool,
+    pub debug_alloc: bool,
+
+    /// Values of the `const` globals known at compile time, so other globals can be computed from
+    /// them
+    pub constant_globals: HashMap<String, i64>,
+
+    /// Source lines, functions and variables for gdb, only with `-g`
+    pub debug_info: Option<DebugInfo>,
+
+    /// Source lines written above the instructions they compile to, only with `--emit=asm`
+    pub annotations: Option<Annotations>,
 
     stack: Vec<String>,
     function_argument_number: Option<usize>,
@@ -46,8 +65,19 @@ impl Default for ASM {
             comparison_num: 0,
             current_label: "_start".to_string(),
             num_ifs: 0,
-
-            include: vec![r#"%include "std.asm""#],
+            num_matches: 0,
+            num_asserts: 0,
+            checked_casts: false,
+            debug_alloc: false,
+
+            include: vec![
+                r#"%include "std.asm""#,
+                r#"%include "io.asm""#,
+                r#"%include "alloc.asm""#,
+                r#"%include "string.asm""#,
+                r#"%include "format.asm""#,
+                r#"%include "process.asm""#,
+            ],
 
             text: vec![String::from("global _start")],
 
@@ -56,11 +86,21 @@ impl Default for ASM {
                 String::from("float_imm dq 0"),
             ],
 
+            rodata: vec![],
+
+            constant_globals: HashMap::new(),
+
+            debug_info: None,
+
+            annotations: None,
+
             bss: vec![
                 // for printing numbers
                 String::from("digitSpace resb 100"),
                 String::from("digitSpacePos resb 8"),
                 String::from("argc resb 8"),
+                // what main(args) returned, see call_main_with_args
+                String::from("exitCode resq 1"),
             ],
 
             labels: vec![Label {
@@ -84,6 +124,26 @@ impl Default for ASM {
 }
 
 impl ASM {
+    pub fn new(options: &CompilerOptions) -> Self {
+        let mut asm = Self::default();
+
+        asm.data.push(format!("float_precision dq {}", options.float_precision));
+        asm.checked_casts = options.checked_casts;
+
+        asm.data.push(format!("heapDebug db {}", options.debug_alloc as u8));
+        asm.debug_alloc = options.debug_alloc;
+
+        if options.debug_info {
+            asm.debug_info = Some(DebugInfo::default());
+        }
+
+        if options.emit_asm {
+            asm.annotations = Some(Annotations::default());
+        }
+
+        asm
+    }
+
     pub fn change_current_label(&mut self, new_label: String) {
         self.current_label = new_label.clone();
 
@@ -100,8 +160,11 @@ impl ASM {
     }
 
     pub fn extend_current_label(&mut self, vec: Vec<String>) {
+        let annotation = self.annotate();
+
         for label in &mut self.labels {
             if label.name == self.current_label {
+                label.code.extend(annotation);
                 label.code.extend(vec);
                 label.code.push(format!(""));
                 break;
@@ -110,8 +173,11 @@ impl ASM {
     }
 
     pub fn add_to_current_label(&mut self, line: String) {
+        let annotation = self.annotate();
+
         for label in &mut self.labels {
             if label.name == self.current_label {
+                label.code.extend(annotation);
                 label.code.push(line);
                 label.code.push(format!(""));
                 break;
@@ -15,7 +15,9 @@ impl ASM {
 
         let mut instructions = match op {
             Operations::Plus => {
-                if matches!(result_type, VarType::Float) {
+                if result_type.is_float() {
+                    let suffix = result_type.get_float_suffix();
+
                     let first = self.stack_pop().unwrap();
                     let second = self.stack_pop().unwrap();
 
@@ -24,9 +26,9 @@ impl ASM {
 
                     let mut inst = vec![
                         format!(";; Plus get the two operands from the stack"),
-                        format!("movsd {xmm0}, {}", first),
-                        format!("movsd {xmm1}, {}", second),
-                        format!("addsd {xmm0}, {xmm1}"),
+                        format!("mov{suffix} {xmm0}, {}", first),
+                        format!("mov{suffix} {xmm1}, {}", second),
+                        format!("add{suffix} {xmm0}, {xmm1}"),
                     ];
 
                     self.unlock_register_from_stack_value(&first);
@@ -67,7 +69,9 @@ impl ASM {
             }
 
             Operations::Minus => {
-                if matches!(result_type, VarType::Float) {
+                if result_type.is_float() {
+                    let suffix = result_type.get_float_suffix();
+
                     let first = self.stack_pop().unwrap();
                     let second = self.stack_pop().unwrap();
 
@@ -76,9 +80,9 @@ impl ASM {
 
                     let mut inst = vec![
                         format!(";; Plus get the two operands from the stack"),
-                        format!("movsd {xmm0}, {}", second),
-                        format!("movsd {xmm1}, {}", first),
-                        format!("subsd {xmm0}, {xmm1}"),
+                        format!("mov{suffix} {xmm0}, {}", second),
+                        format!("mov{suffix} {xmm1}, {}", first),
+                        format!("sub{suffix} {xmm0}, {xmm1}"),
                     ];
 
                     self.unlock_register_from_stack_value(&first);
@@ -115,7 +119,9 @@ impl ASM {
             }
 
             Operations::Divide => {
-                if matches!(result_type, VarType::Float) {
+                if result_type.is_float() {
+                    let suffix = result_type.get_float_suffix();
+
                     let first = self.stack_pop().unwrap();
                     let second = self.stack_pop().unwrap();
 
@@ -124,9 +130,9 @@ impl ASM {
 
                     let mut inst = vec![
                         format!(";; Plus get the two operands from the stack"),
-                        format!("movsd {xmm0}, {}", second),
-                        format!("movsd {xmm1}, {}", first),
-                        format!("divsd {xmm0}, {xmm1}"),
+                        format!("mov{suffix} {xmm0}, {}", second),
+                        format!("mov{suffix} {xmm1}, {}", first),
+                        format!("div{suffix} {xmm0}, {xmm1}"),
                     ];
 
                     self.unlock_register_from_stack_value(&first);
@@ -194,7 +200,9 @@ impl ASM {
             }
 
             Operations::Multiply => {
-                if matches!(result_type, VarType::Float) {
+                if result_type.is_float() {
+                    let suffix = result_type.get_float_suffix();
+
                     let first = self.stack_pop().unwrap();
                     let second = self.stack_pop().unwrap();
 
@@ -203,9 +211,9 @@ impl ASM {
 
                     let mut inst = vec![
                         format!(";; Plus get the two operands from the stack"),
-                        format!("movsd {xmm0}, {}", first),
-                        format!("movsd {xmm1}, {}", second),
-                        format!("mulsd {xmm0}, {xmm1}"),
+                        format!("mov{suffix} {xmm0}, {}", first),
+                        format!("mov{suffix} {xmm1}, {}", second),
+                        format!("mul{suffix} {xmm0}, {xmm1}"),
                     ];
 
                     self.unlock_register_from_stack_value(&first);
@@ -444,9 +452,9 @@ impl ASM {
                 self.stack_push(String::from(reg_to_lock));
             }
 
-            VarType::Str => todo!(),
+            VarType::Str | VarType::OwnedStr => todo!(),
 
-            VarType::Float => {
+            VarType::Float | VarType::Float32 => {
                 self.stack_push(String::from(reg_to_lock));
             }
 
@@ -503,18 +511,25 @@ impl ASM {
                     }
                 }
 
-                VarType::Float => todo!(),
+                VarType::Float | VarType::Float32 => todo!(),
+                VarType::OwnedStr => todo!(),
                 VarType::Ptr(_) => todo!(),
                 VarType::Array(_, _) => todo!(),
+                VarType::Slice(_) => todo!(),
                 VarType::Struct(_, _) => todo!(),
                 VarType::Unknown => todo!(),
                 VarType::Function(_, _, _) => todo!(),
+                VarType::Enum(..) => todo!(),
+                VarType::Union(..) => todo!(),
             },
 
             VarType::Array(_, _) => todo!(),
+            VarType::Slice(_) => todo!(),
             VarType::Struct(_, _) => todo!(),
             VarType::Unknown => todo!(),
             VarType::Function(_, _, _) => todo!(),
+            VarType::Enum(..) => todo!(),
+            VarType::Union(..) => todo!(),
         };
 
         self.extend_current_label(instructions);
@@ -1,6 +1,6 @@
 use crate::{lexer::{registers::get_register_name_for_bits, tokens::Comparators, types::VarType}, trace};
 
-use super::asm::ASM;
+use super::{asm::ASM, type_cast::load_float};
 
 impl ASM {
     /// Only needed for comsd
@@ -45,28 +45,71 @@ impl ASM {
         ]
     }
 
-    pub fn compare_floats(&self) -> Vec<String> {
-        vec![
+    /// ucomisd sets the parity flag if either operand is NaN, i.e. they are unordered. Every
+    /// comparison with a NaN is false, except for !=
+    pub fn compare_floats(&mut self, op: &Comparators, float_type: &VarType) {
+        let first = self.stack_pop().unwrap();
+        let second = self.stack_pop().unwrap();
+
+        let suffix = float_type.get_float_suffix();
+
+        let xmm0 = self.get_free_float_register(None);
+        let xmm1 = self.get_free_float_register(None);
+
+        // a < b is checked as b > a, as 'above' is false for unordered operands while 'below' is
+        // true
+        let (lhs, rhs) = match op {
+            Comparators::LessThan | Comparators::LessThanEq => (xmm1, xmm0),
+            _ => (xmm0, xmm1),
+        };
+
+        let mut instructions = vec![
             format!(";; Floating point comparison"),
-            format!(";; Get the first operand"),
-            format!("pop QWORD [float_imm]"),
-            format!("movsd xmm1, [float_imm]"),
-            format!(";; Get the second operand"),
-            format!("pop QWORD [float_imm]"),
-            format!("movsd xmm0, [float_imm]"),
-            format!(";; floating point comparison"),
-            format!("ucomisd xmm0, xmm1",),
-        ]
+            load_float(&xmm0, &second, float_type),
+            load_float(&xmm1, &first, float_type),
+            format!("ucomi{suffix} {lhs}, {rhs}"),
+        ];
+
+        self.unlock_register_from_stack_value(&first);
+        self.unlock_register_from_stack_value(&second);
+
+        self.unlock_register(xmm0);
+        self.unlock_register(xmm1);
+
+        let rax = self.get_free_register(None);
+        let rbx = self.get_free_register(None);
+
+        let al = get_register_name_for_bits(&rax, 8);
+        let bl = get_register_name_for_bits(&rbx, 8);
+
+        instructions.extend(match op {
+            Comparators::GreaterThan | Comparators::LessThan => vec![format!("seta {al}")],
+            Comparators::GreaterThanEq | Comparators::LessThanEq => vec![format!("setae {al}")],
+
+            Comparators::DoubleEquals => vec![format!("sete {al}"), format!("setnp {bl}"), format!("and {al}, {bl}")],
+            Comparators::NotEquals => vec![format!("setne {al}"), format!("setp {bl}"), format!("or {al}, {bl}")],
+        });
+
+        instructions.push(format!("movzx {rax}, {al}"));
+
+        self.unlock_register(rbx);
+
+        self.extend_current_label(instructions);
+        self.stack_push(String::from(rax));
+
+        self.comparison_num += 1;
     }
 
     pub fn compare_two_numbers(&mut self, op: Comparators, result_type: &VarType) {
         let mut instructions = match result_type {
-            VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Char => self.compare_ints(),
-            VarType::Float => self.compare_floats(),
+            VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Char | VarType::Enum(..) => {
+                self.compare_ints()
+            }
+            VarType::Float | VarType::Float32 => return self.compare_floats(&op, result_type),
 
             VarType::Ptr(inner_type) => match **inner_type {
                 VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Char => self.compare_ints(),
-                VarType::Float => self.compare_floats(),
+                VarType::Float | VarType::Float32 => return self.compare_floats(&op, inner_type),
 
                 _ => {
                     unreachable!("Found type {result_type} while generating ASM for comparison op for a pointer type. This must be a bug in the semantic analysis step")
@@ -0,0 +1,633 @@
+use crate::{
+    ast::abstract_syntax_tree::AST,
+    lexer::{lexer::Token, types::VarType},
+    semantic_analyzer::semantic_analyzer::ARVariable,
+};
+
+use super::asm::ASM;
+
+// DWARF 4 constants, see https://dwarfstd.org/doc/DWARF4.pdf
+const DW_TAG_COMPILE_UNIT: u8 = 0x11;
+const DW_TAG_SUBPROGRAM: u8 = 0x2e;
+const DW_TAG_VARIABLE: u8 = 0x34;
+const DW_TAG_FORMAL_PARAMETER: u8 = 0x05;
+const DW_TAG_BASE_TYPE: u8 = 0x24;
+const DW_TAG_POINTER_TYPE: u8 = 0x0f;
+const DW_TAG_STRUCTURE_TYPE: u8 = 0x13;
+const DW_TAG_MEMBER: u8 = 0x0d;
+
+const DW_AT_LOCATION: u8 = 0x02;
+const DW_AT_NAME: u8 = 0x03;
+const DW_AT_BYTE_SIZE: u8 = 0x0b;
+const DW_AT_STMT_LIST: u8 = 0x10;
+const DW_AT_LOW_PC: u8 = 0x11;
+const DW_AT_HIGH_PC: u8 = 0x12;
+const DW_AT_LANGUAGE: u8 = 0x13;
+const DW_AT_COMP_DIR: u8 = 0x1b;
+const DW_AT_PRODUCER: u8 = 0x25;
+const DW_AT_DATA_MEMBER_LOCATION: u8 = 0x38;
+const DW_AT_DECL_FILE: u8 = 0x3a;
+const DW_AT_DECL_LINE: u8 = 0x3b;
+const DW_AT_ENCODING: u8 = 0x3e;
+const DW_AT_FRAME_BASE: u8 = 0x40;
+const DW_AT_TYPE: u8 = 0x49;
+
+const DW_FORM_ADDR: u8 = 0x01;
+const DW_FORM_DATA1: u8 = 0x0b;
+const DW_FORM_DATA2: u8 = 0x05;
+const DW_FORM_DATA4: u8 = 0x06;
+const DW_FORM_STRING: u8 = 0x08;
+const DW_FORM_REF4: u8 = 0x13;
+const DW_FORM_SEC_OFFSET: u8 = 0x17;
+const DW_FORM_EXPRLOC: u8 = 0x18;
+
+const DW_ATE_FLOAT: u8 = 0x04;
+const DW_ATE_SIGNED: u8 = 0x05;
+const DW_ATE_UNSIGNED_CHAR: u8 = 0x08;
+
+const DW_OP_REG6: u8 = 0x56;
+const DW_OP_FBREG: u8 = 0x91;
+
+const DW_LNS_COPY: u8 = 0x01;
+const DW_LNS_ADVANCE_LINE: u8 = 0x03;
+const DW_LNS_SET_FILE: u8 = 0x04;
+const DW_LNE_END_SEQUENCE: u8 = 0x01;
+const DW_LNE_SET_ADDRESS: u8 = 0x02;
+
+/// There's no language code for Cygnus, and gdb evaluates expressions the way C does anyway
+const DW_LANG_C99: u16 = 0x0c;
+
+/// Abbreviation codes, the abbreviation table below declares what attributes each one has
+const ABBREV_COMPILE_UNIT: u8 = 1;
+const ABBREV_SUBPROGRAM: u8 = 2;
+const ABBREV_VARIABLE: u8 = 3;
+const ABBREV_PARAMETER: u8 = 4;
+const ABBREV_BASE_TYPE: u8 = 5;
+const ABBREV_POINTER: u8 = 6;
+const ABBREV_VOID_POINTER: u8 = 7;
+const ABBREV_STRUCT: u8 = 8;
+const ABBREV_MEMBER: u8 = 9;
+
+/// A statement starts at `label`
+#[derive(Debug)]
+struct LineRow {
+    label: String,
+    file: usize,
+    line: usize,
+}
+
+/// The rows of one label of the generated assembly, a function or `_start`. Labels aren't
+/// written in the order their code is generated, so each one is a sequence of its own
+#[derive(Debug)]
+struct Sequence {
+    asm_label: String,
+    rows: Vec<LineRow>,
+}
+
+#[derive(Debug)]
+struct DebugVariable {
+    name: String,
+    var_type: VarType,
+    /// The variable is at `rbp - offset`
+    offset: usize,
+    file: usize,
+    line: usize,
+    is_parameter: bool,
+}
+
+#[derive(Debug)]
+struct DebugFunction {
+    name: String,
+    asm_label: String,
+    file: usize,
+    line: usize,
+    variables: Vec<DebugVariable>,
+}
+
+/// A type the variables refer to, written once in .debug_info
+#[derive(Debug)]
+struct DebugType {
+    name: String,
+    lines: Vec<String>,
+}
+
+/// Source level debug info, written as DWARF sections after the code when compiling with `-g`.
+/// Every statement gets a label so the line table can say where it starts, and every function
+/// lists its parameters and locals with their offsets from rbp. That's enough for gdb to break
+/// on `file.cy:42`, step through the source and print locals by name.
+///
+/// All the variables of a function are listed with it, the blocks they're declared in aren't
+#[derive(Debug, Default)]
+pub struct DebugInfo {
+    /// Absolute paths, the file number in DWARF is the index + 1
+    files: Vec<String>,
+    sequences: Vec<Sequence>,
+    functions: Vec<DebugFunction>,
+    num_rows: usize,
+    types: Vec<DebugType>,
+}
+
+impl ASM {
+    /// Marks the start of a statement at `token`
+    pub fn debug_line(&mut self, token: &Token) {
+        let current_label = self.current_label();
+
+        let Some(debug_info) = &mut self.debug_info else {
+            return;
+        };
+
+        if let Some(label) = debug_info.add_row(&current_label, token) {
+            self.add_to_current_label(format!("{label}:"));
+        }
+    }
+
+    /// Records a function with its parameters and locals. Has to be called while the function's
+    /// record is still on the call stack, as that's where the variables get their offsets
+    pub fn debug_function(&mut self, name: &str, token: &Token, variables: &[ARVariable]) {
+        let Some(debug_info) = &mut self.debug_info else {
+            return;
+        };
+
+        let variables = variables
+            .iter()
+            .map(|variable| {
+                let variable = variable.borrow();
+                let token = variable.get_token();
+
+                DebugVariable {
+                    name: variable.var_name.clone(),
+                    var_type: variable.var_type.clone(),
+                    offset: variable.offset,
+                    file: debug_info.file_number(&token.file),
+                    line: token.line_number,
+                    is_parameter: variable.is_parameter,
+                }
+            })
+            .collect();
+
+        let function = DebugFunction {
+            name: name.into(),
+            asm_label: format!("_{name}"),
+            file: debug_info.file_number(&token.file),
+            line: token.line_number,
+            variables,
+        };
+
+        debug_info.functions.push(function);
+    }
+}
+
+impl DebugInfo {
+    /// Where the code of `asm_label` ends
+    pub fn end_label(asm_label: &str) -> String {
+        format!("..@{asm_label}_end")
+    }
+
+    fn file_number(&mut self, file: &str) -> usize {
+        let path = std::fs::canonicalize(file)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|_| file.into());
+
+        match self.files.iter().position(|f| *f == path) {
+            Some(index) => index + 1,
+
+            None => {
+                self.files.push(path);
+                self.files.len()
+            }
+        }
+    }
+
+    /// Returns the label to put before the statement, or None if the last statement of the label
+    /// is on the same line
+    fn add_row(&mut self, asm_label: &str, token: &Token) -> Option<String> {
+        let file = self.file_number(&token.file);
+        let line = token.line_number;
+
+        let sequence = match self.sequences.iter().position(|s| s.asm_label == asm_label) {
+            Some(index) => &mut self.sequences[index],
+
+            None => {
+                self.sequences.push(Sequence {
+                    asm_label: asm_label.into(),
+                    rows: vec![],
+                });
+
+                self.sequences.last_mut().unwrap()
+            }
+        };
+
+        if let Some(last) = sequence.rows.last() {
+            if last.file == file && last.line == line {
+                return None;
+            }
+        }
+
+        let label = format!("..@line_{}", self.num_rows);
+        self.num_rows += 1;
+
+        sequence.rows.push(LineRow {
+            label: label.clone(),
+            file,
+            line,
+        });
+
+        Some(label)
+    }
+
+    /// .debug_abbrev, .debug_info and .debug_line. The code has to be between `text_start` and
+    /// `text_end`, and every label with statements has to be followed by its `end_label`
+    pub fn sections(&mut self, text_start: &str, text_end: &str) -> Vec<String> {
+        let mut lines = vec![];
+
+        lines.extend(section(".debug_abbrev"));
+        lines.extend(abbreviations());
+
+        lines.extend(section(".debug_info"));
+        lines.extend(self.info(text_start, text_end));
+
+        lines.extend(section(".debug_line"));
+        lines.extend(self.line_program());
+
+        lines
+    }
+
+    fn info(&mut self, text_start: &str, text_end: &str) -> Vec<String> {
+        // named after the file main is in
+        let main_file = self
+            .functions
+            .iter()
+            .find(|f| f.name == "main")
+            .map(|f| f.file)
+            .unwrap_or(1);
+
+        let source = self.files.get(main_file - 1).cloned().unwrap_or_default();
+
+        let comp_dir = std::env::current_dir()
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let mut lines = vec![
+            format!("..@debug_info:"),
+            format!("dd ..@debug_info_end - ..@debug_info_version"),
+            format!("..@debug_info_version:"),
+            format!("dw 4"),
+            format!("dd ..@debug_abbrev"),
+            format!(";; address size"),
+            format!("db 8"),
+            format!(";; compile unit"),
+            format!("db {ABBREV_COMPILE_UNIT}"),
+            string("Cygnus compiler"),
+            format!("dw {DW_LANG_C99}"),
+            string(&source),
+            string(&comp_dir),
+            format!("dq {text_start}"),
+            format!("dq {text_end}"),
+            format!("dd ..@debug_line"),
+        ];
+
+        let functions = std::mem::take(&mut self.functions);
+
+        for function in &functions {
+            lines.extend([
+                format!(";; function {}", function.name),
+                format!("db {ABBREV_SUBPROGRAM}"),
+                string(&function.name),
+                format!("db {}", function.file),
+                format!("dd {}", function.line),
+                format!("dq {}", function.asm_label),
+                format!("dq {}", DebugInfo::end_label(&function.asm_label)),
+                format!("db 1, {DW_OP_REG6}"),
+            ]);
+
+            for variable in &function.variables {
+                let Some(type_label) = self.type_label(&variable.var_type) else {
+                    continue;
+                };
+
+                let mut location = vec![DW_OP_FBREG];
+                location.extend(sleb128(-(variable.offset as i64)));
+
+                lines.extend([
+                    format!(
+                        "db {}",
+                        if variable.is_parameter {
+                            ABBREV_PARAMETER
+                        } else {
+                            ABBREV_VARIABLE
+                        }
+                    ),
+                    string(&variable.name),
+                    format!("db {}", variable.file),
+                    format!("dd {}", variable.line),
+                    format!("dd {type_label} - ..@debug_info"),
+                    format!("db {}, {}", location.len(), join(&location)),
+                ]);
+            }
+
+            lines.push(format!("db 0 ;; end of {}", function.name));
+        }
+
+        self.functions = functions;
+
+        for debug_type in &self.types {
+            lines.extend(debug_type.lines.iter().cloned());
+        }
+
+        lines.extend([String::from("db 0 ;; end of the compile unit"), String::from("..@debug_info_end:")]);
+
+        lines
+    }
+
+    /// The label of the DIE describing `var_type`. Returns None for types gdb isn't told about,
+    /// variables of those types are left out
+    fn type_label(&mut self, var_type: &VarType) -> Option<String> {
+        let base_type = |name: &str, encoding: u8, size: usize| {
+            vec![
+                format!("db {ABBREV_BASE_TYPE}"),
+                string(name),
+                format!("db {encoding}"),
+                format!("db {size}"),
+            ]
+        };
+
+        let void_pointer = vec![format!("db {ABBREV_VOID_POINTER}"), format!("db 8")];
+
+        // the types a type refers to are added before it
+        let (name, lines) = match var_type {
+            VarType::Int => ("int".into(), base_type("int", DW_ATE_SIGNED, 8)),
+            VarType::Int32 => ("int32".into(), base_type("int32", DW_ATE_SIGNED, 4)),
+            VarType::Int16 => ("int16".into(), base_type("int16", DW_ATE_SIGNED, 2)),
+            VarType::Int8 => ("int8".into(), base_type("int8", DW_ATE_SIGNED, 1)),
+            VarType::Char => ("char".into(), base_type("char", DW_ATE_UNSIGNED_CHAR, 1)),
+            VarType::Float => ("float".into(), base_type("float", DW_ATE_FLOAT, 8)),
+            VarType::Float32 => ("float32".into(), base_type("float32", DW_ATE_FLOAT, 4)),
+
+            VarType::Ptr(inner) => match self.type_label(inner) {
+                Some(inner) => (
+                    format!("*{inner}"),
+                    vec![
+                        format!("db {ABBREV_POINTER}"),
+                        format!("db 8"),
+                        format!("dd {inner} - ..@debug_info"),
+                    ],
+                ),
+
+                None => ("*".into(), void_pointer),
+            },
+
+            // the pointer to the heap block
+            VarType::OwnedStr => ("*".into(), void_pointer),
+
+            // the address of the first character followed by the length
+            VarType::Str => {
+                let data = self.type_label(&VarType::Ptr(Box::new(VarType::Char)))?;
+                let len = self.type_label(&VarType::Int)?;
+
+                (
+                    "str".into(),
+                    vec![
+                        format!("db {ABBREV_STRUCT}"),
+                        string("str"),
+                        format!("db 16"),
+                        format!("db {ABBREV_MEMBER}"),
+                        string("data"),
+                        format!("dd {data} - ..@debug_info"),
+                        format!("db 0"),
+                        format!("db {ABBREV_MEMBER}"),
+                        string("len"),
+                        format!("dd {len} - ..@debug_info"),
+                        format!("db 8"),
+                        format!("db 0 ;; end of str"),
+                    ],
+                )
+            }
+
+            _ => return None,
+        };
+
+        if let Some(index) = self.types.iter().position(|t| t.name == name) {
+            return Some(format!("..@type_{index}"));
+        }
+
+        let label = format!("..@type_{}", self.types.len());
+
+        let mut type_lines = vec![format!("{label}:")];
+        type_lines.extend(lines);
+
+        self.types.push(DebugType { name, lines: type_lines });
+
+        Some(label)
+    }
+
+    fn line_program(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("..@debug_line:"),
+            format!("dd ..@debug_line_end - ..@debug_line_version"),
+            format!("..@debug_line_version:"),
+            format!("dw 4"),
+            format!("dd ..@debug_line_program - ..@debug_line_header"),
+            format!("..@debug_line_header:"),
+            format!(";; minimum instruction length, maximum operations per instruction, default is_stmt"),
+            format!("db 1, 1, 1"),
+            format!(";; line base, line range, opcode base"),
+            format!("db -5, 14, 13"),
+            format!(";; number of operands of the standard opcodes"),
+            format!("db 0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1"),
+            format!(";; no include directories"),
+            format!("db 0"),
+        ];
+
+        for file in &self.files {
+            lines.push(string(file));
+            // directory, modification time and length
+            lines.push(String::from("db 0, 0, 0"));
+        }
+
+        lines.extend([String::from("db 0"), String::from("..@debug_line_program:")]);
+
+        for sequence in &self.sequences {
+            let mut file = 1;
+            let mut line: i64 = 1;
+
+            lines.push(format!(";; {}", sequence.asm_label));
+
+            for row in &sequence.rows {
+                lines.extend(set_address(&row.label));
+
+                if row.file != file {
+                    lines.push(format!("db {DW_LNS_SET_FILE}, {}", join(&uleb128(row.file as u64))));
+                    file = row.file;
+                }
+
+                let advance = row.line as i64 - line;
+
+                if advance != 0 {
+                    lines.push(format!("db {DW_LNS_ADVANCE_LINE}, {}", join(&sleb128(advance))));
+                    line = row.line as i64;
+                }
+
+                lines.push(format!("db {DW_LNS_COPY}"));
+            }
+
+            lines.extend(set_address(&DebugInfo::end_label(&sequence.asm_label)));
+            lines.push(format!("db 0, 1, {DW_LNE_END_SEQUENCE}"));
+        }
+
+        lines.push(String::from("..@debug_line_end:"));
+
+        lines
+    }
+}
+
+fn section(name: &str) -> Vec<String> {
+    vec![format!(""), format!("section {name} noalloc noexec nowrite progbits align=1")]
+}
+
+fn string(s: &str) -> String {
+    format!("db \"{s}\", 0")
+}
+
+fn join(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| b.to_string()).collect::<Vec<String>>().join(", ")
+}
+
+fn set_address(label: &str) -> Vec<String> {
+    vec![format!("db 0, 9, {DW_LNE_SET_ADDRESS}"), format!("dq {label}")]
+}
+
+/// (abbreviation code, tag, has children, [(attribute, form)])
+type Abbreviation = (u8, u8, bool, Vec<(u8, u8)>);
+
+/// Which attributes each kind of entry in .debug_info has, and how they're encoded
+fn abbreviations() -> Vec<String> {
+    let entries: [Abbreviation; 9] = [
+        (
+            ABBREV_COMPILE_UNIT,
+            DW_TAG_COMPILE_UNIT,
+            true,
+            vec![
+                (DW_AT_PRODUCER, DW_FORM_STRING),
+                (DW_AT_LANGUAGE, DW_FORM_DATA2),
+                (DW_AT_NAME, DW_FORM_STRING),
+                (DW_AT_COMP_DIR, DW_FORM_STRING),
+                (DW_AT_LOW_PC, DW_FORM_ADDR),
+                (DW_AT_HIGH_PC, DW_FORM_ADDR),
+                (DW_AT_STMT_LIST, DW_FORM_SEC_OFFSET),
+            ],
+        ),
+        (
+            ABBREV_SUBPROGRAM,
+            DW_TAG_SUBPROGRAM,
+            true,
+            vec![
+                (DW_AT_NAME, DW_FORM_STRING),
+                (DW_AT_DECL_FILE, DW_FORM_DATA1),
+                (DW_AT_DECL_LINE, DW_FORM_DATA4),
+                (DW_AT_LOW_PC, DW_FORM_ADDR),
+                (DW_AT_HIGH_PC, DW_FORM_ADDR),
+                (DW_AT_FRAME_BASE, DW_FORM_EXPRLOC),
+            ],
+        ),
+        (ABBREV_VARIABLE, DW_TAG_VARIABLE, false, variable_attributes()),
+        (ABBREV_PARAMETER, DW_TAG_FORMAL_PARAMETER, false, variable_attributes()),
+        (
+            ABBREV_BASE_TYPE,
+            DW_TAG_BASE_TYPE,
+            false,
+            vec![
+                (DW_AT_NAME, DW_FORM_STRING),
+                (DW_AT_ENCODING, DW_FORM_DATA1),
+                (DW_AT_BYTE_SIZE, DW_FORM_DATA1),
+            ],
+        ),
+        (
+            ABBREV_POINTER,
+            DW_TAG_POINTER_TYPE,
+            false,
+            vec![(DW_AT_BYTE_SIZE, DW_FORM_DATA1), (DW_AT_TYPE, DW_FORM_REF4)],
+        ),
+        (
+            ABBREV_VOID_POINTER,
+            DW_TAG_POINTER_TYPE,
+            false,
+            vec![(DW_AT_BYTE_SIZE, DW_FORM_DATA1)],
+        ),
+        (
+            ABBREV_STRUCT,
+            DW_TAG_STRUCTURE_TYPE,
+            true,
+            vec![(DW_AT_NAME, DW_FORM_STRING), (DW_AT_BYTE_SIZE, DW_FORM_DATA1)],
+        ),
+        (
+            ABBREV_MEMBER,
+            DW_TAG_MEMBER,
+            false,
+            vec![
+                (DW_AT_NAME, DW_FORM_STRING),
+                (DW_AT_TYPE, DW_FORM_REF4),
+                (DW_AT_DATA_MEMBER_LOCATION, DW_FORM_DATA1),
+            ],
+        ),
+    ];
+
+    let mut lines = vec![format!("..@debug_abbrev:")];
+
+    for (code, tag, has_children, attributes) in entries {
+        lines.push(format!("db {code}, {tag}, {}", has_children as u8));
+
+        for (attribute, form) in attributes {
+            lines.push(format!("db {attribute}, {form}"));
+        }
+
+        lines.push(String::from("db 0, 0"));
+    }
+
+    lines.push(String::from("db 0"));
+
+    lines
+}
+
+fn variable_attributes() -> Vec<(u8, u8)> {
+    vec![
+        (DW_AT_NAME, DW_FORM_STRING),
+        (DW_AT_DECL_FILE, DW_FORM_DATA1),
+        (DW_AT_DECL_LINE, DW_FORM_DATA4),
+        (DW_AT_TYPE, DW_FORM_REF4),
+        (DW_AT_LOCATION, DW_FORM_EXPRLOC),
+    ]
+}
+
+fn uleb128(mut value: u64) -> Vec<u8> {
+    let mut bytes = vec![];
+
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+
+        bytes.push(byte | 0x80);
+    }
+}
+
+fn sleb128(mut value: i64) -> Vec<u8> {
+    let mut bytes = vec![];
+
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+
+        if done {
+            bytes.push(byte);
+            return bytes;
+        }
+
+        bytes.push(byte | 0x80);
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
     helpers::compiler_error,
     interpreter::interpreter::Variables,
-    lexer::tokens::{Number, TokenEnum, VariableEnum},
+    lexer::{
+        tokens::{Number, TokenEnum, VariableEnum},
+        types::VarType,
+    },
     semantic_analyzer::semantic_analyzer::{ActivationRecordType, CallStack},
     trace,
 };
@@ -10,7 +13,7 @@ use super::asm::ASM;
 
 impl ASM {
     /// Pushes whatever token's in here onto the stack
-    pub fn generate_asm_factor(&mut self, token: &TokenEnum, call_stack: &CallStack) {
+    pub fn generate_asm_factor(&mut self, token: &TokenEnum, result_type: &VarType, call_stack: &CallStack) {
         let mut instructions: Vec<String> = vec![];
 
         match token {
@@ -26,11 +29,27 @@ impl ASM {
                 Number::Float(f) => {
                     let xmm0 = self.get_free_float_register(None);
 
+                    // nasm needs a decimal point, otherwise it stores an integer. Debug formatting
+                    // keeps it for whole numbers (2.0 and not 2), but not in the exponent form (1e-7)
+                    let mut float = match result_type {
+                        VarType::Float32 => format!("{:?}", *f as f32),
+                        _ => format!("{f:?}"),
+                    };
+
+                    if !float.contains('.') {
+                        float = float.replacen('e', ".e", 1);
+                    }
+
+                    let define = match result_type {
+                        VarType::Float32 => "dd",
+                        _ => "dq",
+                    };
+
                     // add the floating point in the data segement
-                    self.data.push(format!("float_{} dq {f}", self.num_floats));
+                    self.data.push(format!("float_{} {define} {float}", self.num_floats));
 
                     instructions.extend(vec![
-                        format!("movsd {xmm0}, [float_{}]", self.num_floats),
+                        format!("mov{} {xmm0}, [float_{}]", result_type.get_float_suffix(), self.num_floats),
                         // rax contains the address of the float
                         // format!("mov rax, [rax]"),
                         // format!("push rax"),
@@ -43,54 +62,9 @@ impl ASM {
             },
 
             TokenEnum::StringLiteral(s) => {
-                let mut chars = vec![];
-
-                let mut char_iter = s.chars();
-
-                loop {
-                    match char_iter.next() {
-                        Some(c) => match c {
-                            '\\' => {
-                                match char_iter.next() {
-                                    Some(c) => match c {
-                                        'n' => chars.push(('\n' as u8).to_string()),
-                                        '0' => chars.push('0'.into()),
-                                        'r' => chars.push(('\r' as u8).to_string()),
-
-                                        _ => unimplemented!(),
-                                    },
-
-                                    // string literal ends with a backslash
-                                    None => {
-                                        panic!("String cannot end with a \\")
-                                    }
-                                }
-                            }
-
-                            _ => {
-                                chars.push((c as u8).to_string());
-                            }
-                        },
-
-                        None => break,
-                    }
-                }
-
-                // add the string literal in the data segement
-                self.data
-                    .push(format!("string_{} db {}", self.num_strings, chars.join(",")));
-
-                // TODO: Remove this
-                //
-                // instructions.extend(vec![
-                //     format!("mov rax, string_{}", self.num_strings),
-                //     format!("push rax"),
-                //     format!("push {}", chars.len()),
-                // ]);
-
-                self.stack_extend(vec![format!("string_{}", self.num_strings), format!("{}", chars.len())]);
+                let (label, len) = self.add_string_literal(s);
 
-                self.num_strings += 1;
+                self.stack_extend(vec![label, format!("{len}")]);
             }
 
             TokenEnum::Variable(..) => todo!(),
@@ -110,11 +84,59 @@ impl ASM {
             TokenEnum::Type(_) => todo!(),
             TokenEnum::Unknown(_) => todo!(),
             TokenEnum::FunctionReturnIndicator => todo!(),
-            TokenEnum::Comment => todo!(),
+            TokenEnum::Comment(_) => todo!(),
             TokenEnum::EOF => todo!(),
             TokenEnum::Dot => todo!(),
+            TokenEnum::DotDot => todo!(),
+            TokenEnum::Union(..) => unreachable!("Only the interpreter makes union values"),
         }
 
         self.extend_current_label(instructions);
     }
+
+    /// Adds the string literal to the data segment, with its escapes replaced. Returns its label
+    /// and its length in bytes
+    pub fn add_string_literal(&mut self, s: &str) -> (String, usize) {
+        let mut chars = vec![];
+
+        let mut char_iter = s.chars();
+
+        loop {
+            match char_iter.next() {
+                Some(c) => match c {
+                    '\\' => {
+                        match char_iter.next() {
+                            Some(c) => match c {
+                                'n' => chars.push(('\n' as u8).to_string()),
+                                '0' => chars.push('0'.into()),
+                                'r' => chars.push(('\r' as u8).to_string()),
+
+                                _ => unimplemented!(),
+                            },
+
+                            // string literal ends with a backslash
+                            None => {
+                                panic!("String cannot end with a \\")
+                            }
+                        }
+                    }
+
+                    _ => {
+                        chars.push((c as u8).to_string());
+                    }
+                },
+
+                None => break,
+            }
+        }
+
+        let label = format!("string_{}", self.num_strings);
+
+        // add the string literal in the data segement
+        self.data.push(format!("{label} db {}", chars.join(",")));
+
+        self.num_strings += 1;
+
+        (label, chars.len())
+    }
 }
@@ -0,0 +1,171 @@
+use crate::{
+    ast::format_string::FormatSegment, lexer::types::VarType, semantic_analyzer::semantic_analyzer::CallStack,
+};
+
+use super::{asm::ASM, string_functions::STRING_HEADER_SIZE};
+
+/// How many values `format_save_arg` pushes for an argument of this type
+fn num_saved_values(arg_type: &VarType) -> usize {
+    match arg_type {
+        VarType::Str => 2,
+        _ => 1,
+    }
+}
+
+impl ASM {
+    /// Moves the argument on top of the stack to the machine stack, so that formatting the output
+    /// can't be interrupted by a function call in a later argument. Ints are extended to 64 bits
+    /// and floats to doubles. `function_call_prep` has to be called before the first argument
+    pub fn format_save_arg(&mut self, arg_type: &VarType) {
+        match arg_type {
+            VarType::Str | VarType::OwnedStr => {}
+            VarType::Float | VarType::Float32 => self.type_cast(arg_type, &VarType::Float),
+            _ => self.type_cast(arg_type, &VarType::Int),
+        };
+
+        let mut values = vec![];
+
+        for _ in 0..num_saved_values(arg_type) {
+            values.insert(0, self.stack_pop().unwrap());
+        }
+
+        let mut instructions = vec![format!(";; Saving a print argument")];
+
+        for value in values {
+            if value.starts_with("xmm") {
+                instructions.extend(vec![format!("sub rsp, 8"), format!("movsd [rsp], {value}")]);
+            } else if value.starts_with('[') {
+                instructions.push(format!("push QWORD {value}"));
+            } else {
+                instructions.push(format!("push {value}"));
+            }
+
+            self.unlock_register_from_stack_value(&value);
+        }
+
+        self.extend_current_label(instructions);
+    }
+
+    /// Formats the saved arguments into one write to the file descriptor, which was saved before
+    /// them for fprint and is stdout for print. `args` has the type of every argument and whether
+    /// it's a temporary String that has to be freed
+    pub fn format_print(
+        &mut self,
+        segments: &[FormatSegment],
+        args: &[(VarType, bool)],
+        has_fd: bool,
+        call_stack: &CallStack,
+    ) {
+        let num_values: usize = args
+            .iter()
+            .map(|(arg_type, _)| num_saved_values(arg_type))
+            .sum::<usize>()
+            + has_fd as usize;
+
+        // the first value saved is the deepest one, the file descriptor goes below the arguments
+        let mut offsets = vec![];
+        let mut next_offset = (num_values - has_fd as usize) * 8;
+
+        for (arg_type, _) in args {
+            next_offset -= num_saved_values(arg_type) * 8;
+            offsets.push(next_offset);
+        }
+
+        let mut instructions = vec![format!(";; print")];
+
+        if has_fd {
+            instructions.push(format!("mov rdi, [rsp + {}]", (num_values - 1) * 8));
+        } else {
+            instructions.push(String::from("mov rdi, 1"));
+        }
+
+        instructions.push(String::from("call _formatStart"));
+
+        let mut placeholders = args.iter().zip(&offsets);
+
+        for segment in segments {
+            let spec = match segment {
+                FormatSegment::Literal(literal) => {
+                    let (label, len) = self.add_string_literal(literal);
+
+                    instructions.extend(vec![
+                        format!("mov rdi, {label}"),
+                        format!("mov rsi, {len}"),
+                        format!("call _formatBytes"),
+                    ]);
+
+                    continue;
+                }
+
+                FormatSegment::Placeholder(spec) => spec,
+            };
+
+            let ((arg_type, _), offset) = placeholders.next().unwrap();
+
+            let width = spec.width;
+            let flags = spec.flags();
+
+            match arg_type {
+                VarType::Str => instructions.extend(vec![
+                    format!("mov rdi, [rsp + {}]", offset + 8),
+                    format!("mov rsi, [rsp + {offset}]"),
+                    format!("mov rdx, {width}"),
+                    format!("mov rcx, {flags}"),
+                    format!("call _formatPadded"),
+                ]),
+
+                VarType::OwnedStr => instructions.extend(vec![
+                    format!("mov rdi, [rsp + {offset}]"),
+                    format!("mov rsi, [rdi]"),
+                    format!("add rdi, {STRING_HEADER_SIZE}"),
+                    format!("mov rdx, {width}"),
+                    format!("mov rcx, {flags}"),
+                    format!("call _formatPadded"),
+                ]),
+
+                VarType::Float | VarType::Float32 => instructions.extend(vec![
+                    format!("movsd xmm0, [rsp + {offset}]"),
+                    format!("mov rdi, {width}"),
+                    format!("mov rsi, {flags}"),
+                    format!("call _formatFloat"),
+                ]),
+
+                // a char in hex is its code
+                VarType::Char if spec.hex.is_none() => instructions.extend(vec![
+                    format!("mov rdi, [rsp + {offset}]"),
+                    format!("mov rsi, {width}"),
+                    format!("mov rdx, {flags}"),
+                    format!("call _formatChar"),
+                ]),
+
+                _ => instructions.extend(vec![
+                    format!("mov rdi, [rsp + {offset}]"),
+                    format!("mov rsi, {width}"),
+                    format!("mov rdx, {flags}"),
+                    format!("call _formatInt"),
+                ]),
+            }
+        }
+
+        // the bytes have been copied into the buffer by now
+        for ((_, is_temporary), offset) in args.iter().zip(&offsets) {
+            if *is_temporary {
+                instructions.extend(vec![format!("mov rdi, [rsp + {offset}]"), format!("call _heapFree")]);
+            }
+        }
+
+        instructions.push(format!("add rsp, {}", num_values * 8));
+
+        self.extend_current_label(instructions);
+
+        self.function_call(
+            &String::from("_formatFlush"),
+            0,
+            &VarType::Unknown,
+            false,
+            call_stack,
+            true,
+            false,
+        );
+    }
+}
@@ -3,6 +3,7 @@ use std::{cell::RefCell, rc::Rc};
 use crate::{
     ast::variable::Variable,
     lexer::{
+        lexer::Token,
         registers::{Register, ALL_FP_REGISTERS, ALL_REGISTERS},
         types::VarType,
     },
@@ -100,14 +101,14 @@ impl ASM {
         self.extend_current_label(instructions);
     }
 
-    fn handle_float_function_call_arg(&mut self, arg_num: usize) {
+    fn handle_float_function_call_arg(&mut self, arg_num: usize, arg_type: &VarType) {
         let stack_member = self.stack_pop().unwrap();
 
         let mut arg_reg = FUNCTION_FLOAT_ARGS_REGS[arg_num];
 
         self.extend_current_label(vec![
             format!(";; Moving float argument number {}", arg_num + 1),
-            format!("movsd {arg_reg}, {stack_member}"),
+            format!("mov{} {arg_reg}, {stack_member}", arg_type.get_float_suffix()),
         ]);
 
         self.unlock_register_from_stack_value(&stack_member);
@@ -129,8 +130,8 @@ impl ASM {
     ///
     /// arg num for 1 = 0, 2 = 1, 3 = 2, 4.5 = 1
     pub fn function_call_add_arg(&mut self, arg_num: usize, arg_type: VarType) {
-        if matches!(arg_type, VarType::Float) {
-            return self.handle_float_function_call_arg(arg_num);
+        if arg_type.is_float() {
+            return self.handle_float_function_call_arg(arg_num, &arg_type);
         }
 
         self.handle_non_float_function_call_arg(arg_num);
@@ -236,9 +237,13 @@ impl ASM {
         function_name: &String,
         local_var_size: usize,
         func_params: &Vec<Rc<RefCell<Variable>>>,
+        token: &Token,
     ) {
         self.change_current_label(format!("_{function_name}"));
 
+        // the function starts on the line it's defined on, the prologue is part of it
+        self.debug_line(token);
+
         // push rbp            ; Save old base pointer
         // mov rbp, rsp        ; Set base pointer to current stack pointer
         // sub rsp, 16         ; Allocate 16 bytes for local variables
@@ -261,12 +266,15 @@ impl ASM {
 
             match call_stack_var {
                 Some(var) => {
-                    let (operation, register) = if matches!(var.borrow().var_type, VarType::Float) {
+                    let (operation, register) = if var.borrow().var_type.is_float() {
                         float_arg_num += 1;
-                        ("movsd", FUNCTION_FLOAT_ARGS_REGS[float_arg_num as usize])
+                        (
+                            format!("mov{}", var.borrow().var_type.get_float_suffix()),
+                            FUNCTION_FLOAT_ARGS_REGS[float_arg_num as usize],
+                        )
                     } else {
                         non_float_arg_num += 1;
-                        ("mov", FUNCTION_ARGS_REGS[non_float_arg_num as usize])
+                        (String::from("mov"), FUNCTION_ARGS_REGS[non_float_arg_num as usize])
                     };
 
                     instructions.extend([
@@ -289,6 +297,36 @@ impl ASM {
         self.extend_current_label(instructions);
     }
 
+    /// Calls main once the global variables have been initialised and keeps what it returns as
+    /// the exit code. `main(args: []str)` gets the command line arguments in a slice on the stack
+    /// of _start
+    pub fn call_main(&mut self, takes_args: bool, returns_int: bool) {
+        let mut instructions = vec![format!(";; Calling main")];
+
+        if takes_args {
+            instructions.extend(vec![
+                format!("mov rax, [argc]"),
+                format!("mov rax, [rax]"),
+                format!("shl rax, 4"),
+                format!("add rax, 16"),
+                format!("sub rsp, rax"),
+                format!("and rsp, -16"),
+                format!("mov rdi, [argc]"),
+                format!("mov rsi, rsp"),
+                format!("call _argsSlice"),
+                format!("mov rdi, rax"),
+            ]);
+        }
+
+        instructions.push(String::from("call _main"));
+
+        if returns_int {
+            instructions.push(String::from("mov [exitCode], rax"));
+        }
+
+        self.extend_current_label(instructions);
+    }
+
     pub fn extern_function_def(&mut self, function_name: &String) {
         self.data.push(format!("extern {function_name}"))
     }
@@ -307,7 +345,9 @@ impl ASM {
             let stack_member = self.stack_pop().unwrap();
 
             // not locking here should be fine
-            self.add_to_current_label(format!("mov {}, {stack_member}", Register::RAX));
+            // floats are returned in rax as well, and mov can't read from xmm registers
+            let mov = if stack_member.starts_with("xmm") { "movq" } else { "mov" };
+            self.add_to_current_label(format!("{mov} {}, {stack_member}", Register::RAX));
 
             self.unlock_register_from_stack_value(&stack_member);
         }
@@ -0,0 +1,40 @@
+use crate::{
+    lexer::{
+        keywords::{FUNC_ALLOC, FUNC_FREE, FUNC_REALLOC},
+        types::VarType,
+    },
+    semantic_analyzer::semantic_analyzer::CallStack,
+};
+
+use super::asm::ASM;
+
+impl ASM {
+    /// alloc, free and realloc are calls into the allocator in generated/alloc.asm. The arguments
+    /// have to be added with `function_call_add_arg` beforehand
+    pub fn func_heap(
+        &mut self,
+        name: &str,
+        num_args: usize,
+        return_type: &VarType,
+        call_stack: &CallStack,
+        is_result_assigned: bool,
+    ) {
+        let label = match name {
+            FUNC_ALLOC => "_heapAlloc",
+            FUNC_FREE => "_heapFree",
+            FUNC_REALLOC => "_heapRealloc",
+
+            _ => unreachable!("'{name}' is not a heap function"),
+        };
+
+        self.function_call(
+            &String::from(label),
+            num_args,
+            return_type,
+            false,
+            call_stack,
+            true,
+            is_result_assigned,
+        );
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
     trace,
 };
 
-use super::{asm::ASM, functions::FUNCTION_ARGS_REGS};
+use super::{asm::ASM, functions::FUNCTION_ARGS_REGS, string_functions::StringArg};
 
 pub const SYSCALL_ARGS_REGS: [Register; 7] = [
     Register::RAX,
@@ -23,17 +23,57 @@ pub const SYSCALL_ARGS_REGS: [Register; 7] = [
     Register::R9,
 ];
 
-const WRITE_CHAR_ASM_INSTRUCTIONS: [&str; 8] = [
+const WRITE_CHAR_ASM_INSTRUCTIONS: [&str; 6] = [
     ";; Writing a character",
     "mov r8, 1",
     "pop r9",
-    "mov rax, 1",
-    "mov rdi, 1",
     "mov rsi, r9",
     "mov rdx, r8",
-    "syscall",
+    "call _stdoutWrite",
 ];
 
+/// Prints the number in rax. Ints narrower than 64 bits are sign extended first, as only the low
+/// bits of rax are written to
+fn print_rax(type_: &VarType) -> Vec<String> {
+    let sign_extend = match type_ {
+        VarType::Int8 => "movsx rax, al",
+        VarType::Int16 => "movsx rax, ax",
+        VarType::Int32 => "movsxd rax, eax",
+
+        VarType::Int | VarType::Enum(..) => "",
+
+        // chars are unsigned
+        _ => return vec![String::from("call _printRAX")],
+    };
+
+    let mut instructions = vec![];
+
+    if !sign_extend.is_empty() {
+        instructions.push(String::from(sign_extend));
+    }
+
+    instructions.push(String::from("call _printSignedRAX"));
+
+    instructions
+}
+
+/// _printFloat expects a float64 in xmm0, so a float32 is widened first
+fn print_float(value: &String, float_type: &VarType) -> Vec<String> {
+    let in_memory_or_xmm = value.starts_with('[') || value.starts_with("xmm");
+
+    let mut instructions = match (float_type, in_memory_or_xmm) {
+        (VarType::Float32, true) => vec![format!("cvtss2sd xmm0, {value}")],
+        (VarType::Float32, false) => vec![format!("movq xmm0, {value}"), format!("cvtss2sd xmm0, xmm0")],
+
+        (_, true) => vec![format!("movsd xmm0, {value}")],
+        (_, false) => vec![format!("movq xmm0, {value}")],
+    };
+
+    instructions.push(String::from("call _printFloat"));
+
+    instructions
+}
+
 impl ASM {
     fn get_vec_for_write_number(&mut self, type_: VarType) -> Vec<String> {
         // we pop this anyway because in binary op we push "rax" to stack no matter what
@@ -55,6 +95,23 @@ impl ASM {
         }
 
         // TODO: Also check here that there's nothing in rax
+        instructions.extend(print_rax(&type_));
+
+        instructions
+    }
+
+    /// Addresses are printed as unsigned numbers
+    fn get_vec_for_write_address(&mut self) -> Vec<String> {
+        let stack_member = self.stack_pop().unwrap();
+
+        self.unlock_register_from_stack_value(&stack_member);
+
+        let mut instructions = vec![];
+
+        if stack_member != String::from(Register::RAX) {
+            instructions.push(format!("mov rax, {stack_member}"));
+        }
+
         instructions.push(String::from("call _printRAX"));
 
         instructions
@@ -65,21 +122,83 @@ impl ASM {
         self.extend_current_label(vec);
     }
 
-    pub fn func_write_float(&mut self) {
+    pub fn func_write_float(&mut self, float_type: &VarType) {
         let stack_member = self.stack_pop().unwrap();
 
+        let mut instructions = vec![format!(";; write float")];
+        instructions.extend(print_float(&stack_member, float_type));
+
+        self.extend_current_label(instructions);
+
+        self.unlock_register_from_stack_value(&stack_member);
+    }
+
+    pub fn func_exit(&mut self) {
+        self.extend_current_label(vec![
+            format!("pop rdi"),
+            format!("call _stdoutFlush"),
+            format!("mov rax, 60"),
+            format!("syscall"),
+        ]);
+    }
+
+    /// Writes `message` to stderr and exits with 1 if the condition on top of the stack is false,
+    /// like `_castOverflow` does
+    pub fn func_assert(&mut self, message: &str) {
+        let stack_member = self.stack_pop().unwrap();
+
+        let (message_label, message_len) = self.add_string_literal(message);
+
+        let passed_label = format!(".assert_{}", self.num_asserts);
+        self.num_asserts += 1;
+
         self.extend_current_label(vec![
-            format!(";; write float"),
-            format!("movsd [float_imm], {stack_member}"),
-            format!("mov rax, [float_imm]"),
-            format!("call _printRAX"),
+            format!(";; assert"),
+            format!("cmp {stack_member}, 0"),
+            format!("jne {passed_label}"),
+            // what's been written before the assertion comes first
+            format!("call _stdoutFlush"),
+            format!("mov rax, 1"),
+            format!("mov rdi, 2"),
+            format!("mov rsi, {message_label}"),
+            format!("mov rdx, {message_len}"),
+            format!("syscall"),
+            format!("exit 1"),
+            format!("{passed_label}:"),
         ]);
 
         self.unlock_register_from_stack_value(&stack_member);
     }
 
-    pub fn func_exit(&mut self) {
-        self.extend_current_label(vec![format!("pop rdi"), format!("mov rax, 60"), format!("syscall")]);
+    /// Writes out what's buffered for stdout, see generated/io.asm
+    pub fn func_flush(&mut self, call_stack: &CallStack) {
+        self.function_call_prep();
+
+        self.function_call(
+            &String::from("_stdoutFlush"),
+            0,
+            &VarType::Unknown,
+            false,
+            call_stack,
+            true,
+            false,
+        );
+    }
+
+    /// Pushes the value of the environment variable whose name is the str on top of the stack,
+    /// see generated/process.asm. `function_call_prep` has to be called before the name
+    pub fn func_env(&mut self, call_stack: &CallStack) {
+        self.string_function_call("_env", &[StringArg::Bytes(VarType::Str)], &VarType::Unknown, call_stack);
+
+        let address = self.get_free_register(None);
+        let length = self.get_free_register(None);
+
+        self.extend_current_label(vec![
+            format!("mov {address}, [envValue]"),
+            format!("mov {length}, [envValue + 8]"),
+        ]);
+
+        self.stack_extend(vec![String::from(address), String::from(length)]);
     }
 
     pub fn func_write_string(&mut self) {
@@ -88,7 +207,7 @@ impl ASM {
 
         let mut instructions = vec![];
 
-        let used_regs = [Register::RAX, Register::RDI].to_vec();
+        let used_regs = [Register::RSI, Register::RDX].to_vec();
         let used_regs_string: Vec<String> = used_regs.clone().into_iter().map(|x| String::from(x)).collect();
 
         if used_regs_string.contains(&str_len) {
@@ -113,11 +232,9 @@ impl ASM {
         self.unlock_register_from_stack_value(&str_addr);
 
         instructions.extend(vec![
-            "mov rax, 1".into(),
-            "mov rdi, 1".into(),
             format!("mov rsi, {}", str_addr),
             format!("mov rdx, {}", str_len),
-            "syscall".into(),
+            "call _stdoutWrite".into(),
         ]);
 
         self.extend_current_label(instructions);
@@ -144,23 +261,25 @@ impl ASM {
         match **pointer_var_type {
             // a char is always represented as an 8 bit number
             VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Char => {
-                // This is fine as a pointer is always 8 bytes
-                self.get_vec_for_write_number(VarType::Int)
+                if times_dereferenced > 0 {
+                    // This is fine as a pointer is always 8 bytes
+                    self.get_vec_for_write_number(VarType::Int)
+                } else {
+                    self.get_vec_for_write_address()
+                }
             }
 
-            VarType::Float => {
+            VarType::Float | VarType::Float32 => {
                 // we pop this anyway because in binary op we push "rax" to stack no matter what
                 let stack_member = self.stack_pop().unwrap();
 
                 self.unlock_register_from_stack_value(&stack_member);
 
                 // TODO: Also check here that there's nothing in rax
-                vec![
-                    format!(";; Writing ptr -> float"),
-                    format!("movsd [float_imm], {}", stack_member),
-                    format!("mov rax, [float_imm]"),
-                    String::from("call _printRAX"),
-                ]
+                let mut instructions = vec![format!(";; Writing ptr -> float")];
+                instructions.extend(print_float(&stack_member, pointer_var_type));
+
+                instructions
             }
 
             // TODO: Check here whether the pointer is dereferenced or not
@@ -169,7 +288,7 @@ impl ASM {
                     self.func_write_string();
                     vec![]
                 } else {
-                    self.get_vec_for_write_number(VarType::Int)
+                    self.get_vec_for_write_address()
                 }
             }
 
@@ -189,7 +308,7 @@ impl ASM {
 
                             match found {
                                 Some(struct_member) => match struct_member.member_type {
-                                    VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 => {
+                                    VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Enum(..) => {
                                         self.func_write_number(struct_member.member_type.clone());
                                         vec![]
                                     }
@@ -197,13 +316,16 @@ impl ASM {
                                         self.func_write_string();
                                         vec![]
                                     }
-                                    VarType::Float => todo!(),
+                                    VarType::Float | VarType::Float32 => todo!(),
+                                    VarType::OwnedStr => todo!(),
                                     VarType::Char => todo!(),
                                     VarType::Ptr(_) => todo!(),
                                     VarType::Array(_, _) => todo!(),
+                                    VarType::Slice(_) => todo!(),
                                     VarType::Struct(_, _) => todo!(),
                                     VarType::Unknown => todo!(),
                                     VarType::Function(_, _, _) => todo!(),
+                                    VarType::Union(..) => todo!(),
                                 },
 
                                 None => {
@@ -254,7 +376,9 @@ impl ASM {
     }
 
     pub fn func_syscall_call(&mut self, is_result_assigned: bool) {
-        self.add_to_current_label("syscall".into());
+        // a raw write to stdout would otherwise overtake what's still buffered.
+        // _stdoutFlush leaves the syscall's arguments as they are
+        self.extend_current_label(vec!["call _stdoutFlush".into(), "syscall".into()]);
 
         let mut instructions = vec![];
 
@@ -280,142 +404,172 @@ impl ASM {
     }
 
     pub fn func_write_var(&mut self, var: &Variable, call_stack: &CallStack) {
-        let var_name = &var.var_name;
-
-        let instructions = match var_name.as_str() {
-            "argc" => vec![
-                // argc contains the address of rsp
-                format!("mov rax, [argc]"),
-                // since argc contained the address, we need to dereference it again to get the
-                // actual value
-                format!("mov rax, [rax]"),
-                format!("call _printRAX"),
-            ],
-
-            "argv" => vec![
-                // argc contains the address of rsp
-                format!("mov rax, [argc]"),
-                // since argc contained the address, we need to dereference it again to get the
-                // actual value
-                format!("mov rax, [rax + 8]"),
-                // now rax has the string
-                // we need to calculate its length
-                // it's NULL terminated
-
-                // save rax
-                format!("push rax"),
-                format!("mov rbx, 0"),
-                format!(".strlen:"),
-                format!("inc rax"),
-                format!("inc rbx"),
-                format!("mov cl, [rax]"),
-                format!("cmp cl, 0"),
-                format!("jne .strlen"),
-                format!("push rbx"),
-                format!("pop r8"),
-                format!("pop r9"),
-                format!("mov rax, 1"),
-                format!("mov rdi, 1"),
-                format!("mov rsi, r9"),
-                format!("mov rdx, r8"),
-                format!("syscall"),
-            ],
+        // the variable value or its address will be pushed onto the stack
+        // We don't need to check the scope here as the variable value is already
+        // pushed into rax beforehand in `factor` AST
+        let instructions = match &var.var_type {
+            VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Enum(..) => {
+                let stack_member = self.stack_pop().unwrap();
 
-            _ => {
-                // the variable value or its address will be pushed onto the stack
-                // We don't need to check the scope here as the variable value is already
-                // pushed into rax beforehand in `factor` AST
-                match &var.var_type {
-                    VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 => {
-                        let stack_member = self.stack_pop().unwrap();
-
-                        self.unlock_register_from_stack_value(&stack_member);
-
-                        vec![
-                            format!("mov rax, {} {}", var.var_type.get_operation_size(), stack_member),
-                            format!("call _printRAX"),
-                        ]
-                    }
+                self.unlock_register_from_stack_value(&stack_member);
 
-                    VarType::Str => {
-                        self.func_write_string();
-                        vec![]
-                    }
+                let mut instructions = vec![format!(
+                    "mov {}, {} {}",
+                    var.var_type.get_register_name(Register::RAX),
+                    var.var_type.get_operation_size(),
+                    stack_member
+                )];
 
-                    VarType::Char => WRITE_CHAR_ASM_INSTRUCTIONS.map(|x| x.into()).to_vec(),
+                instructions.extend(print_rax(&var.var_type));
 
-                    VarType::Ptr(pointer_var_type) => self.func_write_pointer_internal(
-                        pointer_var_type,
-                        var.times_dereferenced,
-                        call_stack,
-                        Some(var),
-                    ),
+                instructions
+            }
+
+            VarType::Str => {
+                self.func_write_string();
+                vec![]
+            }
+
+            VarType::OwnedStr => {
+                self.func_write_owned_string(false);
+                vec![]
+            }
+
+            VarType::Char => WRITE_CHAR_ASM_INSTRUCTIONS.map(|x| x.into()).to_vec(),
+
+            VarType::Ptr(pointer_var_type) => {
+                self.func_write_pointer_internal(pointer_var_type, var.times_dereferenced, call_stack, Some(var))
+            }
+
+            VarType::Float | VarType::Float32 => {
+                let value = self.stack_pop().unwrap();
+
+                let mut inst = vec![format!(";; Writing float variable")];
+                inst.extend(print_float(&value, &var.var_type));
+
+                self.unlock_register_from_stack_value(&value);
+
+                inst
+            }
+
+            // This will print the address to the array which is 8 bytes
+            VarType::Array(..) => self.get_vec_for_write_address(),
+
+            VarType::Slice(inner) if var.array_aceess_index.is_some() => match &**inner {
+                VarType::Str => {
+                    self.func_write_string();
+                    vec![]
+                }
+
+                VarType::OwnedStr => {
+                    self.func_write_owned_string(false);
+                    vec![]
+                }
 
-                    VarType::Float => {
-                        let value = self.stack_pop().unwrap();
+                // the element is already extended to 64 bits when it's indexed
+                VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Enum(..) => {
+                    self.get_vec_for_write_number(VarType::Int)
+                }
+
+                VarType::Float | VarType::Float32 => {
+                    let value = self.stack_pop().unwrap();
+
+                    let mut inst = vec![format!(";; Writing float element")];
+                    inst.extend(print_float(&value, inner));
+
+                    self.unlock_register_from_stack_value(&value);
+
+                    inst
+                }
+
+                // the element is a value and not an address, so it's written from the stack
+                VarType::Char => {
+                    let value = self.stack_pop().unwrap();
+
+                    self.unlock_register_from_stack_value(&value);
+
+                    vec![
+                        format!(";; Writing a character element"),
+                        format!("push {value}"),
+                        format!("mov rsi, rsp"),
+                        format!("mov rdx, 1"),
+                        format!("call _stdoutWrite"),
+                        format!("add rsp, 8"),
+                    ]
+                }
+
+                VarType::Ptr(pointer_var_type) => {
+                    self.func_write_pointer_internal(pointer_var_type, var.times_dereferenced, call_stack, Some(var))
+                }
+
+                VarType::Slice(_) => self.get_vec_for_write_address(),
 
-                        let mut inst = vec![format!(";; Writing float variable")];
+                VarType::Array(..)
+                | VarType::Function(..)
+                | VarType::Struct(..)
+                | VarType::Union(..)
+                | VarType::Unknown => {
+                    unreachable!("Slices of {inner} can't be indexed and are rejected in semantic analysis")
+                }
+            },
+
+            // the address of the slice header, like arrays
+            VarType::Slice(_) => self.get_vec_for_write_address(),
 
-                        if value.starts_with("[") {
-                            // movsd [memory], [memory] is not allowed
-                            inst.extend(vec![format!("mov rax, {value}"), format!("call _printRAX")])
-                        } else {
-                            inst.extend(vec![
-                                format!("movsd [float_imm], {value}"),
-                                format!("mov rax, [float_imm]"),
-                                format!("call _printRAX"),
-                            ])
+            VarType::Struct(_, member_access) => {
+                let borrow = member_access.borrow();
+                let found = borrow.iter().find(|x| x.name == var.member_access[0]);
+
+                match found {
+                    Some(struct_member_type) => match &struct_member_type.member_type {
+                        VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Enum(..) => {
+                            self.get_vec_for_write_number(struct_member_type.member_type.clone())
                         }
 
-                        self.unlock_register_from_stack_value(&value);
+                        VarType::Str => {
+                            self.func_write_string();
+                            vec![]
+                        }
 
-                        inst
-                    }
+                        VarType::OwnedStr => {
+                            self.func_write_owned_string(false);
+                            vec![]
+                        }
 
-                    // This will print the address to the array which is 8 bytes
-                    VarType::Array(..) => self.get_vec_for_write_number(VarType::Int),
+                        VarType::Ptr(var_type) => {
+                            self.func_write_pointer_internal(var_type, var.times_dereferenced, call_stack, Some(var))
+                        }
 
-                    VarType::Struct(_, member_access) => {
-                        let borrow = member_access.borrow();
-                        let found = borrow.iter().find(|x| x.name == var.member_access[0]);
+                        VarType::Float | VarType::Float32 => {
+                            let value = self.stack_pop().unwrap();
 
-                        match found {
-                            Some(struct_member_type) => match &struct_member_type.member_type {
-                                VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 => {
-                                    self.get_vec_for_write_number(struct_member_type.member_type.clone())
-                                }
+                            let mut inst = vec![format!(";; Writing float member")];
+                            inst.extend(print_float(&value, &struct_member_type.member_type));
 
-                                VarType::Str => {
-                                    self.func_write_string();
-                                    vec![]
-                                }
+                            self.unlock_register_from_stack_value(&value);
 
-                                VarType::Ptr(var_type) => self.func_write_pointer_internal(
-                                    var_type,
-                                    var.times_dereferenced,
-                                    call_stack,
-                                    Some(var),
-                                ),
-
-                                VarType::Float => todo!(),
-                                VarType::Char => todo!(),
-                                VarType::Array(_, _) => todo!(),
-                                VarType::Struct(_, _) => todo!(),
-                                VarType::Unknown => todo!(),
-                                VarType::Function(_, _, _) => todo!(),
-                            },
-
-                            None => unreachable!(
-                                "Could not find memeber '{}' of struct while generating ASM",
-                                var.member_access[0]
-                            ),
+                            inst
                         }
-                    }
 
-                    VarType::Unknown => todo!(),
-                    VarType::Function(_, _, _) => todo!(),
+                        VarType::Char => todo!(),
+                        VarType::Array(_, _) => todo!(),
+                        VarType::Slice(_) => todo!(),
+                        VarType::Struct(_, _) => todo!(),
+                        VarType::Unknown => todo!(),
+                        VarType::Function(_, _, _) => todo!(),
+                        VarType::Union(..) => todo!(),
+                    },
+
+                    None => unreachable!(
+                        "Could not find memeber '{}' of struct while generating ASM",
+                        var.member_access[0]
+                    ),
                 }
             }
+
+            VarType::Unknown => todo!(),
+            VarType::Function(_, _, _) => todo!(),
+            VarType::Union(..) => todo!(),
         };
 
         self.extend_current_label(instructions);
@@ -0,0 +1,153 @@
+use crate::{
+    ast::variable::Variable,
+    lexer::{
+        registers::{get_register_name_for_bits, Register},
+        types::UNION_TAG_SIZE,
+    },
+};
+
+use super::asm::ASM;
+
+impl ASM {
+    pub fn inc_num_matches(&mut self) {
+        self.num_matches += 1;
+    }
+
+    /// `arm_values` has one entry per arm, in the order the arms were written in.
+    /// `None` is the wildcard arm
+    ///
+    /// Dense values are dispatched with a jump table, sparse values with a chain of comparisons
+    pub fn match_start(&mut self, arm_values: &[Option<i32>], match_num: usize) {
+        let default_label = match arm_values.iter().position(|value| value.is_none()) {
+            Some(index) => format!(".match_{match_num}_arm_{index}"),
+            None => format!(".match_{match_num}_end"),
+        };
+
+        // (value, arm index)
+        let cases: Vec<(i64, usize)> = arm_values
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| value.map(|v| (v as i64, index)))
+            .collect();
+
+        let stack_member = self.stack_pop().unwrap();
+
+        let rax = self.get_free_register(None);
+
+        let mut instructions = vec![format!(".match_{match_num}:"), format!("mov {rax}, {stack_member}")];
+
+        self.unlock_register_from_stack_value(&stack_member);
+
+        let min = cases.iter().map(|(value, _)| *value).min().unwrap_or(0);
+        let max = cases.iter().map(|(value, _)| *value).max().unwrap_or(0);
+        let range = max - min + 1;
+
+        if cases.len() >= 2 && range <= 2 * cases.len() as i64 {
+            let table = format!("match_{match_num}_table");
+
+            let rbx = self.get_free_register(None);
+
+            instructions.extend(vec![
+                format!(";; index into the jump table"),
+                format!("sub {rax}, {min}"),
+                format!(";; unsigned comparison, so values smaller than the smallest case also go to the default"),
+                format!("cmp {rax}, {}", range - 1),
+                format!("ja {default_label}"),
+                format!("lea {rbx}, [{table}]"),
+                format!("jmp [{rbx} + {rax} * 8]"),
+            ]);
+
+            self.unlock_register(rbx);
+
+            // local labels have to be fully qualified when referenced from the data section
+            let current_label = self.current_label();
+
+            let entries: Vec<String> = (min..=max)
+                .map(|value| match cases.iter().find(|(v, _)| *v == value) {
+                    Some((_, index)) => format!("{current_label}.match_{match_num}_arm_{index}"),
+                    None => format!("{current_label}{default_label}"),
+                })
+                .collect();
+
+            self.data.push(format!("{table} dq {}", entries.join(", ")));
+        } else {
+            for (value, index) in &cases {
+                instructions.extend(vec![
+                    format!("cmp {rax}, {value}"),
+                    format!("je .match_{match_num}_arm_{index}"),
+                ]);
+            }
+
+            instructions.push(format!("jmp {default_label}"));
+        }
+
+        self.unlock_register(rax);
+
+        self.extend_current_label(instructions);
+    }
+
+    /// The address of the union is on top of the stack. Keeps it in a register, which the caller
+    /// has to unlock after the match, and pushes the tag so it can be matched on
+    pub fn match_load_union_tag(&mut self) -> Register {
+        let stack_member = self.stack_pop().unwrap();
+
+        let address = self.get_free_register(None);
+
+        self.extend_current_label(vec![
+            format!(";; Address of the union being matched on"),
+            format!("mov {address}, {stack_member}"),
+        ]);
+
+        self.unlock_register_from_stack_value(&stack_member);
+
+        self.stack_push(format!("[{address}]"));
+
+        return address;
+    }
+
+    /// Copies the payload of the union at `address` into `binding`. Must be called before anything
+    /// else in the arm, as `address` is only guaranteed to be valid right after the jump
+    pub fn match_bind_union_payload(&mut self, address: Register, binding: &Variable) {
+        let size = binding.var_type.get_size();
+
+        let rax = self.get_free_register(None);
+
+        let mut instructions = vec![format!(";; Binding payload to '{}'", binding.var_name)];
+
+        let mut copied = 0;
+
+        while copied < size {
+            let (chunk, operation_size) = match size - copied {
+                remaining if remaining >= 8 => (8, "QWORD"),
+                remaining if remaining >= 4 => (4, "DWORD"),
+                remaining if remaining >= 2 => (2, "WORD"),
+                _ => (1, "BYTE"),
+            };
+
+            let reg = get_register_name_for_bits(&rax, chunk * 8);
+
+            instructions.extend(vec![
+                format!("mov {reg}, {operation_size} [{address} + {}]", UNION_TAG_SIZE + copied),
+                format!("mov {operation_size} [rbp - {}], {reg}", binding.offset - copied),
+            ]);
+
+            copied += chunk as usize;
+        }
+
+        self.unlock_register(rax);
+
+        self.extend_current_label(instructions);
+    }
+
+    pub fn match_arm_start(&mut self, arm_index: usize, match_num: usize) {
+        self.add_to_current_label(format!(".match_{match_num}_arm_{arm_index}:"));
+    }
+
+    pub fn match_arm_end(&mut self, match_num: usize) {
+        self.add_to_current_label(format!("jmp .match_{match_num}_end"));
+    }
+
+    pub fn match_end(&mut self, match_num: usize) {
+        self.add_to_current_label(format!(".match_{match_num}_end:"));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::lexer::{
+    keywords::{FUNC_ABS, FUNC_CEIL, FUNC_FLOOR, FUNC_MAX, FUNC_MIN, FUNC_SQRT},
+    types::VarType,
+};
+
+use super::{asm::ASM, type_cast::load_float};
+
+impl ASM {
+    /// min and max on the two floats on top of the stack. If either of them is NaN, the second
+    /// one is returned
+    fn func_min_max(&mut self, name: &str, float_type: &VarType) -> Vec<String> {
+        let suffix = float_type.get_float_suffix();
+
+        let second = self.stack_pop().unwrap();
+        let first = self.stack_pop().unwrap();
+
+        let xmm0 = self.get_free_float_register(None);
+        let xmm1 = self.get_free_float_register(None);
+
+        let instructions = vec![
+            load_float(&xmm0, &first, float_type),
+            load_float(&xmm1, &second, float_type),
+            format!("{name}{suffix} {xmm0}, {xmm1}"),
+        ];
+
+        self.unlock_register_from_stack_value(&first);
+        self.unlock_register_from_stack_value(&second);
+
+        self.unlock_register(xmm1);
+
+        self.stack_push(String::from(xmm0));
+
+        instructions
+    }
+
+    /// sqrt, abs, floor, ceil, min and max of the float(s) on top of the stack
+    pub fn func_math(&mut self, name: &str, float_type: &VarType) {
+        let mut instructions = vec![format!(";; {name} of {float_type}")];
+
+        if name == FUNC_MIN || name == FUNC_MAX {
+            instructions.extend(self.func_min_max(name, float_type));
+            self.extend_current_label(instructions);
+            return;
+        }
+
+        let suffix = float_type.get_float_suffix();
+
+        let value = self.stack_pop().unwrap();
+
+        let xmm0 = self.get_free_float_register(None);
+
+        instructions.push(load_float(&xmm0, &value, float_type));
+
+        self.unlock_register_from_stack_value(&value);
+
+        match name {
+            FUNC_SQRT => instructions.push(format!("sqrt{suffix} {xmm0}, {xmm0}")),
+
+            // rounding mode 1 rounds towards -inf and 2 towards +inf
+            FUNC_FLOOR => instructions.push(format!("round{suffix} {xmm0}, {xmm0}, 1")),
+            FUNC_CEIL => instructions.push(format!("round{suffix} {xmm0}, {xmm0}, 2")),
+
+            // clear the sign bit
+            FUNC_ABS => {
+                let mask = match float_type {
+                    VarType::Float32 => "0x7fffffff",
+                    _ => "0x7fffffffffffffff",
+                };
+
+                let rax = self.get_free_register(None);
+                let xmm1 = self.get_free_float_register(None);
+
+                instructions.extend(vec![
+                    format!("mov {rax}, {mask}"),
+                    format!("movq {xmm1}, {rax}"),
+                    format!("andpd {xmm0}, {xmm1}"),
+                ]);
+
+                self.unlock_register(rax);
+                self.unlock_register(xmm1);
+            }
+
+            _ => unreachable!("'{name}' is not a math function"),
+        }
+
+        self.stack_push(String::from(xmm0));
+
+        self.extend_current_label(instructions);
+    }
+}
@@ -1,13 +1,23 @@
+pub mod annotate;
 pub mod asm;
 pub mod asm_loop;
 pub mod binary_op;
 pub mod comparison;
 pub mod conditionals;
+pub mod debug_info;
 pub mod factor;
+pub mod format_functions;
 pub mod functions;
+pub mod heap_functions;
 pub mod internal_functions;
 pub mod logical;
+pub mod match_statement;
+pub mod math_functions;
 pub mod memory_alloc;
+pub mod slice_functions;
+pub mod string_functions;
+pub mod test_runner;
+pub mod type_cast;
 pub mod variable_assignment;
 pub mod variable_declaration;
 pub mod variables;
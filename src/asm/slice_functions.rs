@@ -0,0 +1,95 @@
+use crate::lexer::{registers::get_register_name_for_bits, types::VarType};
+
+use super::{asm::ASM, type_cast::extend_register};
+
+/// A slice is a pointer to a header of the address of the first element followed by the number
+/// of elements
+const SLICE_LEN_OFFSET: usize = 8;
+
+impl ASM {
+    /// Pushes the element of the slice at `slice` whose index is on top of the stack. An index
+    /// past the end exits the program
+    pub fn slice_index(&mut self, element_type: &VarType, slice: &str) {
+        let index = self.stack_pop().unwrap();
+
+        let header = self.get_free_register(None);
+        let element = self.get_free_register(None);
+
+        let mut instructions = vec![
+            format!(";; Indexing a slice"),
+            format!("mov {header}, {slice}"),
+            format!("mov {element}, {index}"),
+            format!("cmp {element}, [{header} + {SLICE_LEN_OFFSET}]"),
+            // negative indices are huge when unsigned
+            format!("jae _sliceIndexOutOfRange"),
+            format!("imul {element}, {}", element_type.get_size()),
+            format!("add {element}, [{header}]"),
+        ];
+
+        self.unlock_register_from_stack_value(&index);
+        self.unlock_register(header);
+
+        match element_type {
+            VarType::Str => {
+                let length = self.get_free_register(None);
+
+                instructions.extend(vec![
+                    format!("mov {length}, [{element} + 8]"),
+                    format!("mov {element}, [{element}]"),
+                ]);
+
+                self.stack_extend(vec![String::from(element), String::from(length)]);
+            }
+
+            VarType::Int
+            | VarType::Float
+            | VarType::Enum(..)
+            | VarType::OwnedStr
+            | VarType::Ptr(_)
+            | VarType::Slice(_) => {
+                instructions.push(format!("mov {element}, [{element}]"));
+
+                self.stack_push(String::from(element));
+            }
+
+            // the narrower elements are loaded into the lower bits and then extended
+            VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Char | VarType::Float32 => {
+                let bits = (element_type.get_size() * 8) as u8;
+
+                instructions.push(format!(
+                    "mov {}, [{element}]",
+                    get_register_name_for_bits(&element, bits)
+                ));
+                instructions.extend(extend_register(&element, element_type));
+
+                self.stack_push(String::from(element));
+            }
+
+            VarType::Array(..)
+            | VarType::Struct(..)
+            | VarType::Union(..)
+            | VarType::Function(..)
+            | VarType::Unknown => {
+                unreachable!("Slices of {element_type} can't be indexed and are rejected in semantic analysis")
+            }
+        }
+
+        self.extend_current_label(instructions);
+    }
+
+    /// Replaces the slice on top of the stack with its number of elements
+    pub fn slice_len(&mut self) {
+        let slice = self.stack_pop().unwrap();
+        self.unlock_register_from_stack_value(&slice);
+
+        let rax = self.get_free_register(None);
+
+        self.extend_current_label(vec![
+            format!(";; Length of a slice"),
+            format!("mov {rax}, {slice}"),
+            format!("mov {rax}, [{rax} + {SLICE_LEN_OFFSET}]"),
+        ]);
+
+        self.stack_push(String::from(rax));
+    }
+}
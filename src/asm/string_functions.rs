@@ -0,0 +1,293 @@
+use crate::{
+    lexer::{tokens::Comparators, types::VarType},
+    semantic_analyzer::semantic_analyzer::CallStack,
+};
+
+use super::{asm::ASM, functions::FUNCTION_ARGS_REGS};
+
+/// Bytes before the first character of a String, see generated/string.asm
+pub const STRING_HEADER_SIZE: usize = 16;
+
+/// How an argument is passed to a function in generated/string.asm
+pub enum StringArg {
+    /// A str or a String, passed as the address of its first byte and its length
+    Bytes(VarType),
+    /// A String made by an expression, like `a + b`, that nothing else points to. Passed like
+    /// Bytes and freed once the function is done with it
+    Temporary,
+    /// Passed as is, a 64 bit integer or the pointer to a String
+    Value,
+}
+
+impl StringArg {
+    pub fn new(var_type: &VarType, is_temporary: bool) -> Self {
+        if is_temporary {
+            StringArg::Temporary
+        } else {
+            StringArg::Bytes(var_type.clone())
+        }
+    }
+}
+
+impl ASM {
+    /// Functions that take strings also take a bit mask of which of them are temporaries as their
+    /// last argument, bit 0 for the first string and bit 1 for the second one
+    fn string_function_call_with_temporaries(
+        &mut self,
+        label: &str,
+        mut args: Vec<StringArg>,
+        return_type: &VarType,
+        call_stack: &CallStack,
+    ) {
+        let temporaries = args
+            .iter()
+            .filter(|arg| !matches!(arg, StringArg::Value))
+            .enumerate()
+            .fold(0, |mask, (index, arg)| match arg {
+                StringArg::Temporary => mask | (1 << index),
+                _ => mask,
+            });
+
+        self.stack_push(format!("{temporaries}"));
+        args.push(StringArg::Value);
+
+        self.string_function_call(label, &args, return_type, call_stack);
+    }
+
+    /// Calls `label` in generated/string.asm with the arguments on top of the stack and pushes
+    /// the result. `function_call_prep` has to be called before the arguments are visited
+    pub fn string_function_call(
+        &mut self,
+        label: &str,
+        args: &[StringArg],
+        return_type: &VarType,
+        call_stack: &CallStack,
+    ) {
+        // a str takes up two places on the stack, the address and then the length
+        let mut stack_values = vec![];
+
+        for arg in args.iter().rev() {
+            let num_values = match arg {
+                StringArg::Bytes(VarType::Str) => 2,
+                _ => 1,
+            };
+
+            let mut values = vec![];
+
+            for _ in 0..num_values {
+                values.insert(0, self.stack_pop().unwrap());
+            }
+
+            stack_values.insert(0, values);
+        }
+
+        let mut instructions = vec![format!(";; Calling {label}")];
+
+        // Going through the stack, as the values might already be in the argument registers
+        for value in stack_values.iter().flatten() {
+            if value.starts_with('[') {
+                instructions.push(format!("push QWORD {value}"));
+            } else {
+                instructions.push(format!("push {value}"));
+            }
+
+            self.unlock_register_from_stack_value(value);
+        }
+
+        let mut arg_regs = FUNCTION_ARGS_REGS.iter();
+        let mut pops = vec![];
+        let mut string_to_bytes = vec![];
+
+        for (arg, values) in args.iter().zip(&stack_values) {
+            match arg {
+                StringArg::Bytes(VarType::Str) => {
+                    let address = arg_regs.next().unwrap();
+                    let length = arg_regs.next().unwrap();
+
+                    pops.extend([format!("pop {address}"), format!("pop {length}")]);
+                }
+
+                StringArg::Bytes(_) | StringArg::Temporary => {
+                    let address = arg_regs.next().unwrap();
+                    let length = arg_regs.next().unwrap();
+
+                    pops.push(format!("pop {address}"));
+
+                    string_to_bytes.extend([
+                        format!("mov {length}, [{address}]"),
+                        format!("add {address}, {STRING_HEADER_SIZE}"),
+                    ]);
+                }
+
+                StringArg::Value => {
+                    pops.extend(values.iter().map(|_| format!("pop {}", arg_regs.next().unwrap())));
+                }
+            }
+        }
+
+        instructions.extend(pops.into_iter().rev());
+        instructions.extend(string_to_bytes);
+
+        self.extend_current_label(instructions);
+
+        self.function_call(
+            &String::from(label),
+            args.len(),
+            return_type,
+            false,
+            call_stack,
+            true,
+            true,
+        );
+    }
+
+    /// left + right, where at least one of them is a String
+    pub fn string_concat(&mut self, left: StringArg, right: StringArg, call_stack: &CallStack) {
+        self.string_function_call_with_temporaries("_stringConcat", vec![left, right], &VarType::OwnedStr, call_stack);
+    }
+
+    /// string += right. The String might move, so the result has to be stored back
+    pub fn string_append(&mut self, right: StringArg, call_stack: &CallStack) {
+        self.string_function_call_with_temporaries(
+            "_stringAppend",
+            vec![StringArg::Value, right],
+            &VarType::OwnedStr,
+            call_stack,
+        );
+    }
+
+    /// Compares two strings byte by byte, where at least one of them is a String
+    pub fn string_compare(&mut self, op: Comparators, left: StringArg, right: StringArg, call_stack: &CallStack) {
+        self.string_function_call_with_temporaries("_stringCompare", vec![left, right], &VarType::Int, call_stack);
+
+        // _stringCompare returns -1, 0 or 1, which compares to 0 the same way the strings compare
+        self.stack_push(String::from("0"));
+        self.compare_two_numbers(op, &VarType::Int);
+    }
+
+    /// string[start..end] for a str or a String, the start and the end have to be 64 bit integers
+    pub fn string_slice(&mut self, string_type: &VarType, call_stack: &CallStack) {
+        self.string_function_call_with_temporaries(
+            "_stringSlice",
+            vec![
+                StringArg::Bytes(string_type.clone()),
+                StringArg::Value,
+                StringArg::Value,
+            ],
+            &VarType::OwnedStr,
+            call_stack,
+        );
+    }
+
+    /// `value as String` and `string as str`. Casts to a String allocate a new one
+    pub fn string_cast(&mut self, from_type: &VarType, to_type: &VarType, call_stack: &CallStack) {
+        match (from_type, to_type) {
+            (VarType::OwnedStr, VarType::OwnedStr) => {}
+
+            (VarType::OwnedStr, VarType::Str) => {
+                let string = self.stack_pop().unwrap();
+                self.unlock_register_from_stack_value(&string);
+
+                let rax = self.get_free_register(None);
+                let rbx = self.get_free_register(None);
+
+                self.extend_current_label(vec![
+                    format!(";; Casting {from_type} to {to_type}"),
+                    format!("mov {rax}, {string}"),
+                    format!("mov {rbx}, [{rax}]"),
+                    format!("add {rax}, {STRING_HEADER_SIZE}"),
+                ]);
+
+                // the length is pushed last
+                self.stack_extend(vec![String::from(rax), String::from(rbx)]);
+            }
+
+            (VarType::Str, _) => {
+                self.string_function_call(
+                    "_stringFromBytes",
+                    &[StringArg::Bytes(VarType::Str)],
+                    &VarType::OwnedStr,
+                    call_stack,
+                );
+            }
+
+            (VarType::Float | VarType::Float32, _) => {
+                self.type_cast(from_type, &VarType::Float);
+                self.function_call_add_arg(0, VarType::Float);
+
+                self.function_call(
+                    &String::from("_stringFromFloat"),
+                    1,
+                    &VarType::OwnedStr,
+                    false,
+                    call_stack,
+                    true,
+                    true,
+                );
+            }
+
+            _ => {
+                self.type_cast(from_type, &VarType::Int);
+                self.function_call_add_arg(0, VarType::Int);
+
+                self.function_call(
+                    &String::from("_stringFromInt"),
+                    1,
+                    &VarType::OwnedStr,
+                    false,
+                    call_stack,
+                    true,
+                    true,
+                );
+            }
+        }
+    }
+
+    /// len(string) for a str or a String
+    pub fn string_len(&mut self, var_type: &VarType) {
+        if let VarType::Str = var_type {
+            let length = self.stack_pop().unwrap();
+            let address = self.stack_pop().unwrap();
+
+            self.unlock_register_from_stack_value(&address);
+            self.stack_push(length);
+
+            return;
+        }
+
+        let string = self.stack_pop().unwrap();
+        self.unlock_register_from_stack_value(&string);
+
+        let rax = self.get_free_register(None);
+
+        self.extend_current_label(vec![
+            format!(";; Length of a String"),
+            format!("mov {rax}, {string}"),
+            format!("mov {rax}, [{rax}]"),
+        ]);
+
+        self.stack_push(String::from(rax));
+    }
+
+    /// Writes the String on top of the stack to stdout. A temporary String is freed afterwards
+    pub fn func_write_owned_string(&mut self, is_temporary: bool) {
+        let string = self.stack_pop().unwrap();
+        self.unlock_register_from_stack_value(&string);
+
+        self.extend_current_label(vec![
+            format!(";; Writing a String"),
+            format!("mov rsi, {string}"),
+            format!("mov rdx, [rsi]"),
+            format!("add rsi, {STRING_HEADER_SIZE}"),
+            format!("call _stdoutWrite"),
+        ]);
+
+        if is_temporary {
+            // _stdoutWrite leaves rsi as is
+            self.extend_current_label(vec![
+                format!("lea rdi, [rsi - {STRING_HEADER_SIZE}]"),
+                format!("call _heapFree"),
+            ]);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::parser::parse_test::Test;
+
+use super::asm::ASM;
+
+const SYS_FORK: usize = 57;
+const SYS_WAIT4: usize = 61;
+
+/// Prints the number at `label` without a newline
+fn print_count(label: &str) -> Vec<String> {
+    vec![
+        format!("mov rax, [{label}]"),
+        format!("mov rdi, 1"),
+        format!("call _printUnsigned"),
+    ]
+}
+
+/// Prints a string added with `add_string_literal`
+fn print_string((label, len): &(String, usize)) -> Vec<String> {
+    vec![
+        format!("mov r9, {label}"),
+        format!("mov r8, {len}"),
+        format!("call _printString"),
+    ]
+}
+
+impl ASM {
+    /// Instead of calling main, runs every test in a child process of its own, so a test that
+    /// fails or crashes can't take the others down with it. A test fails if it exits with
+    /// anything but 0, and crashes if it's killed by a signal. Exits with 1 if any test didn't
+    /// pass
+    pub fn run_tests(&mut self, tests: &[Test]) {
+        self.bss.extend(vec![
+            String::from("testsPassed resq 1"),
+            String::from("testsFailed resq 1"),
+            // what wait4 writes the exit status of the test into
+            String::from("testStatus resq 1"),
+        ]);
+
+        let ok = self.add_string_literal("ok\\n");
+        let failed_message = self.add_string_literal("FAILED\\n");
+        let crashed_message = self.add_string_literal("crashed\\n");
+
+        let mut instructions = vec![];
+
+        for (index, test) in tests.iter().enumerate() {
+            let child = format!(".test_{index}_child");
+            let failed = format!(".test_{index}_failed");
+            let crashed = format!(".test_{index}_crashed");
+            let result = format!(".test_{index}_result");
+            let end = format!(".test_{index}_end");
+
+            instructions.extend(vec![
+                format!(";; Running test \"{}\"", test.name),
+                // the child would write out what's buffered again
+                format!("call _stdoutFlush"),
+                format!("mov rax, {SYS_FORK}"),
+                format!("syscall"),
+                format!("cmp rax, 0"),
+                format!("je {child}"),
+                // a test that couldn't be started fails
+                format!("mov qword [testStatus], 0xff00"),
+                format!("jl {result}"),
+                format!("mov rdi, rax"),
+                format!("mov rsi, testStatus"),
+                format!("xor rdx, rdx"),
+                format!("xor r10, r10"),
+                format!("mov rax, {SYS_WAIT4}"),
+                format!("syscall"),
+                format!("{result}:"),
+            ]);
+
+            let name = self.add_string_literal(&format!("test {} ... ", test.name));
+            instructions.extend(print_string(&name));
+
+            instructions.extend(vec![
+                format!("mov eax, [testStatus]"),
+                // the signal that killed the test
+                format!("test eax, 0x7f"),
+                format!("jnz {crashed}"),
+                // the exit code of the test
+                format!("test eax, 0xff00"),
+                format!("jnz {failed}"),
+            ]);
+
+            instructions.extend(print_string(&ok));
+            instructions.extend(vec![format!("inc qword [testsPassed]"), format!("jmp {end}")]);
+
+            instructions.push(format!("{failed}:"));
+            instructions.extend(print_string(&failed_message));
+            instructions.extend(vec![format!("inc qword [testsFailed]"), format!("jmp {end}")]);
+
+            instructions.push(format!("{crashed}:"));
+            instructions.extend(print_string(&crashed_message));
+            instructions.extend(vec![format!("inc qword [testsFailed]"), format!("jmp {end}")]);
+
+            instructions.extend(vec![
+                format!("{child}:"),
+                format!("call _{}", test.function),
+                format!("exit 0"),
+                format!("{end}:"),
+            ]);
+        }
+
+        let summary_start = self.add_string_literal("\\n");
+        let passed_message = self.add_string_literal(" passed; ");
+        let summary_end = self.add_string_literal(" failed\\n");
+
+        instructions.push(String::from(";; Test summary"));
+        instructions.extend(print_string(&summary_start));
+        instructions.extend(print_count("testsPassed"));
+        instructions.extend(print_string(&passed_message));
+        instructions.extend(print_count("testsFailed"));
+        instructions.extend(print_string(&summary_end));
+
+        instructions.extend(vec![
+            format!("cmp qword [testsFailed], 0"),
+            format!("je .tests_passed"),
+            format!("mov qword [exitCode], 1"),
+            format!(".tests_passed:"),
+        ]);
+
+        self.extend_current_label(instructions);
+    }
+}
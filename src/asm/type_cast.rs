@@ -0,0 +1,174 @@
+use crate::lexer::{
+    registers::{get_register_name_for_bits, Register},
+    types::VarType,
+};
+
+use super::asm::ASM;
+
+/// Instruction that fills the whole of `register` from its lower bits, sign extending ints and
+/// zero extending chars
+pub fn extend_register(register: &Register, var_type: &VarType) -> Option<String> {
+    let reg = get_register_name_for_bits(register, 64);
+
+    match var_type {
+        VarType::Int8 => Some(format!("movsx {reg}, {}", get_register_name_for_bits(register, 8))),
+        VarType::Int16 => Some(format!("movsx {reg}, {}", get_register_name_for_bits(register, 16))),
+        VarType::Int32 => Some(format!("movsxd {reg}, {}", get_register_name_for_bits(register, 32))),
+        VarType::Char => Some(format!("movzx {reg}, {}", get_register_name_for_bits(register, 8))),
+
+        _ => None,
+    }
+}
+
+/// Moves the float `value` into the xmm `register`. Floats returned by functions are in general
+/// purpose registers, which movsd and movss can't read from
+pub fn load_float(register: &Register, value: &str, float_type: &VarType) -> String {
+    if value.starts_with('[') || value.starts_with("xmm") {
+        format!("mov{} {register}, {value}", float_type.get_float_suffix())
+    } else {
+        format!("movq {register}, {value}")
+    }
+}
+
+impl ASM {
+    /// The conversion instructions read floats from memory or xmm registers only, so a float in a
+    /// general purpose register is moved to an xmm register first
+    fn float_operand(&mut self, value: String, float_type: &VarType, instructions: &mut Vec<String>) -> String {
+        if value.starts_with('[') || value.starts_with("xmm") {
+            return value;
+        }
+
+        let xmm0 = self.get_free_float_register(None);
+
+        instructions.push(load_float(&xmm0, &value, float_type));
+
+        self.unlock_register_from_stack_value(&value);
+
+        String::from(xmm0)
+    }
+
+    /// Moves an integer of type `from_type` into a register, extended to 64 bits
+    fn load_integer(&mut self, value: &String, from_type: &VarType) -> (Register, Vec<String>) {
+        let rax = self.get_free_register(None);
+
+        let mut instructions = vec![format!("mov {rax}, {value}")];
+        instructions.extend(extend_register(&rax, from_type));
+
+        self.unlock_register_from_stack_value(value);
+
+        (rax, instructions)
+    }
+
+    /// With --checked-casts, jumps to _castOverflow if the 64 bit value in `register` changes when
+    /// it's narrowed to `to_type`
+    fn check_narrowing(&mut self, register: &Register, to_type: &VarType) -> Vec<String> {
+        if !self.checked_casts {
+            return vec![];
+        }
+
+        let rbx = self.get_free_register(None);
+
+        let mut instructions = vec![format!("mov {rbx}, {register}")];
+
+        match extend_register(&rbx, to_type) {
+            Some(extend) => instructions.push(extend),
+
+            // Nothing to narrow
+            None => {
+                self.unlock_register(rbx);
+                return vec![];
+            }
+        }
+
+        instructions.extend(vec![format!("cmp {rbx}, {register}"), format!("jne _castOverflow")]);
+
+        self.unlock_register(rbx);
+
+        instructions
+    }
+
+    /// Converts the value on top of the stack from `from_type` to `to_type` and pushes the result.
+    /// Ints are always pushed extended to 64 bits
+    pub fn type_cast(&mut self, from_type: &VarType, to_type: &VarType) {
+        // Nothing to convert, and an int of this type is already 64 bits
+        if from_type == to_type && matches!(to_type, VarType::Int | VarType::Float | VarType::Float32) {
+            return;
+        }
+
+        let value = self.stack_pop().unwrap();
+
+        let mut instructions = vec![format!(";; Casting {from_type} to {to_type}")];
+
+        match (from_type, to_type) {
+            (VarType::Float | VarType::Float32, VarType::Float | VarType::Float32) => {
+                let value = self.float_operand(value, from_type, &mut instructions);
+
+                let xmm0 = self.get_free_float_register(None);
+
+                // cvtsd2ss or cvtss2sd
+                instructions.push(format!(
+                    "cvt{}2{} {xmm0}, {value}",
+                    from_type.get_float_suffix(),
+                    to_type.get_float_suffix()
+                ));
+
+                self.unlock_register_from_stack_value(&value);
+
+                self.stack_push(String::from(xmm0));
+            }
+
+            (VarType::Float | VarType::Float32, _) => {
+                let value = self.float_operand(value, from_type, &mut instructions);
+
+                let rax = self.get_free_register(None);
+
+                // truncates towards zero
+                instructions.push(format!("cvtt{}2si {rax}, {value}", from_type.get_float_suffix()));
+
+                self.unlock_register_from_stack_value(&value);
+
+                if self.checked_casts {
+                    // cvttsd2si returns 0x8000000000000000 if the float is too large or is NaN
+                    let rbx = self.get_free_register(None);
+
+                    instructions.extend(vec![
+                        format!("mov {rbx}, 0x8000000000000000"),
+                        format!("cmp {rax}, {rbx}"),
+                        format!("je _castOverflow"),
+                    ]);
+
+                    self.unlock_register(rbx);
+                }
+
+                instructions.extend(self.check_narrowing(&rax, to_type));
+                instructions.extend(extend_register(&rax, to_type));
+
+                self.stack_push(String::from(rax));
+            }
+
+            (_, VarType::Float | VarType::Float32) => {
+                let (rax, load) = self.load_integer(&value, from_type);
+                instructions.extend(load);
+
+                let xmm0 = self.get_free_float_register(None);
+
+                instructions.push(format!("cvtsi2{} {xmm0}, {rax}", to_type.get_float_suffix()));
+
+                self.unlock_register(rax);
+                self.stack_push(String::from(xmm0));
+            }
+
+            _ => {
+                let (rax, load) = self.load_integer(&value, from_type);
+                instructions.extend(load);
+
+                instructions.extend(self.check_narrowing(&rax, to_type));
+                instructions.extend(extend_register(&rax, to_type));
+
+                self.stack_push(String::from(rax));
+            }
+        }
+
+        self.extend_current_label(instructions);
+    }
+}
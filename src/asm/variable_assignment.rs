@@ -69,6 +69,68 @@ impl ASM {
         self.extend_current_label(v);
     }
 
+    fn assign_local_float(&mut self, var_offset: usize, var_type: &VarType) {
+        let stack_item = self.stack_pop().unwrap();
+
+        let suffix = var_type.get_float_suffix();
+
+        let mut v = vec![format!(";; assign_local_float of type {}", var_type)];
+
+        if stack_item.starts_with("xmm") {
+            v.push(format!("mov{suffix} [rbp - {var_offset}], {stack_item}"));
+        } else {
+            let xmm0 = self.get_free_float_register(None);
+
+            // memory to memory moves are not allowed
+            if stack_item.starts_with('[') {
+                v.push(format!("mov{suffix} {xmm0}, {stack_item}"));
+            } else {
+                v.push(format!("movq {xmm0}, {stack_item}"));
+            }
+
+            v.push(format!("mov{suffix} [rbp - {var_offset}], {xmm0}"));
+
+            self.unlock_register(xmm0);
+        }
+
+        self.unlock_register_from_stack_value(&stack_item);
+
+        self.extend_current_label(v);
+    }
+
+    /// ptr.member = value, where ptr is a pointer to a struct
+    fn assign_local_struct_member_through_ptr(&mut self, ptr_offset: usize, member: &StructMemberType) {
+        let stack_member = self.stack_pop().unwrap();
+
+        let rax = self.get_free_register(None);
+        let rbx = self.get_free_register(None);
+
+        let mut instructions = vec![
+            format!(";; Assigning to member '{}' through a pointer", member.name),
+            format!("mov {rax}, {stack_member}"),
+            format!("mov {rbx}, [rbp - {ptr_offset}]"),
+        ];
+
+        match &member.member_type {
+            VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Enum(..) => {
+                instructions.push(format!(
+                    "mov {} [{rbx} + {}], {}",
+                    member.member_type.get_operation_size(),
+                    member.offset,
+                    member.member_type.get_register_name(rax)
+                ));
+            }
+
+            v => unimplemented!("Assignment to var_type '{}' inside struct not handled", v),
+        };
+
+        self.unlock_register_from_stack_value(&stack_member);
+        self.unlock_register(rax);
+        self.unlock_register(rbx);
+
+        self.extend_current_label(instructions);
+    }
+
     fn assign_local_array(
         &mut self,
         var_offset: usize,
@@ -166,17 +228,20 @@ impl ASM {
         match *var_ptr_type.clone() {
             VarType::Ptr(ptr_type) => self.assign_local_pointer(&ptr_type, var_offset, times_dereferenced),
 
-            VarType::Float => {
-                is_ptr_deref = times_dereferenced > 0;
+            // Assigning an address to the pointer itself is the same for every type
+            VarType::Float | VarType::Float32 if times_dereferenced > 0 => {
+                is_ptr_deref = true;
+
+                let suffix = var_ptr_type.get_float_suffix();
 
-                instructions.push(format!(";; assign_local_pointer of type {}", VarType::Float));
+                instructions.push(format!(";; assign_local_pointer of type {}", var_ptr_type));
 
                 let stack_member = self.stack_pop().unwrap();
 
                 let xmm0 = self.get_free_float_register(None);
                 let rax = self.get_free_register(None);
 
-                instructions.push(format!("movsd {xmm0}, {stack_member}"));
+                instructions.push(format!("mov{suffix} {xmm0}, {stack_member}"));
 
                 if is_ptr_deref {
                     instructions.push(format!("mov {rax}, [rbp - {}]", var_offset));
@@ -187,7 +252,7 @@ impl ASM {
                 }
 
                 if is_ptr_deref {
-                    instructions.push(format!("movsd [{rax}], {}", xmm0));
+                    instructions.push(format!("mov{suffix} [{rax}], {}", xmm0));
                 }
 
                 self.unlock_register_from_stack_value(&stack_member);
@@ -286,7 +351,13 @@ impl ASM {
                 let struct_member = borrow.iter().find(|x| x.name == *order).unwrap();
 
                 match &struct_member.member_type {
-                    VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 => {
+                    // a String is the pointer to its block
+                    VarType::Int
+                    | VarType::Int8
+                    | VarType::Int16
+                    | VarType::Int32
+                    | VarType::Enum(..)
+                    | VarType::OwnedStr => {
                         self.add_to_current_label(format!(
                             ";; Member name: {} Struct offset = {struct_offset}. Member offset: {}",
                             struct_member.name, struct_member.offset
@@ -295,7 +366,9 @@ impl ASM {
                         self.assign_local_number(struct_offset - struct_member.offset, &struct_member.member_type)
                     }
 
-                    VarType::Float => todo!(),
+                    VarType::Float | VarType::Float32 => {
+                        self.assign_local_float(struct_offset - struct_member.offset, &struct_member.member_type)
+                    }
 
                     VarType::Str => self.assign_local_string(struct_offset - struct_member.offset),
 
@@ -309,9 +382,11 @@ impl ASM {
                     }
 
                     VarType::Char => todo!(),
+                    VarType::Slice(_) => todo!(),
                     VarType::Struct(_, _) => todo!(),
                     VarType::Unknown => todo!(),
                     VarType::Function(_, _, _) => todo!(),
+                    VarType::Union(..) => todo!(),
                 };
             }
         } else {
@@ -319,6 +394,55 @@ impl ASM {
         }
     }
 
+    /// The tag is on top of the stack followed by the payload of the variant being assigned
+    fn assign_local_union(
+        &mut self,
+        union_offset: usize,
+        struct_assign_order: Option<Vec<&String>>,
+        union_name: &String,
+        variants: &Rc<RefCell<Vec<StructMemberType>>>,
+    ) {
+        let variant_name = match struct_assign_order {
+            Some(order) => order[0].clone(),
+            None => panic!("Need the variant being assigned to union {union_name}"),
+        };
+
+        let tag = self.stack_pop().unwrap();
+
+        self.extend_current_label(vec![
+            format!(";; Assigning local union {union_name}. Variant {variant_name}"),
+            format!("mov QWORD [rbp - {union_offset}], {tag}"),
+        ]);
+
+        self.unlock_register_from_stack_value(&tag);
+
+        let borrow = variants.borrow();
+        let variant = borrow.iter().find(|x| x.name == variant_name).unwrap();
+
+        let payload_offset = union_offset - variant.offset;
+
+        match &variant.member_type {
+            VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Enum(..) => {
+                self.assign_local_number(payload_offset, &variant.member_type)
+            }
+
+            VarType::Float | VarType::Float32 => self.assign_local_float(payload_offset, &variant.member_type),
+
+            VarType::Str => self.assign_local_string(payload_offset),
+
+            VarType::Ptr(inner_type) => self.assign_local_pointer(&inner_type, payload_offset, 0),
+
+            VarType::Char => todo!(),
+            VarType::OwnedStr => todo!(),
+            VarType::Array(_, _) => todo!(),
+            VarType::Slice(_) => todo!(),
+            VarType::Struct(_, _) => todo!(),
+            VarType::Function(_, _, _) => todo!(),
+            VarType::Union(..) => todo!(),
+            VarType::Unknown => todo!(),
+        };
+    }
+
     fn handle_local_eq_assignment(
         &mut self,
         ar_var: &Rc<RefCell<Variable>>,
@@ -346,7 +470,13 @@ impl ASM {
                         .unwrap();
 
                     match &variable_assigned_to.result_type {
-                        VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 => self.assign_local_number(
+                        VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::OwnedStr => self
+                            .assign_local_number(
+                                ar_var.borrow().offset - member.offset,
+                                &variable_assigned_to.result_type,
+                            ),
+
+                        VarType::Float | VarType::Float32 => self.assign_local_float(
                             ar_var.borrow().offset - member.offset,
                             &variable_assigned_to.result_type,
                         ),
@@ -358,31 +488,17 @@ impl ASM {
                 }
             }
 
-            VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 => {
-                self.assign_local_number(ar_var.borrow().offset, &ar_var.borrow().var_type)
-            }
-
-            VarType::Float => {
-                let stack_member = self.stack_pop().unwrap();
-
-                // let xmm0 = self.get_free_float_register(None);
-
-                // self.extend_current_label(vec![
-                //     format!(";; For assignemt of float var name '{}'", ar_var.borrow().var_name),
-                //     // rax contains the memory address of the floating point number
-                //     format!("mov {rax}, {}", stack_member),
-                //     format!("mov [rbp - {}], {rax}", ar_var.borrow().offset),
-                // ]);
-
-                self.extend_current_label(vec![format!(
-                    "movsd [rbp - {}], {stack_member}",
-                    ar_var.borrow().offset
-                )]);
-
-                trace!("stack_member: {stack_member}");
-
-                self.unlock_register_from_stack_value(&stack_member);
-                // self.unlock_register(xmm0);
+            // A String is the pointer to its block and a slice the pointer to its address and length
+            VarType::Int
+            | VarType::Int8
+            | VarType::Int16
+            | VarType::Int32
+            | VarType::Enum(..)
+            | VarType::OwnedStr
+            | VarType::Slice(_) => self.assign_local_number(ar_var.borrow().offset, &ar_var.borrow().var_type),
+
+            VarType::Float | VarType::Float32 => {
+                self.assign_local_float(ar_var.borrow().offset, &ar_var.borrow().var_type)
             }
 
             VarType::Str => self.assign_local_string(ar_var.borrow().offset),
@@ -412,13 +528,25 @@ impl ASM {
             }
 
             // Assignment to a pointer should be simple enough
-            VarType::Ptr(var_ptr_type) => {
-                self.assign_local_pointer(var_ptr_type, ar_var.borrow().offset, times_dereferenced)
-            }
+            VarType::Ptr(var_ptr_type) => match (&**var_ptr_type, variable_assigned_to.member_access.first()) {
+                (VarType::Struct(_, members), Some(member_name)) => {
+                    let borrowed = members.borrow();
+
+                    let member = borrowed.iter().find(|x| x.name == *member_name).unwrap();
+
+                    self.assign_local_struct_member_through_ptr(ar_var.borrow().offset, member)
+                }
+
+                _ => self.assign_local_pointer(var_ptr_type, ar_var.borrow().offset, times_dereferenced),
+            },
             VarType::Array(type_, size) => {
                 self.assign_local_array(ar_var.borrow().offset, &array_access_index, type_, size)
             }
 
+            VarType::Union(name, variants) => {
+                self.assign_local_union(ar_var.borrow().offset, struct_assign_order, name, variants)
+            }
+
             VarType::Unknown => todo!(),
             VarType::Function(_, _, _) => todo!(),
         }
@@ -433,7 +561,12 @@ impl ASM {
         let mut is_string = false;
 
         match &ar_var.borrow().var_type {
-            VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 => {
+            VarType::Int
+            | VarType::Int8
+            | VarType::Int16
+            | VarType::Int32
+            | VarType::Enum(..)
+            | VarType::OwnedStr => {
                 let stack_member = self.stack_pop().unwrap();
 
                 let rax = self.get_free_register(None);
@@ -498,24 +631,30 @@ impl ASM {
                     }
 
                     VarType::Str => todo!(),
+                    VarType::OwnedStr => todo!(),
 
                     VarType::Int8 => todo!(),
                     VarType::Int16 => todo!(),
                     VarType::Int32 => todo!(),
                     VarType::Array(..) => todo!(),
-                    VarType::Float => todo!(),
+                    VarType::Slice(_) => todo!(),
+                    VarType::Float | VarType::Float32 => todo!(),
                     VarType::Char => todo!(),
                     VarType::Ptr(_) => todo!(),
                     VarType::Unknown => todo!(),
                     VarType::Function(_, _, _) => todo!(),
+                    VarType::Enum(..) => todo!(),
+                    VarType::Union(..) => todo!(),
                 }
             }
 
             VarType::Array(..) => todo!(),
-            VarType::Float => todo!(),
+            VarType::Slice(_) => todo!(),
+            VarType::Float | VarType::Float32 => todo!(),
             VarType::Char => todo!(),
             VarType::Unknown => todo!(),
             VarType::Function(_, _, _) => todo!(),
+            VarType::Union(..) => todo!(),
         }
     }
 
@@ -575,12 +714,16 @@ impl ASM {
                 }
             }
 
-            VarType::Str => todo!(),
-            VarType::Float => todo!(),
+            // String += is appended before being assigned with =
+            VarType::Str | VarType::OwnedStr => todo!(),
+            VarType::Float | VarType::Float32 => todo!(),
             VarType::Char => todo!(),
             VarType::Ptr(_) => todo!(),
             VarType::Array(_, _) => todo!(),
+            VarType::Slice(_) => todo!(),
             VarType::Function(_, _, _) => todo!(),
+            VarType::Enum(..) => todo!(),
+            VarType::Union(..) => todo!(),
             VarType::Unknown => todo!(),
         }
     }
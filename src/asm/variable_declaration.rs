@@ -40,4 +40,17 @@ impl ASM {
             ),
         };
     }
+
+    /// A global whose value is known at compile time is written into the binary instead of being
+    /// assigned at startup. `const` globals can never be assigned, so they go in .rodata
+    pub fn constant_global_declaration(&mut self, var_name: &String, value: i64, is_const: bool) {
+        let declaration = format!("{} dq {}", var_name, value);
+
+        if is_const {
+            self.rodata.push(declaration);
+            self.constant_globals.insert(var_name.clone(), value);
+        } else {
+            self.data.push(declaration);
+        }
+    }
 }
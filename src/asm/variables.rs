@@ -40,7 +40,7 @@ impl ASM {
 
             v.extend(std::iter::repeat(format!("mov {rbx}, [{rbx}]")).take(variable.times_dereferenced));
 
-            if !matches!(**var_type, VarType::Float) {
+            if !var_type.is_float() {
                 let rax = self.get_free_register(None);
                 let rax_actual_name = var_type.get_register_name(rax);
 
@@ -55,7 +55,7 @@ impl ASM {
                 let xmm0 = self.get_free_float_register(None);
                 v.extend(vec![
                     format!("mov [float_imm], {rbx}"),
-                    format!("movsd {xmm0}, [float_imm]"),
+                    format!("mov{} {xmm0}, [float_imm]", var_type.get_float_suffix()),
                 ]);
                 self.stack_push(String::from(xmm0));
             }
@@ -132,15 +132,16 @@ impl ASM {
 
             // it's of the type
             // def var: *MyStruct = &another_var;
+            // so the value of the variable is the address of the struct
 
-            let first = &members.borrow()[0];
+            let instruction = if variable.store_address { "lea" } else { "mov" };
 
             self.extend_current_label(vec![
                 format!(
                     ";; Storing address of struct {} for variable {} in handle_local_ptr",
                     struct_name, variable.var_name
                 ),
-                format!("lea {rax}, [rbp - {}]", first.offset),
+                format!("{instruction} {rax}, [rbp - {}]", ar_var_offset),
             ]);
 
             self.stack_push(String::from(rax));
@@ -157,7 +158,7 @@ impl ASM {
             Some(struct_member_type) => match &struct_member_type.member_type {
                 // accessing an integer on a ptr to a structure
                 // We will dereference it automatically here
-                VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 => {
+                VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Enum(..) => {
                     let rax = self.get_free_register(None);
                     let rbx = self.get_free_register(None);
 
@@ -206,12 +207,15 @@ impl ASM {
 
                 VarType::Ptr(var_type) => self.handle_local_ptr(var_type, variable, struct_member_type.offset),
 
-                VarType::Float => todo!(),
+                VarType::Float | VarType::Float32 => todo!(),
+                VarType::OwnedStr => todo!(),
                 VarType::Char => todo!(),
                 VarType::Array(_, _) => todo!(),
+                VarType::Slice(_) => todo!(),
                 VarType::Struct(_, _) => todo!(),
                 VarType::Unknown => todo!(),
                 VarType::Function(_, _, _) => todo!(),
+                VarType::Union(..) => todo!(),
             },
 
             None => unreachable!(
@@ -255,7 +259,7 @@ impl ASM {
 
     fn handle_local_ptr(&mut self, var_type: &Box<VarType>, variable: &RequiredVarFields, ar_var_offset: usize) {
         match *var_type.clone() {
-            VarType::Int | VarType::Int16 | VarType::Int32 | VarType::Int8 | VarType::Float => {
+            VarType::Int | VarType::Int16 | VarType::Int32 | VarType::Int8 | VarType::Float | VarType::Float32 => {
                 self.handle_local_ptr_int_float(var_type, variable, ar_var_offset)
             }
 
@@ -267,6 +271,15 @@ impl ASM {
                 self.handle_local_ptr_struct(var_type, variable, ar_var_offset, &struct_name, members)
             }
 
+            // the value of the pointer is the address of the union
+            VarType::Union(..) => {
+                let rax = self.get_free_register(None);
+
+                self.extend_current_label(vec![format!("mov {rax}, [rbp - {}]", ar_var_offset)]);
+
+                self.stack_push(String::from(rax));
+            }
+
             type_ => {
                 todo!("var_type '{type_}' not handled")
             }
@@ -347,15 +360,19 @@ impl ASM {
             VarType::Int16 => todo!(),
             VarType::Int32 => todo!(),
 
-            VarType::Float => todo!(),
+            VarType::Float | VarType::Float32 => todo!(),
             VarType::Struct(_, _) => todo!(),
 
             VarType::Str => todo!(),
+            VarType::OwnedStr => todo!(),
             VarType::Char => todo!(),
             VarType::Ptr(_) => todo!(),
             VarType::Array(_, _) => todo!(),
+            VarType::Slice(_) => todo!(),
             VarType::Unknown => todo!(),
             VarType::Function(_, _, _) => todo!(),
+            VarType::Enum(..) => todo!(),
+            VarType::Union(..) => todo!(),
         }
     }
 
@@ -495,7 +512,12 @@ impl ASM {
         let var_name = &variable.var_name;
 
         match variable.var_type {
-            VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 => {
+            VarType::Int
+            | VarType::Int8
+            | VarType::Int16
+            | VarType::Int32
+            | VarType::Enum(..)
+            | VarType::OwnedStr => {
                 let rax = self.get_free_register(None);
                 let rax_actual_name = variable.var_type.get_register_name(rax);
 
@@ -524,7 +546,7 @@ impl ASM {
                 }
             }
 
-            VarType::Float => todo!(),
+            VarType::Float | VarType::Float32 => todo!(),
             VarType::Char => todo!(),
 
             VarType::Int8 => todo!(),
@@ -534,9 +556,12 @@ impl ASM {
             VarType::Ptr(_) => self.handle_global_ptr(variable, &ar_var.borrow()),
 
             VarType::Array(..) => todo!(),
+            VarType::Slice(_) => todo!(),
             VarType::Unknown => todo!(),
             VarType::Struct(_, _) => todo!(),
             VarType::Function(_, _, _) => todo!(),
+            VarType::Enum(..) => todo!(),
+            VarType::Union(..) => todo!(),
         }
     }
 
@@ -559,7 +584,14 @@ impl ASM {
 
         // cannot use ar_var here as it does not have the computed types
         match &variable.var_type {
-            VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Float => {
+            VarType::Int
+            | VarType::Int8
+            | VarType::Int16
+            | VarType::Int32
+            | VarType::Float
+            | VarType::Float32
+            | VarType::Enum(..)
+            | VarType::OwnedStr => {
                 self.handle_local_int_float(variable, ar_var.borrow().offset, &variable.var_type)
             }
 
@@ -572,6 +604,11 @@ impl ASM {
 
             VarType::Array(var_type, _) => self.handle_asm_for_array(var_type, variable, &ar_var.borrow()),
 
+            VarType::Slice(var_type) => match variable.array_aceess_index {
+                Some(_) => self.slice_index(var_type, &format!("[rbp - {}]", ar_var.borrow().offset)),
+                None => self.handle_local_int_float(variable, ar_var.borrow().offset, &variable.var_type),
+            },
+
             VarType::Struct(struct_name, member_access) => {
                 let first = &member_access.borrow()[0];
 
@@ -603,12 +640,18 @@ impl ASM {
 
                 match found {
                     Some(struct_member_type) => match &struct_member_type.member_type {
-                        VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Float => self
-                            .handle_local_int_float(
-                                variable,
-                                ar_var.borrow().offset - struct_member_type.offset,
-                                &struct_member_type.member_type,
-                            ),
+                        VarType::Int
+                        | VarType::Int8
+                        | VarType::Int16
+                        | VarType::Int32
+                        | VarType::Float
+                        | VarType::Float32
+                        | VarType::Enum(..)
+                        | VarType::OwnedStr => self.handle_local_int_float(
+                            variable,
+                            ar_var.borrow().offset - struct_member_type.offset,
+                            &struct_member_type.member_type,
+                        ),
 
                         VarType::Str => {
                             self.handle_local_str(variable, ar_var.borrow().offset - struct_member_type.offset)
@@ -623,9 +666,11 @@ impl ASM {
                         VarType::Char => todo!(),
 
                         VarType::Array(_, _) => todo!(),
+                        VarType::Slice(_) => todo!(),
                         VarType::Struct(_, _) => todo!(),
                         VarType::Unknown => todo!(),
                         VarType::Function(_, _, _) => todo!(),
+                        VarType::Union(..) => todo!(),
                     },
 
                     None => unreachable!(
@@ -639,6 +684,18 @@ impl ASM {
                 self.handle_local_function_pointer(variable, ar_var);
             }
 
+            // Same as a struct, the union is referred to by its address
+            VarType::Union(union_name, _) => {
+                let rax = self.get_free_register(None);
+
+                self.extend_current_label(vec![
+                    format!(";; Storing address of union {} for variable {}", union_name, variable.var_name),
+                    format!("lea {rax}, [rbp - {}]", ar_var.borrow().offset),
+                ]);
+
+                self.stack_push(String::from(rax));
+            }
+
             VarType::Unknown => todo!(),
         }
     }
@@ -8,21 +8,25 @@ use std::{
 use crate::{
     asm::asm::ASM,
     interpreter::interpreter::{Functions, Variables},
+    json::Json,
     lexer::{
         lexer::Token,
         tokens::{AllOperations, TokenEnum},
         types::VarType,
     },
-    semantic_analyzer::semantic_analyzer::CallStack,
+    lsp::analysis::source_type_name,
+    semantic_analyzer::{control_flow::ControlFlowGraph, semantic_analyzer::CallStack},
     trace,
+    types::ASTNode,
 };
 
 use super::{
     array::Array, assignment_statement::AssignmentStatement, ast_loop::Loop, binary_op::BinaryOP,
     comparison_exp::ComparisonExp, conditionals::ConditionalStatement, declaration_statement::DeclarationStatement,
-    factor::Factor, function_call::FunctionCall, function_def::FunctionDefinition, jump::Jump,
-    logical_exp::LogicalExpression, memory_alloc::MemoryAlloc, program::Program, structs::StructDecleration,
-    variable::Variable,
+    factor::Factor, function_call::FunctionCall, function_def::FunctionDefinition, generic_function::GenericFunction,
+    jump::Jump,
+    logical_exp::LogicalExpression, match_statement::MatchStatement, memory_alloc::MemoryAlloc, program::Program, structs::StructDecleration,
+    type_cast::TypeCast, union_variant::UnionVariant, variable::Variable,
 };
 
 #[derive(Debug)]
@@ -45,7 +49,7 @@ pub trait AST {
 
 impl Debug for dyn AST {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:#?}", self.print())
+        write!(f, "{:#?}", self.get_node())
     }
 }
 
@@ -66,6 +70,10 @@ pub enum ASTNodeEnumMut<'a> {
     MemoryAlloc(&'a mut MemoryAlloc),
     Array(&'a mut Array),
     Struct(&'a mut StructDecleration),
+    Match(&'a mut MatchStatement),
+    UnionVariant(&'a mut UnionVariant),
+    GenericFunction(&'a mut GenericFunction),
+    TypeCast(&'a mut TypeCast),
 }
 
 pub enum ASTNodeEnum<'a> {
@@ -85,6 +93,12 @@ pub enum ASTNodeEnum<'a> {
     MemoryAlloc(&'a MemoryAlloc),
     Array(&'a Array),
     Struct(&'a StructDecleration),
+    Match(&'a MatchStatement),
+    UnionVariant(&'a UnionVariant),
+    GenericFunction(&'a GenericFunction),
+    TypeCast(&'a TypeCast),
+    /// Definitions of types, which only matter to the parser
+    Void,
 }
 
 impl<'a> Display for ASTNodeEnumMut<'a> {
@@ -106,6 +120,10 @@ impl<'a> Display for ASTNodeEnumMut<'a> {
             ASTNodeEnumMut::MemoryAlloc(_) => "MemoryAlloc",
             ASTNodeEnumMut::Array(_) => "Array",
             ASTNodeEnumMut::Struct(_) => "Struct",
+            ASTNodeEnumMut::Match(_) => "Match",
+            ASTNodeEnumMut::UnionVariant(_) => "UnionVariant",
+            ASTNodeEnumMut::GenericFunction(_) => "GenericFunction",
+            ASTNodeEnumMut::TypeCast(_) => "TypeCast",
         };
 
         write!(f, "{}", name)
@@ -137,6 +155,11 @@ impl<'a> Display for ASTNodeEnum<'a> {
             ASTNodeEnum::MemoryAlloc(_) => "MemoryAlloc",
             ASTNodeEnum::Array(_) => "Array",
             ASTNodeEnum::Struct(_) => "Struct",
+            ASTNodeEnum::Match(_) => "Match",
+            ASTNodeEnum::UnionVariant(_) => "UnionVariant",
+            ASTNodeEnum::GenericFunction(_) => "GenericFunction",
+            ASTNodeEnum::TypeCast(_) => "TypeCast",
+            ASTNodeEnum::Void => "Void",
         };
 
         write!(f, "{}", name)
@@ -162,6 +185,11 @@ impl<'a> Debug for ASTNodeEnum<'a> {
             ASTNodeEnum::MemoryAlloc(a) => write!(f, "Name: MemoryAlloc {:#?}", a),
             ASTNodeEnum::Array(a) => write!(f, "Name: Array {:#?}", a),
             ASTNodeEnum::Struct(a) => write!(f, "Name: Struct {:#?}", a),
+            ASTNodeEnum::Match(a) => write!(f, "Name: Match {:#?}", a),
+            ASTNodeEnum::UnionVariant(a) => write!(f, "Name: UnionVariant {:#?}", a),
+            ASTNodeEnum::GenericFunction(a) => write!(f, "Name: GenericFunction {:#?}", a),
+            ASTNodeEnum::TypeCast(a) => write!(f, "Name: TypeCast {:#?}", a),
+            ASTNodeEnum::Void => write!(f, "Name: Void"),
         }
     }
 }
@@ -211,6 +239,9 @@ impl<'a> ASTNodeEnum<'a> {
             (ComparisonExp(a), Factor(b)) => a.get_type().0.figure_out_type(&b.get_type().0, op),
             (Factor(a), ComparisonExp(b)) => a.get_type().0.figure_out_type(&b.get_type().0, op),
 
+            (TypeCast(a), b) => a.result_type.figure_out_type(&b.get_actual_type(), op),
+            (a, TypeCast(b)) => a.get_actual_type().figure_out_type(&b.result_type, op),
+
             (a, b) => unreachable!("This must be a bug in the parsing step. {a} and {b} not handled"),
         }
     }
@@ -262,10 +293,54 @@ impl<'a> ASTNodeEnum<'a> {
                 (actual_type.can_assign(&variable.get_type().0), actual_type)
             }
 
+            UnionVariant(f) => {
+                let (actual_type, result_type) = f.get_type();
+                (actual_type.can_assign(&variable.get_type().0), actual_type)
+            }
+
+            TypeCast(f) => {
+                let (actual_type, result_type) = f.get_type();
+                (actual_type.can_assign(&variable.get_type().0), actual_type)
+            }
+
             node => unreachable!("Cannot assign a variable to {node}. This could a bug in the parsing stage"),
         };
     }
 
+    /// Whether this makes a new String that nothing else points to, which can be freed as soon as
+    /// it's been used
+    pub fn is_temporary_string(&self) -> bool {
+        match self {
+            ASTNodeEnum::Variable(node) => node.slice_end.is_some(),
+
+            ASTNodeEnum::TypeCast(node) => match node.from_type {
+                VarType::OwnedStr => node.expression.borrow().get_node().is_temporary_string(),
+                _ => matches!(node.result_type, VarType::OwnedStr),
+            },
+
+            ASTNodeEnum::BinaryOp(..) | ASTNodeEnum::FunctionCall(..) => {
+                matches!(self.get_actual_type(), VarType::OwnedStr)
+            }
+
+            _ => false,
+        }
+    }
+
+    /// Type of an expression after all dereferences have been applied
+    pub fn get_actual_type(&self) -> VarType {
+        match self {
+            ASTNodeEnum::Factor(node) => node.get_type().0,
+            ASTNodeEnum::BinaryOp(node) => node.get_type().0,
+            ASTNodeEnum::ComparisonExp(node) => node.get_type().0,
+            ASTNodeEnum::FunctionCall(node) => node.get_type().0,
+            ASTNodeEnum::LogicalExp(node) => node.get_type().0,
+            ASTNodeEnum::Variable(node) => node.get_type().0,
+            ASTNodeEnum::TypeCast(node) => node.get_type().0,
+
+            node => unreachable!("{node} is not an expression"),
+        }
+    }
+
     pub fn get_result_type(&self) -> &VarType {
         match self {
             ASTNodeEnum::Factor(node) => &node.result_type,
@@ -277,6 +352,8 @@ impl<'a> ASTNodeEnum<'a> {
             ASTNodeEnum::MemoryAlloc(node) => &node.result_type,
             ASTNodeEnum::Jump(node) => &node.result_type,
             ASTNodeEnum::Array(node) => &node.result_type,
+            ASTNodeEnum::UnionVariant(node) => &node.result_type,
+            ASTNodeEnum::TypeCast(node) => &node.result_type,
 
             ASTNodeEnum::Struct(_) => todo!(),
             ASTNodeEnum::AssignmentStatement(_) => todo!(),
@@ -284,7 +361,210 @@ impl<'a> ASTNodeEnum<'a> {
             ASTNodeEnum::Conditionals(_) => todo!(),
             ASTNodeEnum::DeclarationStatement(_) => todo!(),
             ASTNodeEnum::FunctionDef(_) => todo!(),
+            ASTNodeEnum::GenericFunction(_) => todo!(),
             ASTNodeEnum::Program(_) => todo!(),
+            ASTNodeEnum::Match(_) => todo!(),
+            ASTNodeEnum::Void => todo!(),
+        }
+    }
+
+    /// Adds the names of the variables and functions this node, and everything under it, uses and
+    /// declares to `names`. Names used inside a function that the function declares itself are left
+    /// out, everything else can still be a local variable
+    pub fn used_names(&self, names: &mut Names) {
+        match self {
+            ASTNodeEnum::AssignmentStatement(node) => node.used_names(names),
+            ASTNodeEnum::Loop(node) => node.used_names(names),
+            ASTNodeEnum::BinaryOp(node) => node.used_names(names),
+            ASTNodeEnum::ComparisonExp(node) => node.used_names(names),
+            ASTNodeEnum::Conditionals(node) => node.used_names(names),
+            ASTNodeEnum::DeclarationStatement(node) => node.used_names(names),
+            ASTNodeEnum::FunctionCall(node) => node.used_names(names),
+            ASTNodeEnum::FunctionDef(node) => node.used_names(names),
+            ASTNodeEnum::Jump(node) => node.used_names(names),
+            ASTNodeEnum::LogicalExp(node) => node.used_names(names),
+            ASTNodeEnum::Program(node) => node.used_names(names),
+            ASTNodeEnum::Variable(node) => node.used_names(names),
+            ASTNodeEnum::MemoryAlloc(node) => node.used_names(names),
+            ASTNodeEnum::Array(node) => node.used_names(names),
+            ASTNodeEnum::Struct(node) => node.used_names(names),
+            ASTNodeEnum::Match(node) => node.used_names(names),
+            ASTNodeEnum::UnionVariant(node) => node.used_names(names),
+            ASTNodeEnum::TypeCast(node) => node.used_names(names),
+
+            // the instances are functions of their own
+            ASTNodeEnum::Factor(_) | ASTNodeEnum::GenericFunction(_) | ASTNodeEnum::Void => {}
+        }
+    }
+
+    /// What `--emit=ast-json` writes about this node besides its kind, type and span, with the
+    /// nodes under it written by `ast_json`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        match self {
+            ASTNodeEnum::AssignmentStatement(node) => node.json_fields(),
+            ASTNodeEnum::Loop(node) => node.json_fields(),
+            ASTNodeEnum::BinaryOp(node) => node.json_fields(),
+            ASTNodeEnum::ComparisonExp(node) => node.json_fields(),
+            ASTNodeEnum::Conditionals(node) => node.json_fields(),
+            ASTNodeEnum::DeclarationStatement(node) => node.json_fields(),
+            ASTNodeEnum::Factor(node) => node.json_fields(),
+            ASTNodeEnum::FunctionCall(node) => node.json_fields(),
+            ASTNodeEnum::FunctionDef(node) => node.json_fields(),
+            ASTNodeEnum::Jump(node) => node.json_fields(),
+            ASTNodeEnum::LogicalExp(node) => node.json_fields(),
+            ASTNodeEnum::Program(node) => node.json_fields(),
+            ASTNodeEnum::Variable(node) => node.json_fields(),
+            ASTNodeEnum::MemoryAlloc(node) => node.json_fields(),
+            ASTNodeEnum::Array(node) => node.json_fields(),
+            ASTNodeEnum::Struct(node) => node.json_fields(),
+            ASTNodeEnum::Match(node) => node.json_fields(),
+            ASTNodeEnum::UnionVariant(node) => node.json_fields(),
+            ASTNodeEnum::GenericFunction(node) => node.json_fields(),
+            ASTNodeEnum::TypeCast(node) => node.json_fields(),
+            ASTNodeEnum::Void => vec![],
+        }
+    }
+
+    /// Adds this statement to the control flow graph of the function it's in. Anything that
+    /// doesn't branch or declare a variable is an expression, and only reads variables
+    pub fn add_to_cfg(&self, cfg: &mut ControlFlowGraph) {
+        match self {
+            ASTNodeEnum::AssignmentStatement(node) => node.add_to_cfg(cfg),
+            ASTNodeEnum::Loop(node) => node.add_to_cfg(cfg),
+            ASTNodeEnum::Conditionals(node) => node.add_to_cfg(cfg),
+            ASTNodeEnum::DeclarationStatement(node) => node.add_to_cfg(cfg),
+            ASTNodeEnum::Jump(node) => node.add_to_cfg(cfg),
+            ASTNodeEnum::Program(node) => node.add_to_cfg(cfg),
+            ASTNodeEnum::MemoryAlloc(node) => node.add_to_cfg(cfg),
+            ASTNodeEnum::Match(node) => node.add_to_cfg(cfg),
+
+            ASTNodeEnum::FunctionDef(_) | ASTNodeEnum::GenericFunction(_) | ASTNodeEnum::Void => {}
+
+            expression => {
+                let mut names = Names::default();
+                expression.used_names(&mut names);
+
+                cfg.add_names(names);
+            }
         }
     }
 }
+
+/// See `ASTNodeEnum::add_to_cfg`
+pub fn add_to_cfg(node: &ASTNode, cfg: &mut ControlFlowGraph) {
+    node.borrow().get_node().add_to_cfg(cfg);
+}
+
+/// Variables and functions used and variables declared by a part of the AST
+#[derive(Debug, Default)]
+pub struct Names {
+    /// Every use of a name and where it is
+    pub used: Vec<(String, Token)>,
+    pub declared: Vec<String>,
+    /// Variables whose address is taken with `&`, these are in `used` as well
+    pub addressed: Vec<String>,
+}
+
+/// See `ASTNodeEnum::used_names`
+pub fn used_names(node: &ASTNode, names: &mut Names) {
+    node.borrow().get_node().used_names(names);
+}
+
+/// A node for `--emit=ast-json`: its kind, its type, where it's written and its fields. The span
+/// covers its own token and the nodes under it that are in the same file
+pub fn ast_json(node: &dyn AST) -> Json {
+    let node_enum = node.get_node();
+    let fields = node_enum.json_fields();
+
+    // neither has a token, and type definitions don't have a type either
+    let (mut tokens, result_type) = match node_enum {
+        ASTNodeEnum::Void => (vec![], VarType::Unknown),
+        ASTNodeEnum::Program(_) => (vec![], node.get_type().1),
+        _ => (vec![node.get_token()], node.get_type().1),
+    };
+
+    // the token of a call is the bracket after the name
+    if let ASTNodeEnum::FunctionCall(call) = &node_enum {
+        tokens.push(call.name_token());
+    }
+
+    let mut spans = vec![];
+
+    // tokens the parser made up aren't anywhere in the file
+    for token in tokens.into_iter().filter(|token| token.index > token.start) {
+        spans.push((token.file.clone(), token.line_number, token.start, token.index));
+    }
+
+    for (_, value) in &fields {
+        child_spans(value, &mut spans);
+    }
+
+    let span = match spans.first() {
+        Some((file, ..)) => {
+            let spans: Vec<_> = spans.iter().filter(|span| &span.0 == file).collect();
+            let (_, line, start, _) = spans.iter().min_by_key(|span| span.2).unwrap();
+            let end = spans.iter().map(|span| span.3).max().unwrap();
+
+            Json::object([
+                ("file", file.as_str().into()),
+                ("line", (*line).into()),
+                ("start", (*start).into()),
+                ("end", end.into()),
+            ])
+        }
+
+        None => Json::Null,
+    };
+
+    let mut members = vec![
+        ("kind".to_string(), node_enum.to_string().into()),
+        ("type".to_string(), type_json(&result_type)),
+        ("span".to_string(), span),
+    ];
+
+    members.extend(fields.into_iter().map(|(key, value)| (key.to_string(), value)));
+
+    Json::Object(members)
+}
+
+/// The type the way it's written in a program, or null for statements, which don't have one
+pub fn type_json(var_type: &VarType) -> Json {
+    match var_type {
+        VarType::Unknown => Json::Null,
+        var_type => source_type_name(var_type).into(),
+    }
+}
+
+/// See `ast_json`
+pub fn node_json(node: &ASTNode) -> Json {
+    ast_json(&**node.borrow())
+}
+
+/// `node_json` of the node, or null if there isn't one
+pub fn optional_node_json(node: &Option<ASTNode>) -> Json {
+    node.as_ref().map_or(Json::Null, node_json)
+}
+
+/// (file, line, start, end) of the nodes in `json` that aren't inside another node
+fn child_spans(json: &Json, spans: &mut Vec<(String, usize, usize, usize)>) {
+    match json {
+        // a node, its span covers everything under it already
+        Json::Object(_) if json.get("kind").as_str().is_some() => {
+            let span = json.get("span");
+
+            if let (Some(file), Some(line), Some(start), Some(end)) = (
+                span.get("file").as_str(),
+                span.get("line").as_u64(),
+                span.get("start").as_u64(),
+                span.get("end").as_u64(),
+            ) {
+                spans.push((file.into(), line as usize, start as usize, end as usize));
+            }
+        }
+
+        Json::Object(members) => members.iter().for_each(|(_, value)| child_spans(value, spans)),
+        Json::Array(values) => values.iter().for_each(|value| child_spans(value, spans)),
+
+        _ => {}
+    }
+}
@@ -1,6 +1,7 @@
 use core::panic;
 use std::{cell::RefCell, rc::Rc};
 
+use crate::json::Json;
 use crate::{
     asm::asm::ASM,
     helpers::compiler_error,
@@ -11,7 +12,7 @@ use crate::{
     types::ASTNode,
 };
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+use super::abstract_syntax_tree::{node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST};
 
 #[derive(Debug)]
 pub struct Array {
@@ -31,6 +32,10 @@ impl Array {
         }
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
     fn get_member_type(member: &ASTNode) -> VarType {
         return match member.borrow().get_node() {
             ASTNodeEnum::BinaryOp(node) => node.result_type.clone(),
@@ -40,12 +45,28 @@ impl Array {
             ASTNodeEnum::LogicalExp(node) => node.result_type.clone(),
             ASTNodeEnum::Variable(node) => node.result_type.clone(),
             ASTNodeEnum::MemoryAlloc(node) => node.result_type.clone(),
+            ASTNodeEnum::TypeCast(node) => node.result_type.clone(),
 
             _ => {
                 panic!("");
             }
         };
     }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        for member in &self.members {
+            used_names(member, names);
+        }
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![(
+            "members",
+            self.members.iter().map(node_json).collect::<Vec<Json>>().into(),
+        )]
+    }
 }
 
 impl AST for Array {
@@ -88,7 +109,7 @@ impl AST for Array {
     }
 
     fn get_token(&self) -> &Token {
-        todo!()
+        &self.token
     }
 
     fn get_node(&self) -> ASTNodeEnum {
@@ -1,21 +1,25 @@
 use crate::helpers::compiler_error;
+use crate::json::Json;
 use crate::lexer::types::VarType;
 use crate::{helpers, trace};
 use crate::{lexer::tokens::AssignmentTypes, types::ASTNode};
 
-use crate::semantic_analyzer::semantic_analyzer::CallStack;
+use crate::semantic_analyzer::{control_flow::ControlFlowGraph, semantic_analyzer::CallStack};
 
 use core::panic;
 use std::process::exit;
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    asm::asm::ASM,
-    interpreter::interpreter::{Functions, Variables},
-    lexer::tokens::{TokenEnum, VariableEnum},
+    asm::{asm::ASM, string_functions::StringArg},
+    interpreter::interpreter::{variable_value, Functions, Variables},
+    lexer::tokens::{Number, TokenEnum, VariableEnum},
 };
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+use super::abstract_syntax_tree::{
+    ast_json, node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST,
+};
+use super::factor::coerce_float_literal;
 use super::variable::Variable;
 
 #[derive(Debug)]
@@ -56,10 +60,104 @@ impl AssignmentStatement {
             );
         }
     }
+
+    fn is_string_append(&self) -> bool {
+        matches!(self.left.result_type, VarType::OwnedStr) && !matches!(self.assignment_type, AssignmentTypes::Equals)
+    }
+
+    /// string += other. Only appending is defined and the right side has to be a str or a String
+    fn verify_string_append(&self) {
+        let rhs_type = self.right.borrow().get_type().0;
+
+        if let AssignmentTypes::MinusEquals = self.assignment_type {
+            compiler_error(
+                format!("'-=' not defined for '{}'", self.left.result_type),
+                self.left.get_token(),
+            );
+        }
+
+        if !matches!(rhs_type, VarType::Str | VarType::OwnedStr) {
+            compiler_error(
+                format!("Cannot append '{rhs_type}' to '{}'", self.left.result_type),
+                self.left.get_token(),
+            );
+        }
+    }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        self.left.used_names(names);
+        used_names(&self.right, names);
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        let operator = match self.assignment_type {
+            AssignmentTypes::Equals => "=",
+            AssignmentTypes::PlusEquals => "+=",
+            AssignmentTypes::MinusEquals => "-=",
+        };
+
+        vec![
+            ("left", ast_json(&self.left)),
+            ("operator", operator.into()),
+            ("right", node_json(&self.right)),
+        ]
+    }
+
+    /// See `ASTNodeEnum::add_to_cfg`
+    pub fn add_to_cfg(&self, cfg: &mut ControlFlowGraph) {
+        cfg.read(&self.right);
+
+        let left = &self.left;
+
+        let assigns_whole_variable = matches!(self.assignment_type, AssignmentTypes::Equals)
+            && !left.dereference
+            && left.array_aceess_index.is_none()
+            && left.member_access.is_empty();
+
+        if assigns_whole_variable {
+            cfg.write(left);
+            return;
+        }
+
+        // a[i] = 5, a.b = 5 or a += 5 need a to have a value already
+        let mut names = Names::default();
+        left.used_names(&mut names);
+
+        cfg.add_names(names);
+    }
 }
 
 impl AST for AssignmentStatement {
     fn visit_com(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
+        // Appending might move the String, so the result is assigned back to the variable
+        if self.is_string_append() {
+            asm.function_call_prep();
+
+            self.left.visit_com(v, f.clone(), asm, call_stack);
+            self.right.borrow().visit_com(v, f, asm, call_stack);
+
+            let right = self.right.borrow();
+
+            asm.string_append(
+                StringArg::new(&right.get_type().0, right.get_node().is_temporary_string()),
+                call_stack,
+            );
+
+            asm.variable_assignment(
+                &self.left.var_name,
+                &AssignmentTypes::Equals,
+                call_stack,
+                self.left.times_dereferenced,
+                &self.left.array_aceess_index,
+                None,
+                &self.left,
+            );
+
+            return;
+        }
+
         self.right.borrow().visit_com(v, f.clone(), asm, call_stack);
 
         // Only this is required for arrays. Not actually calling the visit_com function for the
@@ -72,13 +170,20 @@ impl AST for AssignmentStatement {
             }
         }
 
+        let borrow = self.right.borrow();
+
+        let member_order = match borrow.get_node() {
+            ASTNodeEnum::UnionVariant(u) => Some(vec![&u.variant_name]),
+            _ => None,
+        };
+
         asm.variable_assignment(
             &self.left.var_name,
             &self.assignment_type,
             call_stack,
             self.left.times_dereferenced,
             &self.left.array_aceess_index,
-            None,
+            member_order,
             &self.left,
         );
     }
@@ -86,23 +191,42 @@ impl AST for AssignmentStatement {
     // TODO: change this so that the expression is stored here and we need to visit the varible
     // to evaluate the value
     fn visit(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
-        let right_visit = self.right.borrow().visit(v, f, call_stack);
+        let right_visit = self.right.borrow().visit(v, Rc::clone(&f), call_stack);
 
-        match &*right_visit.token {
-            TokenEnum::StringLiteral(s) => {
-                v.insert(self.left.var_name.clone(), VariableEnum::String(s.into()));
-            }
+        // only to check the variable can be interpreted, `a.b = 1` can't
+        self.left.visit(v, f, call_stack);
 
-            TokenEnum::Number(n) => {
-                v.insert(self.left.var_name.clone(), VariableEnum::Number(n.clone()));
-            }
+        let token = self.left.get_token();
+        let value = variable_value(&right_visit.token, token);
+
+        let value = match (&self.assignment_type, &v[&self.left.var_name], value) {
+            (AssignmentTypes::Equals, _, value) => value,
 
-            TokenEnum::Variable(_) => todo!(),
+            (assignment_type, VariableEnum::Number(current), VariableEnum::Number(change)) => {
+                let sign = if let AssignmentTypes::PlusEquals = assignment_type {
+                    1
+                } else {
+                    -1
+                };
+
+                VariableEnum::Number(match (current, change) {
+                    (Number::Integer(current), Number::Integer(change)) => Number::Integer(current + sign * change),
+                    (Number::Float(current), Number::Float(change)) => Number::Float(current + sign as f64 * change),
+
+                    _ => {
+                        compiler_error("Cannot add Float and Integer", token);
+                        exit(1);
+                    }
+                })
+            }
 
             _ => {
-                panic!("Variable value is not a Number, String or Variable");
+                compiler_error("The interpreter can only add to and subtract from numbers", token);
+                exit(1);
             }
-        }
+        };
+
+        v.insert(self.left.var_name.clone(), value);
 
         return VisitResult {
             token: right_visit.token,
@@ -110,7 +234,7 @@ impl AST for AssignmentStatement {
     }
 
     fn get_token(&self) -> &crate::lexer::lexer::Token {
-        todo!()
+        self.left.get_token()
     }
 
     fn print(&self) {
@@ -121,6 +245,8 @@ impl AST for AssignmentStatement {
         self.right.borrow_mut().semantic_visit(call_stack, f.clone());
         self.left.semantic_visit(call_stack, f);
 
+        coerce_float_literal(&self.right, &self.left.get_type().0);
+
         if self.left.is_const {
             compiler_error(
                 format!("Cannot reassign constant variable {}", self.left.var_name),
@@ -128,6 +254,11 @@ impl AST for AssignmentStatement {
             );
         }
 
+        if self.is_string_append() {
+            self.verify_string_append();
+            return;
+        }
+
         self.verify_type();
     }
 
@@ -1,11 +1,16 @@
 use crate::helpers::compiler_error;
+use crate::json::Json;
+use crate::lexer::keywords::{BREAK, CONTINUE, RETURN};
 use crate::lexer::lexer::Token;
 use crate::lexer::tokens::VariableEnum;
 use crate::lexer::types::VarType;
 use crate::types::ASTNode;
 use crate::{helpers, trace};
 
-use crate::semantic_analyzer::semantic_analyzer::{ActivationRecord, ActivationRecordType, CallStack};
+use crate::semantic_analyzer::{
+    control_flow::ControlFlowGraph,
+    semantic_analyzer::{ActivationRecord, ActivationRecordType, CallStack},
+};
 
 use crate::{
     asm::asm::ASM,
@@ -14,9 +19,13 @@ use crate::{
 };
 use core::panic;
 use std::process::exit;
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+use super::abstract_syntax_tree::{
+    add_to_cfg, ast_json, node_json, optional_node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult,
+    AST,
+};
+use super::factor::constant_int;
 use super::variable::Variable;
 
 #[derive(Debug)]
@@ -29,6 +38,8 @@ pub struct Loop {
     with_var: Option<Rc<RefCell<Variable>>>,
     block: ASTNode,
     loop_number: usize,
+    /// The `loop` keyword
+    token: Token,
 }
 
 impl Loop {
@@ -39,8 +50,10 @@ impl Loop {
         with_var: Option<Rc<RefCell<Variable>>>,
         block: ASTNode,
         loop_number: usize,
+        token: Token,
     ) -> Self {
         Self {
+            token,
             from_range,
             to_range,
             step_by,
@@ -49,10 +62,105 @@ impl Loop {
             loop_number,
         }
     }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        for node in [&self.from_range, &self.to_range, &self.step_by].into_iter().flatten() {
+            used_names(node, names);
+        }
+
+        if let Some(with_var) = &self.with_var {
+            names.declared.push(with_var.borrow().var_name.clone());
+        }
+
+        used_names(&self.block, names);
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("from", optional_node_json(&self.from_range)),
+            ("to", optional_node_json(&self.to_range)),
+            ("step", optional_node_json(&self.step_by)),
+            (
+                "with",
+                self.with_var
+                    .as_ref()
+                    .map_or(Json::Null, |var| ast_json(&*var.borrow())),
+            ),
+            ("block", node_json(&self.block)),
+        ]
+    }
+
+    /// See `ASTNodeEnum::add_to_cfg`
+    pub fn add_to_cfg(&self, cfg: &mut ControlFlowGraph) {
+        for node in [&self.from_range, &self.to_range, &self.step_by].into_iter().flatten() {
+            cfg.read(node);
+        }
+
+        let before = cfg.current();
+        let header = cfg.new_block();
+        let after = cfg.new_block();
+
+        let is_infinite = self.from_range.is_none();
+
+        // `loop from 0 to 10` runs at least once, a range only known at runtime could be empty
+        let runs_at_least_once = match (&self.from_range, &self.to_range) {
+            (Some(from), Some(to)) => matches!(
+                (constant_int(from, &HashMap::new()), constant_int(to, &HashMap::new())),
+                (Some(from), Some(to)) if from < to
+            ),
+
+            _ => true,
+        };
+
+        let body = cfg.new_block();
+
+        cfg.add_edge(before, if runs_at_least_once { body } else { header });
+        cfg.add_edge(header, body);
+
+        if !is_infinite {
+            cfg.add_edge(header, after);
+        }
+
+        cfg.set_current(body);
+
+        if let Some(with_var) = &self.with_var {
+            cfg.declare(&with_var.borrow(), true);
+        }
+
+        cfg.push_loop(header, after);
+        add_to_cfg(&self.block, cfg);
+        cfg.pop_loop();
+
+        cfg.add_edge(cfg.current(), header);
+        cfg.set_current(after);
+    }
 }
 
 impl Loop {
+    /// Called by the interpreter after each run of the block. Returns whether the loop is over,
+    /// because of a `break` or a `return`
+    fn iteration_ended(&self, v: &mut Variables) -> bool {
+        v.remove(CONTINUE);
+
+        v.remove(BREAK).is_some() || v.contains_key(RETURN)
+    }
+
     fn add_call_stack(&self, call_stack: &mut CallStack) {
+        call_stack.push("".into(), ActivationRecordType::Loop);
+
+        // an infinite loop has no bounds
+        if self.from_range.is_some() {
+            self.add_bounds(call_stack);
+        }
+
+        if let Some(var) = &self.with_var {
+            call_stack.insert_variable(Rc::clone(var))
+        }
+    }
+
+    fn add_bounds(&self, call_stack: &mut CallStack) {
         let from_name = format!("loop_{}_from", self.loop_number);
         let to_name = format!("loop_{}_to", self.loop_number);
         let step_name = format!("loop_{}_step", self.loop_number);
@@ -61,13 +169,15 @@ impl Loop {
             token: TokenEnum::Variable(from_name.clone()),
             col_number: 0,
             index: 0,
+            start: 0,
             line_number: 0,
             file: "".into(),
         };
 
         // TODO: Fix this, this doesn't need to be done this way
         //
-        // These variables live in the outer scope not in the loop scope
+        // These variables are in the function's record so they're found by name, but their stack
+        // slots belong to the loop and are used again once it ends
         call_stack.insert_variable_in_most_recent_function(Rc::new(RefCell::new(Variable::new(
             Box::new(token.clone()),
             VarType::Int,
@@ -94,12 +204,6 @@ impl Loop {
             false,
             0,
         ))));
-
-        call_stack.push("".into(), ActivationRecordType::Loop);
-
-        if let Some(var) = &self.with_var {
-            call_stack.insert_variable(Rc::clone(var))
-        }
     }
 }
 
@@ -131,9 +235,13 @@ impl AST for Loop {
 
             (None, None, None) => {
                 // infinite loop
+                self.add_call_stack(call_stack);
+
                 asm.gen_inf_loop_start(self.loop_number);
                 self.block.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
                 asm.gen_inf_loop_end(self.loop_number);
+
+                call_stack.pop();
             }
 
             _ => panic!("from, to or step not defined"),
@@ -141,12 +249,22 @@ impl AST for Loop {
     }
 
     fn visit(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
-        let from = self
-            .from_range
-            .as_ref()
-            .unwrap()
-            .borrow()
-            .visit(v, Rc::clone(&f), call_stack);
+        // `loop { ... }` goes on until it's broken out of
+        let Some(from_range) = &self.from_range else {
+            loop {
+                self.block.borrow().visit(v, Rc::clone(&f), call_stack);
+
+                if self.iteration_ended(v) {
+                    break;
+                }
+            }
+
+            return VisitResult {
+                token: Box::new(TokenEnum::Unknown("".into())),
+            };
+        };
+
+        let from = from_range.borrow().visit(v, Rc::clone(&f), call_stack);
         let to = self
             .to_range
             .as_ref()
@@ -189,17 +307,35 @@ impl AST for Loop {
             exit(1);
         };
 
-        for _ in (from..to).step_by(step_by) {
+        // the variable only exists in the loop
+        let with_var = self.with_var.as_ref().map(|var| var.borrow().var_name.clone());
+        let hidden = with_var.as_ref().and_then(|name| v.get(name).cloned());
+
+        for i in (from..to).step_by(step_by) {
+            if let Some(name) = &with_var {
+                v.insert(name.clone(), VariableEnum::Number(Number::Integer(i)));
+            }
+
             self.block.borrow().visit(v, Rc::clone(&f), call_stack);
+
+            if self.iteration_ended(v) {
+                break;
+            }
         }
 
+        match (with_var, hidden) {
+            (Some(name), Some(value)) => v.insert(name, value),
+            (Some(name), None) => v.remove(&name),
+            _ => None,
+        };
+
         return VisitResult {
             token: Box::new(TokenEnum::Unknown("".into())),
         };
     }
 
     fn get_token(&self) -> &crate::lexer::lexer::Token {
-        todo!()
+        &self.token
     }
 
     fn print(&self) {
@@ -209,7 +345,6 @@ impl AST for Loop {
     fn semantic_visit(&mut self, call_stack: &mut CallStack, f: Rc<RefCell<Functions>>) {
         let var_enum = VariableEnum::Number(Number::Integer(1));
 
-        // These variables live in the outer scope not in the loop scope
         self.add_call_stack(call_stack);
 
         match (&self.from_range, &self.to_range, &self.step_by) {
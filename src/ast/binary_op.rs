@@ -1,3 +1,4 @@
+use crate::json::Json;
 use crate::lexer::tokens::AllOperations;
 use crate::lexer::types::VarType;
 use crate::trace;
@@ -6,7 +7,7 @@ use crate::types::ASTNode;
 use crate::semantic_analyzer::semantic_analyzer::CallStack;
 
 use crate::{
-    asm::asm::ASM,
+    asm::{asm::ASM, string_functions::StringArg},
     constants,
     interpreter::interpreter::{Functions, Variables},
     lexer::{
@@ -15,9 +16,10 @@ use crate::{
     },
 };
 use core::panic;
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+use super::abstract_syntax_tree::{node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST};
+use super::factor::{coerce_float_literal, constant_int, is_float_literal};
 
 #[derive(Debug)]
 pub struct BinaryOP {
@@ -67,6 +69,48 @@ impl BinaryOP {
         }
     }
 
+    /// 1.0 / 3.0, where every operand is a float literal
+    pub fn is_float_literal(&self) -> bool {
+        is_float_literal(&self.left) && is_float_literal(&self.right)
+    }
+
+    /// 4 * 1024, where every operand is an integer known at compile time. Expressions that would
+    /// fault at runtime, like dividing by zero, are left for the runtime
+    pub fn constant_int(&self, constants: &HashMap<String, i64>) -> Option<i64> {
+        if !matches!(
+            self.result_type,
+            VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32
+        ) {
+            return None;
+        }
+
+        let l = constant_int(&self.left, constants)?;
+        let r = constant_int(&self.right, constants)?;
+
+        let TokenEnum::Op(op) = &self.operator.token else {
+            return None;
+        };
+
+        match op {
+            Operations::Plus => Some(l.wrapping_add(r)),
+            Operations::Minus => Some(l.wrapping_sub(r)),
+            Operations::Multiply => Some(l.wrapping_mul(r)),
+            Operations::Divide => l.checked_div(r),
+            Operations::Modulo => l.checked_rem(r),
+            // shl and shr only look at the lowest 6 bits of the count
+            Operations::ShiftLeft => Some(((l as u64) << (r & 63)) as i64),
+            Operations::ShiftRight => Some(((l as u64) >> (r & 63)) as i64),
+        }
+    }
+
+    /// Stores every literal in this expression as a float32
+    pub fn coerce_float_literals(&mut self) {
+        coerce_float_literal(&self.left, &VarType::Float32);
+        coerce_float_literal(&self.right, &VarType::Float32);
+
+        self.result_type = VarType::Float32;
+    }
+
     fn evaluate_float<T>(&self, l: T, r: T) -> T
     where
         T: std::ops::Add<Output = T>,
@@ -122,6 +166,7 @@ impl BinaryOP {
                 VariableEnum::Number(var_num) => self.eval_number_number(number, var_num),
                 VariableEnum::String(_) => todo!(),
                 VariableEnum::Pointer(_) => todo!(),
+                VariableEnum::Union(..) => todo!(),
             },
 
             None => panic!("Variable {} is not defined", variable),
@@ -144,6 +189,7 @@ impl BinaryOP {
                 (VariableEnum::Pointer(_), VariableEnum::Number(_)) => todo!(),
                 (VariableEnum::Pointer(_), VariableEnum::String(_)) => todo!(),
                 (VariableEnum::Pointer(_), VariableEnum::Pointer(_)) => todo!(),
+                (VariableEnum::Union(..), _) | (_, VariableEnum::Union(..)) => todo!(),
             },
 
             (None, Some(_)) => panic!("Variable {} is not defined", var2),
@@ -162,10 +208,43 @@ impl BinaryOP {
             (Operand::Variable(v1), Operand::Variable(v2)) => self.eval_var_var(v1, v2, i),
         }
     }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        used_names(&self.left, names);
+        used_names(&self.right, names);
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("left", node_json(&self.left)),
+            ("operator", self.operator.token.to_string().into()),
+            ("right", node_json(&self.right)),
+        ]
+    }
 }
 
 impl AST for BinaryOP {
     fn visit_com(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
+        // Concatenation is a call into the String runtime
+        if let VarType::OwnedStr = self.result_type {
+            asm.function_call_prep();
+
+            self.left.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+            self.right.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+
+            let left = self.left.borrow();
+            let right = self.right.borrow();
+
+            asm.string_concat(
+                StringArg::new(&left.get_type().0, left.get_node().is_temporary_string()),
+                StringArg::new(&right.get_type().0, right.get_node().is_temporary_string()),
+                call_stack,
+            );
+            return;
+        }
+
         self.left.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
 
         self.right.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
@@ -226,6 +305,12 @@ impl AST for BinaryOP {
         self.left.borrow_mut().semantic_visit(call_stack, Rc::clone(&f));
         self.right.borrow_mut().semantic_visit(call_stack, f);
 
+        let left_type = self.left.borrow().get_type().0;
+        let right_type = self.right.borrow().get_type().0;
+
+        coerce_float_literal(&self.left, &right_type);
+        coerce_float_literal(&self.right, &left_type);
+
         if let TokenEnum::Op(op) = &self.operator.token {
             self.result_type = self
                 .left
@@ -242,6 +327,8 @@ impl AST for BinaryOP {
         } else {
             unreachable!("Found Operation '{:?}' which is not defined for a binary operation. This must be a bug in the parsing step", self.operator.token)
         }
+
+        call_stack.symbols.expression_type(&self.operator, &self.result_type);
     }
 
     fn get_node(&self) -> ASTNodeEnum {
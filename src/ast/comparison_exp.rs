@@ -1,11 +1,13 @@
+use crate::json::Json;
 use crate::lexer::tokens::AllOperations;
 use crate::lexer::types::VarType;
 use crate::types::ASTNode;
 
 use crate::semantic_analyzer::semantic_analyzer::CallStack;
+use crate::semantic_analyzer::warnings::Warning;
 
 use crate::{
-    asm::asm::ASM,
+    asm::{asm::ASM, string_functions::StringArg},
     constants,
     interpreter::interpreter::{Functions, Variables},
     lexer::{
@@ -13,9 +15,10 @@ use crate::{
         tokens::{Comparators, Number, Operand, TokenEnum, VariableEnum},
     },
 };
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+use super::abstract_syntax_tree::{node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST};
+use super::factor::{coerce_float_literal, constant_int};
 
 #[derive(Debug)]
 pub struct ComparisonExp {
@@ -35,6 +38,46 @@ impl ComparisonExp {
         }
     }
 
+    /// The result of comparing two integers known at compile time, None if either side isn't one
+    pub fn constant_result(&self) -> Option<bool> {
+        let constants = HashMap::new();
+
+        let l = constant_int(&self.left, &constants)?;
+        let r = constant_int(&self.right, &constants)?;
+
+        let TokenEnum::Comparator(op) = &self.comp_op.token else {
+            return None;
+        };
+
+        Some(match op {
+            Comparators::DoubleEquals => l == r,
+            Comparators::NotEquals => l != r,
+            Comparators::LessThan => l < r,
+            Comparators::GreaterThan => l > r,
+            Comparators::LessThanEq => l <= r,
+            Comparators::GreaterThanEq => l >= r,
+        })
+    }
+
+    /// A char is never negative, so comparing one with a negative number always has the same result
+    fn warn_unsigned_negative_comparison(&self, call_stack: &mut CallStack) {
+        let constants = HashMap::new();
+
+        for (side, other) in [(&self.left, &self.right), (&self.right, &self.left)] {
+            if !matches!(side.borrow().get_type().0, VarType::Char) {
+                continue;
+            }
+
+            if let Some(value) = constant_int(other, &constants).filter(|value| *value < 0) {
+                call_stack.warnings.warn(
+                    Warning::UnsignedNegativeComparison,
+                    format!("Comparing a char with {value}, a char is never negative"),
+                    &self.comp_op,
+                );
+            }
+        }
+    }
+
     fn compare<T>(&self, l: T, r: T) -> TokenEnum
     where
         T: PartialOrd,
@@ -83,6 +126,7 @@ impl ComparisonExp {
                 VariableEnum::Number(var_num) => self.eval_number_number(number, var_num),
                 VariableEnum::String(_) => todo!(),
                 VariableEnum::Pointer(_) => todo!(),
+                VariableEnum::Union(..) => todo!(),
             },
 
             None => panic!("Variable {} is not defined", variable),
@@ -105,6 +149,7 @@ impl ComparisonExp {
                 (VariableEnum::Pointer(_), VariableEnum::Number(_)) => todo!(),
                 (VariableEnum::Pointer(_), VariableEnum::String(_)) => todo!(),
                 (VariableEnum::Pointer(_), VariableEnum::Pointer(_)) => todo!(),
+                (VariableEnum::Union(..), _) | (_, VariableEnum::Union(..)) => todo!(),
             },
 
             (None, Some(_)) => panic!("Variable {} is not defined", var1),
@@ -123,10 +168,48 @@ impl ComparisonExp {
             (Operand::Variable(v1), Operand::Variable(v2)) => self.eval_var_var(v1, v2, i),
         }
     }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        used_names(&self.left, names);
+        used_names(&self.right, names);
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("left", node_json(&self.left)),
+            ("operator", self.comp_op.token.to_string().into()),
+            ("right", node_json(&self.right)),
+        ]
+    }
 }
 
 impl AST for ComparisonExp {
     fn visit_com(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
+        let left_type = self.left.borrow().get_type().0;
+        let right_type = self.right.borrow().get_type().0;
+
+        // Strings are compared byte by byte in the String runtime
+        if matches!(left_type, VarType::OwnedStr) || matches!(right_type, VarType::OwnedStr) {
+            let TokenEnum::Comparator(c) = &self.comp_op.token else {
+                unreachable!("Found non comparator for a Comparison Expression")
+            };
+
+            asm.function_call_prep();
+
+            self.left.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+            self.right.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+
+            asm.string_compare(
+                c.clone(),
+                StringArg::new(&left_type, self.left.borrow().get_node().is_temporary_string()),
+                StringArg::new(&right_type, self.right.borrow().get_node().is_temporary_string()),
+                call_stack,
+            );
+            return;
+        }
+
         self.left.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
         self.right.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
 
@@ -181,6 +264,12 @@ impl AST for ComparisonExp {
         self.left.borrow_mut().semantic_visit(call_stack, f.clone());
         self.right.borrow_mut().semantic_visit(call_stack, f);
 
+        let left_type = self.left.borrow().get_type().0;
+        let right_type = self.right.borrow().get_type().0;
+
+        coerce_float_literal(&self.left, &right_type);
+        coerce_float_literal(&self.right, &left_type);
+
         if let TokenEnum::Comparator(op) = &self.comp_op.token {
             // need to do this even though it's always going to be an int
             self.result_type = self
@@ -195,6 +284,10 @@ impl AST for ComparisonExp {
                 self.comp_op.token
             )
         }
+
+        call_stack.symbols.expression_type(&self.comp_op, &self.result_type);
+
+        self.warn_unsigned_negative_comparison(call_stack);
     }
 
     fn get_node(&self) -> ASTNodeEnum {
@@ -1,16 +1,24 @@
+use crate::json::Json;
 use crate::lexer::types::VarType;
 use crate::types::ASTNode;
 
-use crate::semantic_analyzer::semantic_analyzer::{ActivationRecord, ActivationRecordType, CallStack};
+use crate::semantic_analyzer::{
+    control_flow::ControlFlowGraph,
+    semantic_analyzer::{ActivationRecord, ActivationRecordType, CallStack},
+    warnings::Warning,
+};
 
 use crate::{
     asm::{asm::ASM, conditionals::ConditionalJumpTo},
     interpreter::interpreter::{Functions, Variables},
-    lexer::tokens::TokenEnum,
+    lexer::{lexer::Token, tokens::TokenEnum},
 };
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+use super::abstract_syntax_tree::{
+    add_to_cfg, node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST,
+};
+use super::factor::constant_int;
 
 #[derive(Debug)]
 pub struct IfStatement {
@@ -22,6 +30,33 @@ impl IfStatement {
     pub fn new(condition: ASTNode, block: ASTNode) -> Self {
         Self { condition, block }
     }
+
+    /// A condition made up of numbers only is decided at compile time, so it's almost certainly a
+    /// mistake, either the branch always runs or it never does
+    fn warn_if_constant(&self, call_stack: &mut CallStack) {
+        let condition = self.condition.borrow();
+
+        let constant = match condition.get_node() {
+            ASTNodeEnum::ComparisonExp(comparison) => comparison.constant_result(),
+            _ => constant_int(&self.condition, &HashMap::new()).map(|value| value != 0),
+        };
+
+        match constant {
+            Some(true) => call_stack.warnings.warn(
+                Warning::AlwaysTrueCondition,
+                "This condition is always true",
+                condition.get_token(),
+            ),
+
+            Some(false) => call_stack.warnings.warn(
+                Warning::AlwaysFalseCondition,
+                "This condition is always false, so its block never runs",
+                condition.get_token(),
+            ),
+
+            None => {}
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -40,6 +75,8 @@ pub struct ConditionalStatement {
     if_statement: IfStatement,
     elif_ladder: Vec<IfStatement>,
     else_statement: Option<ElseStatement>,
+    /// The `if` keyword
+    token: Token,
 }
 
 impl ConditionalStatement {
@@ -47,13 +84,74 @@ impl ConditionalStatement {
         if_statement: IfStatement,
         elif_ladder: Vec<IfStatement>,
         else_statement: Option<ElseStatement>,
+        token: Token,
     ) -> Self {
         Self {
+            token,
             if_statement,
             elif_ladder,
             else_statement,
         }
     }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        for if_statement in std::iter::once(&self.if_statement).chain(&self.elif_ladder) {
+            used_names(&if_statement.condition, names);
+            used_names(&if_statement.block, names);
+        }
+
+        if let Some(else_statement) = &self.else_statement {
+            used_names(&else_statement.block, names);
+        }
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        let branch = |if_statement: &IfStatement| {
+            Json::object([
+                ("condition", node_json(&if_statement.condition)),
+                ("block", node_json(&if_statement.block)),
+            ])
+        };
+
+        vec![
+            ("if", branch(&self.if_statement)),
+            (
+                "elif",
+                self.elif_ladder.iter().map(branch).collect::<Vec<Json>>().into(),
+            ),
+            (
+                "else",
+                self.else_statement.as_ref().map_or(Json::Null, |e| node_json(&e.block)),
+            ),
+        ]
+    }
+
+    /// See `ASTNodeEnum::add_to_cfg`
+    pub fn add_to_cfg(&self, cfg: &mut ControlFlowGraph) {
+        let after = cfg.new_block();
+
+        for if_statement in std::iter::once(&self.if_statement).chain(&self.elif_ladder) {
+            cfg.read(&if_statement.condition);
+
+            let condition = cfg.current();
+
+            cfg.start_block(&[condition]);
+            add_to_cfg(&if_statement.block, cfg);
+            cfg.add_edge(cfg.current(), after);
+
+            // the next condition is only checked if this one was false
+            cfg.start_block(&[condition]);
+        }
+
+        if let Some(else_statement) = &self.else_statement {
+            add_to_cfg(&else_statement.block, cfg);
+        }
+
+        cfg.add_edge(cfg.current(), after);
+        cfg.set_current(after);
+    }
 }
 
 impl AST for ConditionalStatement {
@@ -135,7 +233,9 @@ impl AST for ConditionalStatement {
         if let Some(e) = &self.else_statement {
             asm.else_start(current_num_if);
 
+            call_stack.push("".into(), ActivationRecordType::IfElse);
             e.block.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+            call_stack.pop();
 
             asm.else_end(current_num_if);
         }
@@ -174,7 +274,7 @@ impl AST for ConditionalStatement {
     }
 
     fn get_token(&self) -> &crate::lexer::lexer::Token {
-        todo!()
+        &self.token
     }
 
     fn print(&self) {
@@ -187,6 +287,8 @@ impl AST for ConditionalStatement {
             .borrow_mut()
             .semantic_visit(call_stack, Rc::clone(&f));
 
+        self.if_statement.warn_if_constant(call_stack);
+
         call_stack.push("".into(), ActivationRecordType::IfElse);
 
         self.if_statement
@@ -198,6 +300,7 @@ impl AST for ConditionalStatement {
 
         for elif in &self.elif_ladder {
             elif.condition.borrow_mut().semantic_visit(call_stack, Rc::clone(&f));
+            elif.warn_if_constant(call_stack);
 
             call_stack.push("".into(), ActivationRecordType::IfElse);
 
@@ -1,23 +1,28 @@
+use crate::json::Json;
 use crate::lexer::types::VarType;
 use crate::{helpers, trace};
 use crate::{lexer::tokens::AssignmentTypes, types::ASTNode};
 
-use crate::semantic_analyzer::semantic_analyzer::CallStack;
+use crate::semantic_analyzer::{
+    control_flow::ControlFlowGraph,
+    semantic_analyzer::{ActivationRecordType, CallStack},
+};
 
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     asm::asm::ASM,
-    interpreter::interpreter::{Functions, Variables},
+    interpreter::interpreter::{variable_value, Functions, Variables},
     lexer::{
         lexer::Token,
-        tokens::{TokenEnum, VariableEnum},
+        tokens::{Number, TokenEnum, VariableEnum},
     },
 };
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut};
+use super::abstract_syntax_tree::{ast_json, optional_node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names};
 use super::{
     abstract_syntax_tree::{VisitResult, AST},
+    factor::{coerce_float_literal, constant_int},
     variable::Variable,
 };
 
@@ -25,11 +30,85 @@ use super::{
 pub struct DeclarationStatement {
     left: Rc<RefCell<Variable>>,
     right: Option<ASTNode>,
+    /// The token of the variable, `left` can't lend it out of the RefCell
+    token: Token,
 }
 
 impl DeclarationStatement {
     pub fn new(left: Rc<RefCell<Variable>>, right: Option<ASTNode>) -> Self {
-        Self { left, right }
+        let token = left.borrow().get_token().clone();
+
+        Self { left, right, token }
+    }
+
+    pub fn get_variable(&self) -> &Rc<RefCell<Variable>> {
+        &self.left
+    }
+
+    fn inference_error(&self, reason: &str) {
+        let left = self.left.borrow();
+
+        helpers::compiler_error(
+            format!(
+                "Cannot infer the type of '{}' from {reason}. Add a type annotation",
+                left.var_name
+            ),
+            left.get_token(),
+        );
+    }
+
+    /// Has to run before the RHS is visited, as visiting an empty array is an error of its own
+    fn check_empty_array(&self) {
+        if let Some(right_node) = &self.right {
+            if let ASTNodeEnum::Array(array) = right_node.borrow().get_node() {
+                if array.is_empty() {
+                    self.inference_error("an empty array");
+                }
+            }
+        }
+    }
+
+    /// def a = RHS;
+    ///
+    /// The variable takes the type of the RHS, which is only known after the RHS is visited
+    fn infer_type(&mut self, call_stack: &CallStack) {
+        let Some(right_node) = &self.right else {
+            unreachable!("Declaration without a type or a value")
+        };
+
+        let right_borrow = right_node.borrow();
+
+        // A memory block is a pointer to raw bytes, we don't know what it's supposed to point to
+        if let ASTNodeEnum::Variable(var) = right_borrow.get_node() {
+            if let (Some(in_stack), _) = call_stack.get_var_with_name(&var.var_name) {
+                if in_stack.borrow().is_memory_block && var.array_aceess_index.is_none() {
+                    self.inference_error(&format!("the memory block '{}'", var.var_name));
+                }
+            }
+        }
+
+        let (mut inferred_type, _) = right_borrow.get_type();
+
+        // Has to come first, as a pointer compares equal to its inner type
+        if matches!(&inferred_type, VarType::Ptr(inner) if matches!(**inner, VarType::Unknown)) {
+            self.inference_error("an untyped pointer");
+        }
+
+        if inferred_type == VarType::Unknown {
+            self.inference_error("an expression with no type");
+        }
+
+        // Struct and union literals build their type from the members they were given, which
+        // don't have offsets. Use the definition instead
+        if let VarType::Struct(name, _) | VarType::Union(name, _) = &inferred_type {
+            if let Some(user_defined_type) = call_stack.user_defined_types.iter().find(|t| t.name == *name) {
+                inferred_type = user_defined_type.type_.clone();
+            }
+        }
+
+        let mut left = self.left.borrow_mut();
+        left.var_type = inferred_type.clone();
+        left.result_type = inferred_type;
     }
 
     fn verify_type(&self) {
@@ -53,12 +132,77 @@ impl DeclarationStatement {
             }
         }
     }
+
+    /// A global initialised with an integer known at compile time doesn't need any code, its value
+    /// goes straight into the binary. Returns whether that's the case
+    fn declare_constant_global(&self, asm: &mut ASM, call_stack: &CallStack) -> bool {
+        let Some(right_node) = &self.right else {
+            return false;
+        };
+
+        let left = self.left.borrow();
+
+        if !matches!(
+            call_stack.get_var_with_name(&left.var_name).1,
+            ActivationRecordType::Global
+        ) {
+            return false;
+        }
+
+        let Some(value) = constant_int(right_node, &asm.constant_globals) else {
+            return false;
+        };
+
+        let fits = match left.var_type {
+            VarType::Int => true,
+            VarType::Int8 => i8::try_from(value).is_ok(),
+            VarType::Int16 => i16::try_from(value).is_ok(),
+            VarType::Int32 => i32::try_from(value).is_ok(),
+            _ => false,
+        };
+
+        if fits {
+            asm.constant_global_declaration(&left.var_name, value, left.is_const);
+        }
+
+        fits
+    }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        names.declared.push(self.left.borrow().var_name.clone());
+
+        if let Some(right) = &self.right {
+            used_names(right, names);
+        }
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("variable", ast_json(&*self.left.borrow())),
+            ("value", optional_node_json(&self.right)),
+        ]
+    }
+
+    /// See `ASTNodeEnum::add_to_cfg`
+    pub fn add_to_cfg(&self, cfg: &mut ControlFlowGraph) {
+        if let Some(right) = &self.right {
+            cfg.read(right);
+        }
+
+        cfg.declare(&self.left.borrow(), self.right.is_some());
+    }
 }
 
 impl AST for DeclarationStatement {
     fn visit_com(&self, vars: &mut Variables, f: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
         call_stack.insert_variable(Rc::clone(&self.left));
 
+        if self.declare_constant_global(asm, call_stack) {
+            return;
+        }
+
         asm.variable_declaration(&self.left.borrow().var_name, call_stack);
 
         if let Some(right_node) = &self.right {
@@ -72,6 +216,9 @@ impl AST for DeclarationStatement {
                     Some(binding)
                 }
 
+                // Only the payload of the variant being assigned is written
+                ASTNodeEnum::UnionVariant(u) => Some(vec![&u.variant_name]),
+
                 _ => None,
             };
 
@@ -93,11 +240,25 @@ impl AST for DeclarationStatement {
         functions: Rc<RefCell<Functions>>,
         call_stack: &mut CallStack,
     ) -> VisitResult {
-        unimplemented!()
+        let value = match &self.right {
+            Some(right) => variable_value(&right.borrow().visit(vars, functions, call_stack).token, &self.token),
+
+            None => match self.left.borrow().var_type {
+                VarType::Float | VarType::Float32 => VariableEnum::Number(Number::Float(0.0)),
+                VarType::Str => VariableEnum::String("".into()),
+                _ => VariableEnum::Number(Number::Integer(0)),
+            },
+        };
+
+        vars.insert(self.left.borrow().var_name.clone(), value);
+
+        VisitResult {
+            token: Box::new(TokenEnum::Unknown("".into())),
+        }
     }
 
     fn get_token(&self) -> &Token {
-        todo!()
+        &self.token
     }
 
     fn print(&self) {
@@ -105,20 +266,28 @@ impl AST for DeclarationStatement {
     }
 
     fn semantic_visit(&mut self, call_stack: &mut CallStack, f: Rc<RefCell<Functions>>) {
+        if self.left.borrow().var_type == VarType::Unknown {
+            self.check_empty_array();
+        }
+
         if let Some(right_node) = &self.right {
             right_node.borrow_mut().semantic_visit(call_stack, f.clone());
         }
 
         // Before inserting in the call stack we need the type of the variable to calculate its
         // size
-        {
-            let left_mut = self.left.borrow_mut();
+        if self.left.borrow().var_type == VarType::Unknown {
+            self.infer_type(call_stack);
         }
 
         call_stack.insert_variable(Rc::clone(&self.left));
 
         self.left.borrow_mut().semantic_visit(call_stack, f);
 
+        if let Some(right_node) = &self.right {
+            coerce_float_literal(right_node, &self.left.borrow().get_type().0);
+        }
+
         self.verify_type();
     }
 
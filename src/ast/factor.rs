@@ -1,20 +1,22 @@
 use crate::helpers::compiler_error;
+use crate::json::Json;
 use crate::lexer::tokens::Number;
 use crate::lexer::types::VarType;
 use crate::semantic_analyzer::semantic_analyzer::CallStack;
 
 use crate::trace;
+use crate::types::ASTNode;
 use crate::{
     asm::asm::ASM,
     constants,
-    interpreter::interpreter::{Functions, Variables},
+    interpreter::interpreter::{variable_token, Functions, Variables},
     lexer::{
         lexer::Token,
         tokens::{TokenEnum, VariableEnum},
     },
 };
 use std::process::exit;
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
 
@@ -33,14 +35,73 @@ impl Factor {
         }
     }
 
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        let value = match &self.token.token {
+            TokenEnum::Number(Number::Integer(int)) => Json::Number(*int as f64),
+            TokenEnum::Number(Number::Float(float)) => Json::Number(*float),
+            TokenEnum::Bool(bool) => (*bool).into(),
+            token => token.to_string().into(),
+        };
+
+        vec![("value", value)]
+    }
+
     pub fn get_type_factor(&self) -> &VarType {
         return &self.result_type;
     }
 }
 
+/// 2.5 or an expression made up of only float literals, like 1.0 / 3.0
+pub fn is_float_literal(node: &ASTNode) -> bool {
+    match node.borrow().get_node() {
+        ASTNodeEnum::Factor(factor) => matches!(factor.token.token, TokenEnum::Number(Number::Float(_))),
+        ASTNodeEnum::BinaryOp(binary_op) => binary_op.is_float_literal(),
+        _ => false,
+    }
+}
+
+/// The value of an integer expression made up of literals and the `const` globals in `constants`,
+/// or None if it can only be known at runtime
+pub fn constant_int(node: &ASTNode, constants: &HashMap<String, i64>) -> Option<i64> {
+    match node.borrow().get_node() {
+        ASTNodeEnum::Factor(factor) => match factor.token.token {
+            TokenEnum::Number(Number::Integer(i)) => Some(i as i64),
+            _ => None,
+        },
+
+        ASTNodeEnum::BinaryOp(binary_op) => binary_op.constant_int(constants),
+
+        ASTNodeEnum::Variable(var) => {
+            if var.dereference || var.store_address || var.array_aceess_index.is_some() || !var.member_access.is_empty()
+            {
+                return None;
+            }
+
+            constants.get(&var.var_name).copied()
+        }
+
+        _ => None,
+    }
+}
+
+/// A float literal is a float64, unless it's used where a float32 is expected. Then it's stored as a
+/// float32 instead of being converted at runtime
+pub fn coerce_float_literal(node: &ASTNode, expected_type: &VarType) {
+    if !matches!(expected_type, VarType::Float32) || !is_float_literal(node) {
+        return;
+    }
+
+    match node.borrow_mut().get_node_mut() {
+        ASTNodeEnumMut::Factor(factor) => factor.result_type = VarType::Float32,
+        ASTNodeEnumMut::BinaryOp(binary_op) => binary_op.coerce_float_literals(),
+        _ => {}
+    }
+}
+
 impl AST for Factor {
     fn visit_com(&self, x: &mut Variables, _: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
-        asm.generate_asm_factor(&self.token.token, call_stack);
+        asm.generate_asm_factor(&self.token.token, &self.result_type, call_stack);
     }
 
     fn visit(&self, v: &mut Variables, _: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
@@ -55,6 +116,12 @@ impl AST for Factor {
                         VariableEnum::Number(n) => TokenEnum::Number(n.clone()),
                         VariableEnum::String(s) => TokenEnum::StringLiteral(s.to_string()),
                         VariableEnum::Pointer(_) => todo!(),
+                        VariableEnum::Union(union_name, variant_name, tag, payload) => TokenEnum::Union(
+                            union_name.clone(),
+                            variant_name.clone(),
+                            *tag,
+                            Box::new(variable_token(payload, &self.token)),
+                        ),
                     }
                 } else {
                     compiler_error(
@@ -101,7 +168,10 @@ impl AST for Factor {
             }
 
             TokenEnum::Number(num) => match num {
+                // enum variants are lowered to integers by the parser, but they keep the enum type
+                Number::Integer(_) if matches!(self.result_type, VarType::Enum(..)) => self.result_type.clone(),
                 Number::Integer(_) => VarType::Int,
+                Number::Float(_) if matches!(self.result_type, VarType::Float32) => VarType::Float32,
                 Number::Float(_) => VarType::Float,
             },
 
@@ -109,6 +179,8 @@ impl AST for Factor {
 
             _ => VarType::Unknown,
         };
+
+        call_stack.symbols.expression_type(&self.token, &self.result_type);
     }
 
     fn get_node(&self) -> ASTNodeEnum {
@@ -0,0 +1,147 @@
+use std::process::exit;
+
+use crate::{helpers::compiler_error, lexer::lexer::Token};
+
+/// Bits of the flags passed to generated/format.asm, see FORMAT_LEFT and friends over there
+pub const FORMAT_LEFT: u8 = 1;
+pub const FORMAT_ZERO: u8 = 2;
+pub const FORMAT_HEX: u8 = 4;
+pub const FORMAT_UPPER: u8 = 8;
+
+/// What goes after the colon in `{:<08x}`. All of it is optional, in this order
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FormatSpec {
+    /// `<`, pad on the right instead of the left
+    pub left_align: bool,
+    /// `0`, pad numbers with zeros after the sign
+    pub zero_pad: bool,
+    pub width: usize,
+    /// `x` or `X`, integers in lower or upper case hex
+    pub hex: Option<bool>,
+}
+
+impl FormatSpec {
+    fn parse(spec: &str) -> Option<Self> {
+        let mut format_spec = FormatSpec::default();
+        let mut rest = spec;
+
+        if let Some(r) = rest.strip_prefix('<') {
+            format_spec.left_align = true;
+            rest = r;
+        }
+
+        if let Some(r) = rest.strip_prefix('0') {
+            format_spec.zero_pad = true;
+            rest = r;
+        }
+
+        let width_len = rest.bytes().take_while(|b| b.is_ascii_digit()).count();
+
+        if width_len > 0 {
+            format_spec.width = rest[..width_len].parse().ok()?;
+            rest = &rest[width_len..];
+        }
+
+        format_spec.hex = match rest {
+            "" => None,
+            "x" => Some(false),
+            "X" => Some(true),
+            _ => return None,
+        };
+
+        Some(format_spec)
+    }
+
+    pub fn flags(&self) -> u8 {
+        let mut flags = 0;
+
+        if self.left_align {
+            flags |= FORMAT_LEFT;
+        }
+
+        if self.zero_pad {
+            flags |= FORMAT_ZERO;
+        }
+
+        match self.hex {
+            Some(true) => flags |= FORMAT_HEX | FORMAT_UPPER,
+            Some(false) => flags |= FORMAT_HEX,
+            None => {}
+        }
+
+        flags
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatSegment {
+    /// Text copied as is, still with the escapes of the string literal
+    Literal(String),
+    /// `{}` or `{:spec}`, replaced by the next argument
+    Placeholder(FormatSpec),
+}
+
+/// Splits the format string of print into text and placeholders. `{{` and `}}` stand for `{`
+/// and `}`
+pub fn parse_format_string(format: &str, token: &Token) -> Vec<FormatSegment> {
+    let mut segments = vec![];
+    let mut literal = String::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+
+            '{' => {
+                let mut placeholder = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => compiler_error("Unclosed '{' in format string", token),
+                    }
+                }
+
+                let spec = match placeholder.strip_prefix(':') {
+                    Some(spec) => FormatSpec::parse(spec),
+                    None if placeholder.is_empty() => Some(FormatSpec::default()),
+                    None => None,
+                };
+
+                let spec = match spec {
+                    Some(spec) => spec,
+
+                    None => {
+                        compiler_error(format!("Invalid format specifier '{{{placeholder}}}'"), token);
+                        exit(1);
+                    }
+                };
+
+                if !literal.is_empty() {
+                    segments.push(FormatSegment::Literal(std::mem::take(&mut literal)));
+                }
+
+                segments.push(FormatSegment::Placeholder(spec));
+            }
+
+            '}' => compiler_error("Unmatched '}' in format string, use '}}' for a literal '}'", token),
+
+            _ => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(FormatSegment::Literal(literal));
+    }
+
+    segments
+}
@@ -1,18 +1,24 @@
 use crate::asm::functions::FUNCTION_ARGS_REGS;
 use crate::ast::function_def::FunctionDefinition;
 use crate::helpers::{self, compiler_error};
-use crate::lexer::keywords::{self, FUNC_SYSCALL};
+use crate::json::Json;
+use crate::lexer::keywords::{
+    self, FUNC_ABS, FUNC_ALLOC, FUNC_ASSERT, FUNC_CEIL, FUNC_ENV, FUNC_FLOOR, FUNC_FLUSH, FUNC_FPRINT, FUNC_FREE,
+    FUNC_LEN, FUNC_MAX, FUNC_MIN, FUNC_PRINT, FUNC_READ_LINE, FUNC_REALLOC, FUNC_SQRT, FUNC_SYSCALL,
+};
 use crate::lexer::types::VarType;
+use crate::parser::parse_impl::get_method_name;
 use crate::{trace, types::ASTNode};
 
 use crate::semantic_analyzer::semantic_analyzer::{ActivationRecordType, CallStack};
 
 use std::fs::read;
+use std::io::Write;
 use std::{cell::RefCell, process::exit, rc::Rc};
 
 use crate::{
     asm::asm::ASM,
-    interpreter::interpreter::{Functions, Variables},
+    interpreter::interpreter::{value_text, variable_value, Functions, Variables},
     lexer::{
         keywords::{FUNC_EXIT, FUNC_WRITE},
         lexer::Token,
@@ -20,27 +26,465 @@ use crate::{
     },
 };
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+use super::abstract_syntax_tree::{node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST};
+use super::factor::coerce_float_literal;
+use super::format_string::{parse_format_string, FormatSegment};
 
 #[derive(Debug)]
 pub struct FunctionCall {
     name: String,
+    /// Where the name of the function is, `token` is the LPAREN after it
+    name_token: Token,
     token: Token,
     arguments: Vec<ASTNode>,
     /// This is basically the return type for this function
     pub result_type: VarType,
     pub is_result_assigned: bool,
+    /// `point.len()`. The receiver is the first argument until the call is resolved
+    is_method_call: bool,
+    /// The parsed format string of print and fprint
+    format_segments: Vec<FormatSegment>,
 }
 
 impl FunctionCall {
-    pub fn new(name: String, token: Token, arguments: Vec<ASTNode>, is_result_assigned: bool) -> Self {
+    pub fn new(
+        name: String,
+        name_token: Token,
+        token: Token,
+        arguments: Vec<ASTNode>,
+        is_result_assigned: bool,
+    ) -> Self {
         Self {
             name,
+            name_token,
             token,
             arguments,
             result_type: VarType::Unknown,
             is_result_assigned,
+            is_method_call: false,
+            format_segments: vec![],
+        }
+    }
+
+    pub fn new_method_call(
+        name: String,
+        name_token: Token,
+        token: Token,
+        receiver: ASTNode,
+        mut arguments: Vec<ASTNode>,
+        is_result_assigned: bool,
+    ) -> Self {
+        arguments.insert(0, receiver);
+
+        Self {
+            name,
+            name_token,
+            token,
+            arguments,
+            result_type: VarType::Unknown,
+            is_result_assigned,
+            is_method_call: true,
+            format_segments: vec![],
+        }
+    }
+
+    /// Now that the type of the receiver is known, call the method of its struct and pass the
+    /// receiver's address, unless it's already a pointer
+    fn resolve_method_call(&mut self, call_stack: &mut CallStack, f: Rc<RefCell<Functions>>) {
+        let receiver = Rc::clone(&self.arguments[0]);
+
+        receiver.borrow_mut().semantic_visit(call_stack, Rc::clone(&f));
+
+        let receiver_type = receiver.borrow().get_type().0;
+
+        let struct_name = match &receiver_type {
+            VarType::Struct(name, _) => {
+                if let ASTNodeEnumMut::Variable(variable) = receiver.borrow_mut().get_node_mut() {
+                    variable.store_address = true;
+                }
+
+                name.clone()
+            }
+
+            VarType::Ptr(inner) if matches!(**inner, VarType::Struct(..)) => inner.to_string(),
+
+            _ => {
+                compiler_error(
+                    format!("Cannot call method '{}' on type '{receiver_type}'", self.name),
+                    &self.token,
+                );
+                exit(1);
+            }
+        };
+
+        let method_name = get_method_name(&struct_name, &self.name);
+
+        if !f.borrow().contains_key(&method_name) {
+            compiler_error(
+                format!("Struct '{struct_name}' has no method '{}'", self.name),
+                &self.token,
+            );
+        }
+
+        self.name = method_name;
+        self.is_method_call = false;
+    }
+
+    /// sqrt, abs, floor, ceil take one float and min, max take two floats of the same type.
+    /// The result has the type of the arguments
+    fn semantic_visit_math_function(&mut self, num_args: usize) {
+        if self.arguments.len() != num_args {
+            compiler_error(
+                format!(
+                    "Function '{}' expects {} arguments but got {}",
+                    self.name,
+                    num_args,
+                    self.arguments.len()
+                ),
+                &self.token,
+            );
+        }
+
+        if num_args == 2 {
+            let first_type = self.arguments[0].borrow().get_type().0;
+            let second_type = self.arguments[1].borrow().get_type().0;
+
+            coerce_float_literal(&self.arguments[0], &second_type);
+            coerce_float_literal(&self.arguments[1], &first_type);
+        }
+
+        let expected_type = self.arguments[0].borrow().get_type().0;
+
+        for arg in &self.arguments {
+            let arg_type = arg.borrow().get_type().0;
+
+            if !arg_type.is_float() {
+                compiler_error(
+                    format!("'{}' expects floating point arguments but got '{arg_type}'", self.name),
+                    arg.borrow().get_token(),
+                );
+            }
+
+            if arg_type != expected_type {
+                compiler_error(
+                    format!(
+                        "'{}' expects arguments of the same type but got '{expected_type}' and '{arg_type}'",
+                        self.name
+                    ),
+                    arg.borrow().get_token(),
+                );
+            }
+        }
+
+        self.result_type = expected_type;
+    }
+
+    /// alloc(size) returns an untyped pointer that can be assigned to any pointer, free(p) takes
+    /// any pointer or a String and realloc(p, size) returns a pointer of the same type as p
+    fn semantic_visit_heap_function(&mut self) {
+        let num_args = if self.name == FUNC_REALLOC { 2 } else { 1 };
+
+        if self.arguments.len() != num_args {
+            compiler_error(
+                format!(
+                    "Function '{}' expects {} arguments but got {}",
+                    self.name,
+                    num_args,
+                    self.arguments.len()
+                ),
+                &self.token,
+            );
+        }
+
+        let (pointer_arg, size_arg) = match self.name.as_str() {
+            FUNC_ALLOC => (None, Some(0)),
+            FUNC_FREE => (Some(0), None),
+            _ => (Some(0), Some(1)),
+        };
+
+        if let Some(index) = pointer_arg {
+            let arg_type = self.arguments[index].borrow().get_type().0;
+
+            let is_string = self.name == FUNC_FREE && matches!(arg_type, VarType::OwnedStr);
+
+            if !matches!(arg_type, VarType::Ptr(_)) && !is_string {
+                compiler_error(
+                    format!("'{}' expects a pointer but got '{arg_type}'", self.name),
+                    self.arguments[index].borrow().get_token(),
+                );
+            }
+
+            self.result_type = if self.name == FUNC_REALLOC {
+                arg_type
+            } else {
+                VarType::Unknown
+            };
+        }
+
+        if let Some(index) = size_arg {
+            let arg_type = self.arguments[index].borrow().get_type().0;
+
+            if !matches!(arg_type, VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32) {
+                compiler_error(
+                    format!("'{}' expects an integer size but got '{arg_type}'", self.name),
+                    self.arguments[index].borrow().get_token(),
+                );
+            }
+        }
+
+        if self.name == FUNC_ALLOC {
+            self.result_type = VarType::Ptr(Box::new(VarType::Unknown));
+        }
+    }
+
+    /// len(s) returns the number of bytes in a str or a String, or the number of elements in a
+    /// slice
+    fn semantic_visit_len(&mut self) {
+        if self.arguments.len() != 1 {
+            compiler_error(
+                format!("Function '{}' expects 1 arguments but got {}", self.name, self.arguments.len()),
+                &self.token,
+            );
+        }
+
+        let arg_type = self.arguments[0].borrow().get_type().0;
+
+        if !matches!(arg_type, VarType::Str | VarType::OwnedStr | VarType::Slice(_)) {
+            compiler_error(
+                format!("'{}' expects a string or a slice but got '{arg_type}'", self.name),
+                self.arguments[0].borrow().get_token(),
+            );
+        }
+
+        self.result_type = VarType::Int;
+    }
+
+    /// flush() takes nothing and read_line(fd) returns the next line of fd as a String
+    fn semantic_visit_io_function(&mut self) {
+        let num_args = if self.name == FUNC_READ_LINE { 1 } else { 0 };
+
+        if self.arguments.len() != num_args {
+            compiler_error(
+                format!(
+                    "Function '{}' expects {} arguments but got {}",
+                    self.name,
+                    num_args,
+                    self.arguments.len()
+                ),
+                &self.token,
+            );
+        }
+
+        if self.name == FUNC_FLUSH {
+            self.result_type = VarType::Unknown;
+            return;
+        }
+
+        let fd_type = self.arguments[0].borrow().get_type().0;
+
+        if !matches!(fd_type, VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32) {
+            compiler_error(
+                format!("'{}' expects an integer file descriptor but got '{fd_type}'", self.name),
+                self.arguments[0].borrow().get_token(),
+            );
+        }
+
+        self.result_type = VarType::OwnedStr;
+    }
+
+    /// env(name) returns the value of an environment variable as a str, which is empty if the
+    /// variable isn't set
+    fn semantic_visit_env(&mut self) {
+        if self.arguments.len() != 1 {
+            compiler_error(
+                format!("Function '{}' expects 1 arguments but got {}", self.name, self.arguments.len()),
+                &self.token,
+            );
+        }
+
+        let name_type = self.arguments[0].borrow().get_type().0;
+
+        if !matches!(name_type, VarType::Str) {
+            compiler_error(
+                format!("'{}' expects a str but got '{name_type}'", self.name),
+                self.arguments[0].borrow().get_token(),
+            );
+        }
+
+        self.result_type = VarType::Str;
+    }
+
+    /// print(format, args...) and fprint(fd, format, args...). The format has to be a string
+    /// literal so every `{}` in it can be checked against the type of its argument
+    fn semantic_visit_print(&mut self) {
+        let format_index = if self.name == FUNC_FPRINT { 1 } else { 0 };
+
+        if self.arguments.len() <= format_index {
+            compiler_error(format!("Function '{}' expects a format string", self.name), &self.token);
+        }
+
+        if format_index == 1 {
+            let fd_type = self.arguments[0].borrow().get_type().0;
+
+            if !matches!(fd_type, VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32) {
+                compiler_error(
+                    format!("'{}' expects an integer file descriptor but got '{fd_type}'", self.name),
+                    self.arguments[0].borrow().get_token(),
+                );
+            }
+        }
+
+        let format_token = self.arguments[format_index].borrow().get_token().clone();
+
+        self.format_segments = match (&format_token.token, self.arguments[format_index].borrow().get_node()) {
+            (TokenEnum::StringLiteral(format), ASTNodeEnum::Factor(..)) => parse_format_string(format, &format_token),
+
+            _ => {
+                compiler_error(
+                    format!("The format string of '{}' has to be a string literal", self.name),
+                    &format_token,
+                );
+                exit(1);
+            }
+        };
+
+        let placeholders: Vec<_> = self
+            .format_segments
+            .iter()
+            .filter_map(|segment| match segment {
+                FormatSegment::Placeholder(spec) => Some(spec),
+                FormatSegment::Literal(..) => None,
+            })
+            .collect();
+
+        let args = &self.arguments[format_index + 1..];
+
+        if placeholders.len() != args.len() {
+            compiler_error(
+                format!(
+                    "Format string has {} placeholders but {} arguments were given",
+                    placeholders.len(),
+                    args.len()
+                ),
+                &format_token,
+            );
+        }
+
+        for (spec, arg) in placeholders.iter().zip(args) {
+            let arg_type = arg.borrow().get_type().0;
+
+            let is_integer = matches!(
+                arg_type,
+                VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Char | VarType::Enum(..)
+            );
+
+            let is_number = is_integer || arg_type.is_float();
+
+            if !is_number && !matches!(arg_type, VarType::Str | VarType::OwnedStr) {
+                compiler_error(
+                    format!("Cannot format a value of type '{arg_type}'"),
+                    arg.borrow().get_token(),
+                );
+            }
+
+            if spec.hex.is_some() && !is_integer {
+                compiler_error(
+                    format!("Hex format needs an integer but got '{arg_type}'"),
+                    arg.borrow().get_token(),
+                );
+            }
+
+            if spec.zero_pad && !is_number {
+                compiler_error(
+                    format!("Zero padding needs a number but got '{arg_type}'"),
+                    arg.borrow().get_token(),
+                );
+            }
+        }
+
+        self.result_type = VarType::Unknown;
+    }
+
+    /// assert(condition) and assert(condition, message). The message has to be a string literal
+    /// as it's written out together with where the assertion is
+    fn semantic_visit_assert(&mut self) {
+        if !(1..=2).contains(&self.arguments.len()) {
+            compiler_error(
+                format!(
+                    "Function '{}' expects 1 or 2 arguments but got {}",
+                    self.name,
+                    self.arguments.len()
+                ),
+                &self.token,
+            );
+        }
+
+        let condition_type = self.arguments[0].borrow().get_type().0;
+
+        if !matches!(
+            condition_type,
+            VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32
+        ) {
+            compiler_error(
+                format!("'{}' expects a condition but got '{condition_type}'", self.name),
+                self.arguments[0].borrow().get_token(),
+            );
+        }
+
+        if let Some(message) = self.arguments.get(1) {
+            let message = message.borrow();
+
+            if !matches!(
+                (&message.get_token().token, message.get_node()),
+                (TokenEnum::StringLiteral(..), ASTNodeEnum::Factor(..))
+            ) {
+                compiler_error(
+                    format!("The message of '{}' has to be a string literal", self.name),
+                    message.get_token(),
+                );
+            }
         }
+
+        self.result_type = VarType::Unknown;
+    }
+
+    /// What a failed assert writes to stderr, with the escapes of the message still in it
+    fn assert_message(&self) -> String {
+        let location = format!("{}:{}", self.name_token.file, self.name_token.line_number);
+
+        let message = self
+            .arguments
+            .get(1)
+            .map(|message| message.borrow().get_token().token.clone());
+
+        match message {
+            Some(TokenEnum::StringLiteral(message)) => format!("{location}: assertion failed: {message}"),
+            _ => format!("{location}: assertion failed"),
+        }
+    }
+
+    pub fn name_token(&self) -> &Token {
+        &self.name_token
+    }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        names.used.push((self.name.clone(), self.token.clone()));
+
+        for argument in &self.arguments {
+            used_names(argument, names);
+        }
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("name", self.name.as_str().into()),
+            (
+                "arguments",
+                self.arguments.iter().map(node_json).collect::<Vec<Json>>().into(),
+            ),
+        ]
     }
 }
 
@@ -52,6 +496,8 @@ impl AST for FunctionCall {
                     arg.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
 
                     match arg.borrow().get_node() {
+                        ASTNodeEnum::Variable(v) if v.slice_end.is_some() => asm.func_write_owned_string(true),
+
                         ASTNodeEnum::Variable(v) => {
                             asm.func_write_var(v, call_stack);
                         }
@@ -63,19 +509,26 @@ impl AST for FunctionCall {
 
                             VarType::Str => asm.func_write_string(),
 
-                            VarType::Float => asm.func_write_float(),
+                            VarType::OwnedStr => asm.func_write_owned_string(true),
+
+                            VarType::Float | VarType::Float32 => asm.func_write_float(&bo.result_type),
 
                             VarType::Ptr(ptr_type) => {
                                 asm.func_write_pointer(&ptr_type, bo.times_dereferenced, &call_stack, None)
                             }
 
                             VarType::Array(..) => todo!(),
+                            VarType::Slice(_) => todo!(),
                             VarType::Struct(_, _) => todo!(),
                             VarType::Unknown => todo!(),
                             VarType::Function(_, _, _) => todo!(),
+                            VarType::Enum(..) => todo!(),
+                            VarType::Union(..) => todo!(),
                         },
 
                         ASTNodeEnum::Factor(f) => match &f.get_token().token {
+                            TokenEnum::Number(Number::Float(_)) => asm.func_write_float(&f.result_type),
+
                             // Int64 is the default for a number literal
                             TokenEnum::Number(_) => asm.func_write_number(VarType::Int),
                             TokenEnum::StringLiteral(_) => asm.func_write_string(),
@@ -83,12 +536,33 @@ impl AST for FunctionCall {
                             tok => unreachable!("This should be unreachable"),
                         },
 
+                        // Casts to ints are always extended to 64 bits
+                        ASTNodeEnum::TypeCast(tc) => match tc.result_type {
+                            VarType::Float | VarType::Float32 => asm.func_write_float(&tc.result_type),
+                            VarType::Str => asm.func_write_string(),
+                            VarType::OwnedStr => {
+                                asm.func_write_owned_string(ASTNodeEnum::TypeCast(tc).is_temporary_string())
+                            }
+                            _ => asm.func_write_number(VarType::Int),
+                        },
+
                         // This will always be an integer
                         ASTNodeEnum::LogicalExp(lo) => asm.func_write_number(todo!()),
 
                         // This will always be an integer
                         ASTNodeEnum::ComparisonExp(..) => asm.func_write_number(todo!()),
 
+                        // math functions leave their result in an xmm register
+                        ASTNodeEnum::FunctionCall(fc) if fc.result_type.is_float() => {
+                            asm.func_write_float(&fc.result_type)
+                        }
+
+                        ASTNodeEnum::FunctionCall(fc) if fc.name == FUNC_LEN => asm.func_write_number(VarType::Int),
+
+                        ASTNodeEnum::FunctionCall(fc) if fc.name == FUNC_READ_LINE => asm.func_write_owned_string(true),
+
+                        ASTNodeEnum::FunctionCall(fc) if fc.name == FUNC_ENV => asm.func_write_string(),
+
                         ASTNodeEnum::FunctionCall(fc) => {
                             // if the function returns anything, then that will be in rax
 
@@ -97,10 +571,12 @@ impl AST for FunctionCall {
                             let func_def = borrow.get(&fc.name).unwrap();
 
                             match func_def.return_type {
-                                VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 => {
+                                VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32 | VarType::Enum(..) => {
                                     asm.func_write_number(func_def.return_type.clone())
                                 }
 
+                                VarType::OwnedStr => asm.func_write_owned_string(true),
+
                                 _ => unimplemented!(),
                             };
                         }
@@ -121,6 +597,99 @@ impl AST for FunctionCall {
                 asm.func_exit();
             }
 
+            FUNC_ASSERT => {
+                self.arguments[0].borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+
+                asm.func_assert(&format!("{}\\n", self.assert_message()));
+            }
+
+            FUNC_SQRT | FUNC_ABS | FUNC_FLOOR | FUNC_CEIL | FUNC_MIN | FUNC_MAX => {
+                for arg in &self.arguments {
+                    arg.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+                }
+
+                asm.func_math(&self.name, &self.result_type);
+            }
+
+            FUNC_ALLOC | FUNC_FREE | FUNC_REALLOC => {
+                asm.function_call_prep();
+
+                for (index, arg) in self.arguments.iter().enumerate() {
+                    arg.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+                    asm.function_call_add_arg(index, arg.borrow().get_type().1);
+                }
+
+                asm.func_heap(
+                    &self.name,
+                    self.arguments.len(),
+                    &self.result_type,
+                    call_stack,
+                    self.is_result_assigned,
+                );
+            }
+
+            FUNC_LEN => {
+                self.arguments[0].borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+
+                match self.arguments[0].borrow().get_type().0 {
+                    VarType::Slice(_) => asm.slice_len(),
+                    arg_type => asm.string_len(&arg_type),
+                }
+            }
+
+            FUNC_PRINT | FUNC_FPRINT => {
+                asm.function_call_prep();
+
+                let has_fd = self.name == FUNC_FPRINT;
+                let format_index = has_fd as usize;
+
+                if has_fd {
+                    self.arguments[0].borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+                    asm.format_save_arg(&self.arguments[0].borrow().get_type().0);
+                }
+
+                let mut args = vec![];
+
+                for arg in &self.arguments[format_index + 1..] {
+                    arg.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+
+                    let arg_type = arg.borrow().get_type().0;
+                    asm.format_save_arg(&arg_type);
+
+                    args.push((arg_type, arg.borrow().get_node().is_temporary_string()));
+                }
+
+                asm.format_print(&self.format_segments, &args, has_fd, call_stack);
+            }
+
+            FUNC_FLUSH => asm.func_flush(call_stack),
+
+            FUNC_READ_LINE => {
+                asm.function_call_prep();
+
+                self.arguments[0].borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+                asm.type_cast(&self.arguments[0].borrow().get_type().0, &VarType::Int);
+                asm.function_call_add_arg(0, VarType::Int);
+
+                asm.function_call(
+                    &String::from("_readLine"),
+                    1,
+                    &self.result_type,
+                    false,
+                    call_stack,
+                    true,
+                    self.is_result_assigned,
+                );
+            }
+
+            FUNC_ENV => {
+                asm.function_call_prep();
+
+                self.arguments[0].borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+
+                asm.func_env(call_stack);
+            }
+
             FUNC_SYSCALL => {
                 asm.function_call_prep();
 
@@ -148,7 +717,7 @@ impl AST for FunctionCall {
 
                         let arg_type = argument.borrow().get_type().1;
 
-                        let arg_num = if matches!(arg_type, VarType::Float) {
+                        let arg_num = if arg_type.is_float() {
                             float_arg_num += 1;
                             float_arg_num
                         } else {
@@ -210,7 +779,7 @@ impl AST for FunctionCall {
 
                         let arg_type = argument.borrow().get_type().1;
 
-                        let arg_num = if matches!(arg_type, VarType::Float) {
+                        let arg_num = if arg_type.is_float() {
                             float_arg_num += 1;
                             float_arg_num
                         } else {
@@ -239,10 +808,17 @@ impl AST for FunctionCall {
         match self.name.as_str() {
             FUNC_WRITE => {
                 for arg in &self.arguments {
-                    // println!("Visiting func write. Arg {:?}", arg);
-                    println!("{:?}", arg.borrow().visit(v, Rc::clone(&f), call_stack));
+                    let value = arg.borrow().visit(v, Rc::clone(&f), call_stack).token;
+
+                    // like `_printRAX`, numbers are followed by a line break
+                    match *value {
+                        TokenEnum::Number(_) => println!("{}", value_text(&value)),
+                        _ => print!("{}", value_text(&value)),
+                    }
                 }
 
+                std::io::stdout().flush();
+
                 return VisitResult {
                     token: Box::new(TokenEnum::Unknown("".into())),
                 };
@@ -282,11 +858,55 @@ impl AST for FunctionCall {
                 exit(1);
             }
 
-            name => match f.borrow().get(name) {
-                Some(function_ast) => function_ast.func.borrow().visit(v, Rc::clone(&f), call_stack),
+            FUNC_ASSERT => {
+                let condition = self.arguments[0].borrow().visit(v, Rc::clone(&f), call_stack);
 
-                None => unimplemented!("Function {} unimplemented", self.name),
-            },
+                let passed = match *condition.token {
+                    TokenEnum::Bool(passed) => passed,
+                    TokenEnum::Number(Number::Integer(i)) => i != 0,
+
+                    t => {
+                        compiler_error(format!("The interpreter can't assert '{t}'"), &self.name_token);
+                        exit(1);
+                    }
+                };
+
+                if !passed {
+                    eprintln!("{}", value_text(&TokenEnum::StringLiteral(self.assert_message())));
+                    exit(1);
+                }
+
+                VisitResult {
+                    token: Box::new(TokenEnum::Unknown("".into())),
+                }
+            }
+
+            name => {
+                let arguments = self
+                    .arguments
+                    .iter()
+                    .map(|arg| {
+                        let value = arg.borrow().visit(v, Rc::clone(&f), call_stack);
+                        variable_value(&value.token, arg.borrow().get_token())
+                    })
+                    .collect();
+
+                let Some(func) = f.borrow().get(name).map(|function| Rc::clone(&function.func)) else {
+                    compiler_error(format!("The interpreter can't call '{name}'"), &self.name_token);
+                    exit(1);
+                };
+
+                let func = func.borrow();
+
+                match func.get_node() {
+                    ASTNodeEnum::FunctionDef(fd) => fd.call(arguments, v, f, call_stack),
+
+                    _ => {
+                        compiler_error(format!("The interpreter can't call '{name}'"), &self.name_token);
+                        exit(1);
+                    }
+                }
+            }
         }
     }
 
@@ -299,6 +919,10 @@ impl AST for FunctionCall {
     }
 
     fn semantic_visit(&mut self, call_stack: &mut CallStack, f: Rc<RefCell<Functions>>) {
+        if self.is_method_call {
+            self.resolve_method_call(call_stack, Rc::clone(&f));
+        }
+
         // need to do this first to compute resulting types
         for arg in &self.arguments {
             arg.borrow_mut().semantic_visit(call_stack, Rc::clone(&f));
@@ -315,12 +939,31 @@ impl AST for FunctionCall {
                 self.result_type = VarType::Int
             }
 
+            FUNC_SQRT | FUNC_ABS | FUNC_FLOOR | FUNC_CEIL => self.semantic_visit_math_function(1),
+            FUNC_MIN | FUNC_MAX => self.semantic_visit_math_function(2),
+
+            FUNC_ALLOC | FUNC_FREE | FUNC_REALLOC => self.semantic_visit_heap_function(),
+
+            FUNC_LEN => self.semantic_visit_len(),
+
+            FUNC_PRINT | FUNC_FPRINT => self.semantic_visit_print(),
+
+            FUNC_FLUSH | FUNC_READ_LINE => self.semantic_visit_io_function(),
+
+            FUNC_ENV => self.semantic_visit_env(),
+
+            FUNC_ASSERT => self.semantic_visit_assert(),
+
             _ => {
                 if self.arguments.len() > FUNCTION_ARGS_REGS.len() {
                     todo!("Functions with more than {} args not handled", FUNCTION_ARGS_REGS.len())
                 }
 
                 if let Some(function_definition) = all_functions_map_borrow.get(&self.name) {
+                    call_stack
+                        .symbols
+                        .refer(&self.name_token, function_definition.func.borrow().get_token());
+
                     if let ASTNodeEnum::FunctionDef(fd) = function_definition.func.borrow().get_node() {
                         if fd.parameters.len() != self.arguments.len() {
                             compiler_error(
@@ -337,6 +980,8 @@ impl AST for FunctionCall {
                         self.result_type = fd.return_type.clone();
 
                         for (actual_param, formal_param) in fd.parameters.iter().zip(&self.arguments) {
+                            coerce_float_literal(formal_param, &actual_param.borrow().get_type().0);
+
                             let binding = formal_param.borrow();
                             let binding = binding.get_node();
 
@@ -365,6 +1010,7 @@ impl AST for FunctionCall {
                     // This might be a function pointer
                     // TODO: Handle function pointers inside structs
                     let mut function_found = false;
+                    let mut definition = None;
 
                     if let Some(last_activation_record) = call_stack.peek() {
                         for (var_name, ar_var) in &last_activation_record.variable_members {
@@ -373,6 +1019,8 @@ impl AST for FunctionCall {
                             }
 
                             function_found = true;
+                            ar_var.borrow_mut().is_used = true;
+                            definition = Some(ar_var.borrow().get_token().clone());
 
                             match &ar_var.borrow().var_type {
                                 VarType::Function(func_def_name, params, return_type) => {
@@ -392,6 +1040,8 @@ impl AST for FunctionCall {
                                     for (index, (actual_param, formal_param)) in
                                         params.iter().zip(&self.arguments).enumerate()
                                     {
+                                        coerce_float_literal(formal_param, actual_param);
+
                                         let binding = formal_param.borrow();
                                         let binding = binding.get_node();
 
@@ -427,9 +1077,15 @@ impl AST for FunctionCall {
                         compiler_error(format!("Function '{}' is not defined", &self.name), &self.token);
                         exit(1);
                     }
+
+                    if let Some(definition) = definition {
+                        call_stack.symbols.refer(&self.name_token, &definition);
+                    }
                 }
             }
         };
+
+        call_stack.symbols.expression_type(&self.name_token, &self.result_type);
     }
 
     fn get_node(&self) -> ASTNodeEnum {
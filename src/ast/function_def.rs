@@ -1,21 +1,28 @@
 use crate::helpers::compiler_error;
+use crate::json::Json;
 use crate::lexer::types::VarType;
+use crate::parser::parse_test::is_test_function;
 use crate::trace;
 use crate::types::ASTNode;
 
-use crate::semantic_analyzer::semantic_analyzer::{ActivationRecord, ActivationRecordType, CallStack};
+use crate::semantic_analyzer::{
+    control_flow::ControlFlowGraph,
+    semantic_analyzer::{ActivationRecord, ActivationRecordType, CallStack},
+    symbols::SymbolKind,
+};
 
 use crate::{
     asm::asm::ASM,
-    interpreter::interpreter::{Functions, Variables},
+    interpreter::interpreter::{variable_token, Functions, Variables},
     lexer::{
+        keywords::RETURN,
         lexer::Token,
         tokens::{Number, TokenEnum, VariableEnum},
     },
 };
 use std::{cell::RefCell, rc::Rc};
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut};
+use super::abstract_syntax_tree::{ast_json, node_json, type_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names};
 use super::jump::JumpType;
 use super::{
     abstract_syntax_tree::{VisitResult, AST},
@@ -64,9 +71,120 @@ impl FunctionDefinition {
         );
     }
 
+    /// A function with a return type can't reach the end of its body. If it never returns at all
+    /// the error is about the function, else about the last statement of a path that doesn't
+    fn check_every_path_returns(&self, cfg: &ControlFlowGraph) {
+        let Some(last_statement) = cfg.path_without_return() else {
+            return;
+        };
+
+        match last_statement {
+            Some(token) if cfg.has_return() => compiler_error(
+                format!(
+                    "Function '{}' needs to return '{}' but a path through it ends here without returning",
+                    self.name, self.return_type
+                ),
+                &token,
+            ),
+
+            _ => self.return_type_error(),
+        }
+    }
+
+    /// main is where the program starts. It either takes nothing or the command line arguments,
+    /// and what it returns is the exit code
+    fn check_main_signature(&self) {
+        let valid_params = match self.parameters.as_slice() {
+            [] => true,
+            [param] => matches!(&param.borrow().var_type, VarType::Slice(inner) if matches!(**inner, VarType::Str)),
+            _ => false,
+        };
+
+        if !valid_params || !matches!(self.return_type, VarType::Unknown | VarType::Int) {
+            compiler_error(
+                "'main' has to be 'fun main()' or 'fun main(args: []str)', returning nothing or an 'int'",
+                &self.token,
+            );
+        }
+    }
+
     fn visit_com_external_func(&self, asm: &mut ASM) {
         asm.extern_function_def(&self.name);
     }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        let mut body = Names::default();
+
+        body.declared.extend(self.parameters.iter().map(|p| p.borrow().var_name.clone()));
+
+        used_names(&self.block, &mut body);
+
+        names.used.extend(body.used.into_iter().filter(|(name, _)| !body.declared.contains(name)));
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|p| ast_json(&*p.borrow()))
+            .collect::<Vec<Json>>();
+
+        vec![
+            ("name", self.name.as_str().into()),
+            ("parameters", parameters.into()),
+            ("return_type", type_json(&self.return_type)),
+            ("extern", self.is_extern_func.into()),
+            ("block", node_json(&self.block)),
+        ]
+    }
+
+    /// Interprets the body with the parameters set to `arguments`. The function gets its own
+    /// copy of the variables, only the globals it assigns are copied back
+    pub fn call(
+        &self,
+        arguments: Vec<VariableEnum>,
+        v: &mut Variables,
+        f: Rc<RefCell<Functions>>,
+        call_stack: &mut CallStack,
+    ) -> VisitResult {
+        if self.is_extern_func {
+            compiler_error(
+                format!("'{}' is an extern function, the interpreter can't call it", self.name),
+                &self.token,
+            );
+        }
+
+        let mut locals = Names::default();
+        locals
+            .declared
+            .extend(self.parameters.iter().map(|p| p.borrow().var_name.clone()));
+        used_names(&self.block, &mut locals);
+
+        let mut scope = v.clone();
+
+        for (param, argument) in self.parameters.iter().zip(arguments) {
+            scope.insert(param.borrow().var_name.clone(), argument);
+        }
+
+        self.block.borrow().visit(&mut scope, f, call_stack);
+
+        for (name, value) in v.iter_mut() {
+            if !locals.declared.contains(name) {
+                if let Some(assigned) = scope.get(name) {
+                    *value = assigned.clone();
+                }
+            }
+        }
+
+        let token = match scope.get(RETURN) {
+            Some(value) => variable_token(value, &self.token),
+            None => TokenEnum::Unknown("".into()),
+        };
+
+        VisitResult { token: Box::new(token) }
+    }
 }
 
 impl AST for FunctionDefinition {
@@ -88,46 +206,28 @@ impl AST for FunctionDefinition {
             call_stack.insert_variable(Rc::clone(arg));
         }
 
+        // the prologue and epilogue come from the line the function is defined on
+        asm.start_statement(&self.token);
+
         // args -> rax, rdi, rsi, rdx, r10, r8, r9
-        asm.function_def(call_stack, &self.name, self.stack_var_size, &self.parameters);
+        asm.function_def(call_stack, &self.name, self.stack_var_size, &self.parameters, &self.token);
 
         self.block.borrow().visit_com(v, f, asm, call_stack);
 
+        asm.debug_function(&self.name, &self.token, call_stack.function_variables());
+
         // pop the record here
         call_stack.pop();
 
         asm.function_def_end(&self.name);
+
+        asm.end_statement();
     }
 
     // TODO: This function will be visited twice, once when the interpreter calls visit, and
     // another when the function is actually called
     fn visit(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
-        // TODO: handle global variables and function parameters with the same name
-        for param in &self.parameters {
-            let value = match param.borrow().var_type {
-                VarType::Int => todo!(),
-                VarType::Int8 => todo!(),
-                VarType::Int16 => todo!(),
-                VarType::Int32 => todo!(),
-                VarType::Array(..) => todo!(),
-                VarType::Str => todo!(),
-                VarType::Float => todo!(),
-                VarType::Ptr(_) => todo!(),
-                VarType::Unknown => todo!(),
-                VarType::Char => todo!(),
-                VarType::Struct(_, _) => todo!(),
-                VarType::Function(_, _, _) => todo!(),
-            };
-
-            v.insert(param.borrow().var_name.clone(), VariableEnum::Number(value));
-        }
-
-        self.block.borrow().visit(v, f, call_stack);
-
-        for param in &self.parameters {
-            v.remove(&param.borrow().var_name.clone());
-        }
-
+        // defining a function doesn't run it, see `call`
         return VisitResult {
             token: Box::new(TokenEnum::Unknown("".to_string())),
         };
@@ -142,10 +242,27 @@ impl AST for FunctionDefinition {
     }
 
     fn semantic_visit(&mut self, call_stack: &mut CallStack, f: Rc<RefCell<Functions>>) {
+        let function_type = VarType::Function(
+            self.name.clone(),
+            self.parameters.iter().map(|p| p.borrow().var_type.clone()).collect(),
+            Box::new(self.return_type.clone()),
+        );
+
+        // tests can't be referred to, their token is the name of the test
+        if !is_test_function(&self.name) {
+            call_stack
+                .symbols
+                .define(&self.name, SymbolKind::Function, &self.token, &function_type);
+        }
+
         if self.is_extern_func {
             return
         }
 
+        if self.name == "main" {
+            self.check_main_signature();
+        }
+
         call_stack.push(self.name.to_string(), ActivationRecordType::Function(0));
 
         for arg in &self.parameters {
@@ -158,37 +275,15 @@ impl AST for FunctionDefinition {
 
         // In the AST of return statement we check the type of the returned value
         // But here we also need to check whether the function even returns something
+        let cfg = ControlFlowGraph::new(&self.block);
 
         if !matches!(self.return_type, VarType::Unknown) {
-            // The very last statement needs to be a return statement
-            // Else, nothing is being returned from a function that doesn't have void return type
-
-            match self.block.borrow().get_node() {
-                ASTNodeEnum::Program(program) => {
-                    match program.get_statements().last() {
-                        Some(last_statement) => match last_statement.borrow().get_node() {
-                            ASTNodeEnum::Jump(jump) => match jump.typ {
-                                JumpType::Return => {}
-
-                                JumpType::Break | JumpType::Continue  => self.return_type_error(),
-                            },
-
-                            _ => self.return_type_error(),
-                        },
-
-                        None => {
-                            // function has a return type but is empty
-                            self.return_type_error();
-                        }
-                    }
-                }
-
-                t => {
-                    unreachable!("Block inside function was of type {}, where 'Program' was expected.", t)
-                }
-            };
+            self.check_every_path_returns(&cfg);
         }
 
+        cfg.check_definite_assignment();
+        cfg.warn_unreachable(&mut call_stack.warnings);
+
         // pop the record here
         call_stack.pop();
     }
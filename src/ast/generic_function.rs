@@ -0,0 +1,90 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::json::Json;
+use crate::{
+    asm::asm::ASM,
+    interpreter::interpreter::{Functions, Variables},
+    lexer::{lexer::Token, tokens::TokenEnum, types::VarType},
+    semantic_analyzer::semantic_analyzer::CallStack,
+    types::ASTNode,
+};
+
+use super::abstract_syntax_tree::{node_json, ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+
+/// fun max<T>(a: T, b: T) -> T { ... }
+///
+/// The template itself generates no code. Every time it's used with a new set of type arguments
+/// the parser parses the template again with the type parameters bound to the type arguments, and
+/// adds the resulting function definition to `instances`
+#[derive(Debug)]
+pub struct GenericFunction {
+    name: String,
+    instances: Rc<RefCell<Vec<ASTNode>>>,
+    token: Token,
+}
+
+impl GenericFunction {
+    pub fn new(name: String, instances: Rc<RefCell<Vec<ASTNode>>>, token: Token) -> Self {
+        Self { name, instances, token }
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("name", self.name.as_str().into()),
+            (
+                "instances",
+                self.instances
+                    .borrow()
+                    .iter()
+                    .map(node_json)
+                    .collect::<Vec<Json>>()
+                    .into(),
+            ),
+        ]
+    }
+}
+
+impl AST for GenericFunction {
+    fn visit(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
+        for instance in self.instances.borrow().iter() {
+            instance.borrow().visit(v, Rc::clone(&f), call_stack);
+        }
+
+        return VisitResult {
+            token: Box::new(TokenEnum::Unknown("".into())),
+        };
+    }
+
+    fn visit_com(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
+        for instance in self.instances.borrow().iter() {
+            instance.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+        }
+    }
+
+    fn semantic_visit(&mut self, call_stack: &mut CallStack, f: Rc<RefCell<Functions>>) {
+        for instance in self.instances.borrow().iter() {
+            instance.borrow_mut().semantic_visit(call_stack, Rc::clone(&f));
+        }
+    }
+
+    fn get_token(&self) -> &Token {
+        &self.token
+    }
+
+    fn get_node(&self) -> ASTNodeEnum {
+        ASTNodeEnum::GenericFunction(self)
+    }
+
+    fn get_node_mut(&mut self) -> ASTNodeEnumMut {
+        ASTNodeEnumMut::GenericFunction(self)
+    }
+
+    fn print(&self) {
+        println!("{:#?}", self);
+    }
+
+    fn get_type(&self) -> (VarType, VarType) {
+        (VarType::Unknown, VarType::Unknown)
+    }
+}
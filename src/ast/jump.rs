@@ -1,7 +1,11 @@
+use crate::json::Json;
 use crate::{
     helpers::compiler_error,
     lexer::{lexer::Token, types::VarType},
-    semantic_analyzer::semantic_analyzer::{CallStack, PopTypes},
+    semantic_analyzer::{
+        control_flow::ControlFlowGraph,
+        semantic_analyzer::{CallStack, PopTypes},
+    },
     trace,
     types::ASTNode,
 };
@@ -10,11 +14,16 @@ use std::{cell::RefCell, process::exit, rc::Rc};
 
 use crate::{
     asm::asm::ASM,
-    interpreter::interpreter::{Functions, Variables},
-    lexer::tokens::TokenEnum,
+    interpreter::interpreter::{variable_value, Functions, Variables},
+    lexer::{
+        keywords::{BREAK, CONTINUE, RETURN},
+        tokens::{Number, TokenEnum, VariableEnum},
+    },
 };
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+use super::abstract_syntax_tree::{
+    optional_node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST,
+};
 
 #[derive(Debug)]
 pub enum JumpType {
@@ -50,13 +59,60 @@ impl Jump {
             result_type: VarType::Unknown,
         }
     }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        if let Some(return_node) = &self.return_node {
+            used_names(return_node, names);
+        }
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        let jump = match self.typ {
+            JumpType::Return => RETURN,
+            JumpType::Break => BREAK,
+            JumpType::Continue => CONTINUE,
+        };
+
+        vec![("jump", jump.into()), ("value", optional_node_json(&self.return_node))]
+    }
+
+    /// See `ASTNodeEnum::add_to_cfg`
+    pub fn add_to_cfg(&self, cfg: &mut ControlFlowGraph) {
+        if let Some(return_node) = &self.return_node {
+            cfg.read(return_node);
+        }
+
+        cfg.mark(&self.token);
+
+        match self.typ {
+            JumpType::Return => cfg.add_return(),
+            JumpType::Break => cfg.add_break(),
+            JumpType::Continue => cfg.add_continue(),
+        }
+    }
 }
 
 impl AST for Jump {
-    fn visit(&self, _v: &mut Variables, _f: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
-        todo!();
+    fn visit(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
+        // see `is_jumping`
+        let (keyword, value) = match self.typ {
+            JumpType::Return => {
+                let value = match &self.return_node {
+                    Some(node) => variable_value(&node.borrow().visit(v, f, call_stack).token, &self.token),
+                    None => VariableEnum::Number(Number::Integer(0)),
+                };
+
+                (RETURN, value)
+            }
+
+            JumpType::Break => (BREAK, VariableEnum::Number(Number::Integer(0))),
+            JumpType::Continue => (CONTINUE, VariableEnum::Number(Number::Integer(0))),
+        };
+
+        v.insert(keyword.into(), value);
 
-        // this is pretty straightforward. We simply return
         return VisitResult {
             token: Box::new(TokenEnum::Unknown("".into())),
         };
@@ -88,7 +144,7 @@ impl AST for Jump {
     }
 
     fn get_token(&self) -> &crate::lexer::lexer::Token {
-        todo!()
+        &self.token
     }
 
     fn print(&self) {
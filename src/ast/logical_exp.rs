@@ -1,16 +1,22 @@
-use crate::{lexer::types::VarType, types::ASTNode};
+use crate::json::Json;
+use crate::{helpers::compiler_error, lexer::types::VarType, types::ASTNode};
 
 use crate::semantic_analyzer::semantic_analyzer::CallStack;
 
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, process::exit, rc::Rc};
 
 use crate::{
     asm::asm::ASM,
     interpreter::interpreter::{Functions, Variables},
-    lexer::{lexer::Token, tokens::TokenEnum},
+    lexer::{
+        lexer::Token,
+        tokens::{LogicalOps, Number, TokenEnum},
+    },
 };
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+use super::abstract_syntax_tree::{
+    node_json, optional_node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST,
+};
 
 #[derive(Debug)]
 pub struct LogicalExpression {
@@ -29,17 +35,49 @@ impl LogicalExpression {
             result_type: VarType::Int,
         }
     }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        if let Some(left) = &self.left {
+            used_names(left, names);
+        }
+
+        used_names(&self.right, names);
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("left", optional_node_json(&self.left)),
+            ("operator", self.op.token.to_string().into()),
+            ("right", node_json(&self.right)),
+        ]
+    }
 }
 
 impl AST for LogicalExpression {
     fn visit(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
-        if let Some(left) = &self.left {
-            left.borrow().visit(v, Rc::clone(&f), call_stack);
+        let mut truth = |node: &ASTNode| match *node.borrow().visit(v, Rc::clone(&f), call_stack).token {
+            TokenEnum::Bool(b) => b,
+            TokenEnum::Number(Number::Integer(i)) => i != 0,
+
+            t => {
+                compiler_error(format!("'{t}' is not a boolean"), node.borrow().get_token());
+                exit(1);
+            }
         };
 
-        let _right = self.right.borrow().visit(v, Rc::clone(&f), call_stack);
+        let result = match (&self.op.token, &self.left) {
+            (TokenEnum::LogicalOp(LogicalOps::Not), _) => !truth(&self.right),
+            (TokenEnum::LogicalOp(LogicalOps::And), Some(left)) => truth(left) && truth(&self.right),
+            (TokenEnum::LogicalOp(LogicalOps::Or), Some(left)) => truth(left) || truth(&self.right),
 
-        todo!()
+            _ => unreachable!("Found {} in a logical expression", self.op),
+        };
+
+        VisitResult {
+            token: Box::new(TokenEnum::Bool(result)),
+        }
     }
 
     fn visit_com(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
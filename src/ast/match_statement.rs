@@ -0,0 +1,473 @@
+use std::{cell::RefCell, fmt::Display, process::exit, rc::Rc};
+
+use crate::json::Json;
+use crate::{
+    asm::asm::ASM,
+    helpers::compiler_error,
+    interpreter::interpreter::{variable_value, Functions, Variables},
+    lexer::{
+        lexer::Token,
+        tokens::{Number, TokenEnum},
+        types::VarType,
+    },
+    semantic_analyzer::{
+        control_flow::ControlFlowGraph,
+        semantic_analyzer::{ActivationRecordType, CallStack},
+    },
+    types::ASTNode,
+};
+
+use super::{
+    abstract_syntax_tree::{
+        add_to_cfg, ast_json, node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST,
+    },
+    variable::Variable,
+};
+
+#[derive(Debug)]
+pub enum MatchPattern {
+    /// _
+    Wildcard,
+    /// 3
+    Integer(i32),
+    /// (Enum name, Variant name)
+    EnumVariant(String, String),
+    /// (Union name, Variant name, Name the payload is bound to)
+    UnionVariant(String, String, Option<String>),
+}
+
+impl Display for MatchPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchPattern::Wildcard => write!(f, "_"),
+            MatchPattern::Integer(int) => write!(f, "{int}"),
+            MatchPattern::EnumVariant(enum_name, variant_name) => write!(f, "{enum_name}.{variant_name}"),
+            MatchPattern::UnionVariant(union_name, variant_name, binding) => match binding {
+                Some(binding) => write!(f, "{union_name}.{variant_name}({binding})"),
+                None => write!(f, "{union_name}.{variant_name}"),
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MatchArm {
+    pattern: MatchPattern,
+    token: Token,
+    block: ASTNode,
+    /// The value this arm matches on. Resolved during semantic analysis. None for the wildcard
+    value: Option<i32>,
+    /// Variable the payload of a union variant is copied into
+    binding: Option<Rc<RefCell<Variable>>>,
+}
+
+impl MatchArm {
+    pub fn new(pattern: MatchPattern, token: Token, block: ASTNode) -> Self {
+        Self {
+            value: match pattern {
+                MatchPattern::Integer(int) => Some(int),
+                _ => None,
+            },
+            pattern,
+            token,
+            block,
+            binding: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MatchStatement {
+    scrutinee: ASTNode,
+    arms: Vec<MatchArm>,
+    token: Token,
+}
+
+impl MatchStatement {
+    pub fn new(scrutinee: ASTNode, arms: Vec<MatchArm>, token: Token) -> Self {
+        Self { scrutinee, arms, token }
+    }
+
+    fn resolve_arm_value(&self, arm: &MatchArm, scrutinee_type: &VarType) -> Option<i32> {
+        match &arm.pattern {
+            MatchPattern::Wildcard => None,
+
+            MatchPattern::Integer(int) => {
+                if let VarType::Enum(name, _) = scrutinee_type {
+                    compiler_error(
+                        format!("Expected a variant of enum '{name}', found integer '{int}'"),
+                        &arm.token,
+                    );
+                }
+
+                Some(*int)
+            }
+
+            MatchPattern::EnumVariant(enum_name, variant_name) => match scrutinee_type {
+                VarType::Enum(name, variants) => {
+                    if name != enum_name {
+                        compiler_error(
+                            format!("Expected a variant of enum '{name}', found '{}'", arm.pattern),
+                            &arm.token,
+                        );
+                    }
+
+                    match variants.iter().find(|v| v.name == *variant_name) {
+                        Some(variant) => Some(variant.discriminant),
+
+                        None => {
+                            compiler_error(format!("Enum '{name}' has no variant '{variant_name}'"), &arm.token);
+                            exit(1);
+                        }
+                    }
+                }
+
+                _ => {
+                    compiler_error(
+                        format!("Cannot match '{scrutinee_type}' against enum variant '{}'", arm.pattern),
+                        &arm.token,
+                    );
+                    exit(1);
+                }
+            },
+
+            MatchPattern::UnionVariant(union_name, variant_name, _) => match scrutinee_type.get_pointer_type() {
+                VarType::Union(name, variants) => {
+                    if name != *union_name {
+                        compiler_error(
+                            format!("Expected a variant of union '{name}', found '{}'", arm.pattern),
+                            &arm.token,
+                        );
+                    }
+
+                    // the tag of a variant is its index
+                    match variants.borrow().iter().position(|v| v.name == *variant_name) {
+                        Some(tag) => Some(tag as i32),
+
+                        None => {
+                            compiler_error(format!("Union '{name}' has no variant '{variant_name}'"), &arm.token);
+                            exit(1);
+                        }
+                    }
+                }
+
+                _ => {
+                    compiler_error(
+                        format!("Cannot match '{scrutinee_type}' against union variant '{}'", arm.pattern),
+                        &arm.token,
+                    );
+                    exit(1);
+                }
+            },
+        }
+    }
+
+    /// Creates the variable the payload of the union variant is bound to, if any
+    fn create_binding(&self, arm: &MatchArm, scrutinee_type: &VarType) -> Option<Rc<RefCell<Variable>>> {
+        if let MatchPattern::UnionVariant(_, variant_name, Some(binding)) = &arm.pattern {
+            if let VarType::Union(_, variants) = scrutinee_type.get_pointer_type() {
+                let borrow = variants.borrow();
+                let variant = borrow.iter().find(|v| v.name == *variant_name).unwrap();
+
+                return Some(Rc::new(RefCell::new(Variable::new(
+                    Box::new(arm.token.clone()),
+                    variant.member_type.clone(),
+                    binding.clone(),
+                    false,
+                    false,
+                    0,
+                ))));
+            }
+        }
+
+        return None;
+    }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        used_names(&self.scrutinee, names);
+
+        for arm in &self.arms {
+            if let Some(binding) = &arm.binding {
+                names.declared.push(binding.borrow().var_name.clone());
+            }
+
+            used_names(&arm.block, names);
+        }
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        let arms = self
+            .arms
+            .iter()
+            .map(|arm| {
+                let pattern = match &arm.pattern {
+                    MatchPattern::Wildcard => "_".into(),
+                    MatchPattern::Integer(int) => int.to_string(),
+                    MatchPattern::EnumVariant(name, variant) | MatchPattern::UnionVariant(name, variant, _) => {
+                        format!("{name}.{variant}")
+                    }
+                };
+
+                Json::object([
+                    ("pattern", pattern.into()),
+                    (
+                        "binding",
+                        arm.binding.as_ref().map_or(Json::Null, |b| ast_json(&*b.borrow())),
+                    ),
+                    ("block", node_json(&arm.block)),
+                ])
+            })
+            .collect::<Vec<Json>>();
+
+        vec![("scrutinee", node_json(&self.scrutinee)), ("arms", arms.into())]
+    }
+
+    /// See `ASTNodeEnum::add_to_cfg`
+    pub fn add_to_cfg(&self, cfg: &mut ControlFlowGraph) {
+        cfg.read(&self.scrutinee);
+        cfg.mark(&self.token);
+
+        let scrutinee = cfg.current();
+        let after = cfg.new_block();
+
+        // matches are exhaustive, so one of the arms always runs
+        for arm in &self.arms {
+            cfg.start_block(&[scrutinee]);
+
+            if let Some(binding) = &arm.binding {
+                cfg.declare(&binding.borrow(), true);
+            }
+
+            add_to_cfg(&arm.block, cfg);
+            cfg.add_edge(cfg.current(), after);
+        }
+
+        cfg.set_current(after);
+    }
+}
+
+impl AST for MatchStatement {
+    fn visit_com(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
+        let current_num_match = asm.num_matches;
+        asm.inc_num_matches();
+
+        self.scrutinee.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+
+        // for unions we get the address, which is needed to copy out the payload in the arms
+        let union_address = match self.scrutinee.borrow().get_type().0.get_pointer_type() {
+            VarType::Union(..) => Some(asm.match_load_union_tag()),
+            _ => None,
+        };
+
+        let arm_values: Vec<Option<i32>> = self.arms.iter().map(|arm| arm.value).collect();
+
+        asm.match_start(&arm_values, current_num_match);
+
+        for (index, arm) in self.arms.iter().enumerate() {
+            asm.match_arm_start(index, current_num_match);
+
+            call_stack.push("".into(), ActivationRecordType::IfElse);
+
+            if let (Some(binding), Some(address)) = (&arm.binding, union_address) {
+                call_stack.insert_variable(Rc::clone(binding));
+                asm.match_bind_union_payload(address, &binding.borrow());
+            }
+
+            arm.block.borrow().visit_com(v, Rc::clone(&f), asm, call_stack);
+            call_stack.pop();
+
+            asm.match_arm_end(current_num_match);
+        }
+
+        asm.match_end(current_num_match);
+
+        if let Some(address) = union_address {
+            asm.unlock_register(address);
+        }
+    }
+
+    fn visit(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
+        let (value, payload) = match *self.scrutinee.borrow().visit(v, Rc::clone(&f), call_stack).token {
+            TokenEnum::Number(Number::Integer(int)) => (int, None),
+            TokenEnum::Union(_, _, tag, payload) => (tag as i32, Some(payload)),
+
+            t => {
+                compiler_error(format!("Cannot match on '{t}'"), &self.token);
+                exit(1);
+            }
+        };
+
+        for arm in &self.arms {
+            if arm.value.is_some() && arm.value != Some(value) {
+                continue;
+            }
+
+            let (binding, payload) = match (&arm.binding, payload) {
+                (Some(binding), Some(payload)) => (binding.borrow().var_name.clone(), payload),
+
+                _ => return arm.block.borrow().visit(v, Rc::clone(&f), call_stack),
+            };
+
+            // the binding is only in scope for the arm
+            let hidden = v.get(&binding).cloned();
+            v.insert(binding.clone(), variable_value(&payload, &arm.token));
+
+            let result = arm.block.borrow().visit(v, Rc::clone(&f), call_stack);
+
+            match hidden {
+                Some(value) => v.insert(binding, value),
+                None => v.remove(&binding),
+            };
+
+            return result;
+        }
+
+        return VisitResult {
+            token: Box::new(TokenEnum::Unknown("".into())),
+        };
+    }
+
+    fn semantic_visit(&mut self, call_stack: &mut CallStack, f: Rc<RefCell<Functions>>) {
+        self.scrutinee.borrow_mut().semantic_visit(call_stack, Rc::clone(&f));
+
+        let scrutinee_type = self.scrutinee.borrow().get_type().0;
+
+        match scrutinee_type.get_pointer_type() {
+            VarType::Int
+            | VarType::Int8
+            | VarType::Int16
+            | VarType::Int32
+            | VarType::Char
+            | VarType::Enum(..)
+            | VarType::Union(..) => {}
+
+            _ => {
+                compiler_error(
+                    format!("Cannot match on '{scrutinee_type}'. Only integers, enums and unions can be matched on"),
+                    &self.token,
+                );
+            }
+        }
+
+        if matches!(scrutinee_type, VarType::Ptr(..)) && !matches!(scrutinee_type.get_pointer_type(), VarType::Union(..))
+        {
+            compiler_error(format!("Cannot match on '{scrutinee_type}'"), &self.token);
+        }
+
+        // (value, line number of the arm that matches it)
+        let mut matched_values: Vec<(i32, usize)> = vec![];
+        let mut wildcard_line: Option<usize> = None;
+
+        for index in 0..self.arms.len() {
+            let arm = &self.arms[index];
+
+            if let Some(line) = wildcard_line {
+                compiler_error(
+                    format!("Match arm '{}' is unreachable as '_' on line {line} matches everything", arm.pattern),
+                    &arm.token,
+                );
+            }
+
+            let value = self.resolve_arm_value(arm, &scrutinee_type);
+
+            match value {
+                Some(value) => {
+                    if let Some((_, line)) = matched_values.iter().find(|(v, _)| *v == value) {
+                        compiler_error(
+                            format!(
+                                "Match arm '{}' is unreachable as it's already matched on line {line}",
+                                arm.pattern
+                            ),
+                            &arm.token,
+                        );
+                    }
+
+                    matched_values.push((value, arm.token.line_number));
+                }
+
+                None => wildcard_line = Some(arm.token.line_number),
+            }
+
+            let binding = self.create_binding(arm, &scrutinee_type);
+
+            self.arms[index].value = value;
+            self.arms[index].binding = binding.clone();
+
+            call_stack.push("".into(), ActivationRecordType::IfElse);
+
+            if let Some(binding) = binding {
+                call_stack.insert_variable(binding);
+            }
+
+            self.arms[index].block.borrow_mut().semantic_visit(call_stack, Rc::clone(&f));
+            call_stack.pop();
+        }
+
+        if wildcard_line.is_some() {
+            return;
+        }
+
+        match &scrutinee_type.get_pointer_type() {
+            VarType::Union(name, variants) => {
+                let missing: Vec<String> = variants
+                    .borrow()
+                    .iter()
+                    .enumerate()
+                    .filter(|(tag, _)| !matched_values.iter().any(|(v, _)| *v == *tag as i32))
+                    .map(|(_, variant)| format!("'{}'", variant.name))
+                    .collect();
+
+                if !missing.is_empty() {
+                    compiler_error(
+                        format!("Match on '{name}' is not exhaustive. Missing: {}", missing.join(", ")),
+                        &self.token,
+                    );
+                }
+            }
+
+            VarType::Enum(name, variants) => {
+                let missing: Vec<String> = variants
+                    .iter()
+                    .filter(|variant| !matched_values.iter().any(|(v, _)| *v == variant.discriminant))
+                    .map(|variant| format!("'{}'", variant.name))
+                    .collect();
+
+                if !missing.is_empty() {
+                    compiler_error(
+                        format!("Match on '{name}' is not exhaustive. Missing: {}", missing.join(", ")),
+                        &self.token,
+                    );
+                }
+            }
+
+            _ => {
+                compiler_error(
+                    format!("Match on '{scrutinee_type}' is not exhaustive. Add a '_' arm to handle all other values"),
+                    &self.token,
+                );
+            }
+        }
+    }
+
+    fn get_token(&self) -> &Token {
+        return &self.token;
+    }
+
+    fn get_node(&self) -> ASTNodeEnum {
+        return ASTNodeEnum::Match(&self);
+    }
+
+    fn get_node_mut(&mut self) -> ASTNodeEnumMut {
+        return ASTNodeEnumMut::Match(self);
+    }
+
+    fn get_type(&self) -> (VarType, VarType) {
+        return (VarType::Unknown, VarType::Unknown);
+    }
+
+    fn print(&self) {
+        println!("{:#?}", self);
+    }
+}
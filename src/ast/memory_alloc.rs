@@ -1,19 +1,21 @@
 use std::{cell::RefCell, process::exit, rc::Rc};
 
+use crate::json::Json;
 use crate::{
     asm::asm::ASM,
     helpers,
     interpreter::interpreter::{Functions, Variables},
     lexer::{
+        lexer::Token,
         tokens::{Number, TokenEnum},
         types::VarType,
     },
-    semantic_analyzer::semantic_analyzer::CallStack,
+    semantic_analyzer::{control_flow::ControlFlowGraph, semantic_analyzer::CallStack},
     trace,
 };
 
 use super::{
-    abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST},
+    abstract_syntax_tree::{ast_json, node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST},
     variable::Variable,
 };
 
@@ -22,16 +24,44 @@ pub struct MemoryAlloc {
     variable: Rc<RefCell<Variable>>,
     size: Rc<RefCell<Box<dyn AST>>>,
     pub result_type: VarType,
+    /// The token of the variable, `variable` can't lend it out of the RefCell
+    token: Token,
 }
 
 impl MemoryAlloc {
     pub fn new(variable: Rc<RefCell<Variable>>, size: Rc<RefCell<Box<dyn AST>>>) -> Self {
+        let token = variable.borrow().get_token().clone();
+
         Self {
             variable,
             size,
             result_type: VarType::Ptr(Box::new(VarType::Int)),
+            token,
         }
     }
+
+    pub fn get_variable(&self) -> &Rc<RefCell<Variable>> {
+        &self.variable
+    }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        names.declared.push(self.variable.borrow().var_name.clone());
+        used_names(&self.size, names);
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("variable", ast_json(&*self.variable.borrow())),
+            ("size", node_json(&self.size)),
+        ]
+    }
+
+    /// See `ASTNodeEnum::add_to_cfg`
+    pub fn add_to_cfg(&self, cfg: &mut ControlFlowGraph) {
+        cfg.declare(&self.variable.borrow(), true);
+    }
 }
 
 impl AST for MemoryAlloc {
@@ -88,11 +118,11 @@ impl AST for MemoryAlloc {
     }
 
     fn get_token(&self) -> &crate::lexer::lexer::Token {
-        todo!()
+        &self.token
     }
 
     fn get_node(&self) -> ASTNodeEnum {
-        todo!()
+        ASTNodeEnum::MemoryAlloc(self)
     }
 
     fn get_node_mut(&mut self) -> ASTNodeEnumMut {
@@ -7,13 +7,18 @@ pub mod comparison_exp;
 pub mod conditionals;
 pub mod declaration_statement;
 pub mod factor;
+pub mod format_string;
 pub mod function_call;
 pub mod function_def;
+pub mod generic_function;
 pub mod jump;
 pub mod logical_exp;
+pub mod match_statement;
 pub mod memory_alloc;
 pub mod program;
 pub mod structs;
+pub mod type_cast;
 pub mod typedef;
+pub mod union_variant;
 pub mod variable;
 pub mod void;
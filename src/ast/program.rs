@@ -1,16 +1,21 @@
+use crate::json::Json;
 use crate::lexer::types::VarType;
 use crate::types::ASTNode;
 
-use crate::semantic_analyzer::semantic_analyzer::CallStack;
+use crate::semantic_analyzer::{
+    control_flow::ControlFlowGraph, global_order::order_globals, semantic_analyzer::CallStack,
+};
 
 use crate::{
     asm::asm::ASM,
-    interpreter::interpreter::{Functions, Variables},
+    interpreter::interpreter::{is_jumping, Functions, Variables},
     lexer::tokens::TokenEnum,
 };
 use std::{cell::RefCell, rc::Rc};
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+use super::abstract_syntax_tree::{
+    add_to_cfg, node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST,
+};
 
 #[derive(Debug)]
 pub struct Program {
@@ -25,12 +30,66 @@ impl Program {
     pub fn get_statements(&self) -> &Vec<ASTNode> {
         return &self.statements;
     }
+
+    /// See `order_globals`
+    pub fn order_globals(&mut self, functions: &Functions) {
+        self.statements = order_globals(&self.statements, functions);
+    }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        for statement in &self.statements {
+            used_names(statement, names);
+        }
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![(
+            "statements",
+            self.statements.iter().map(node_json).collect::<Vec<Json>>().into(),
+        )]
+    }
+
+    /// See `ASTNodeEnum::add_to_cfg`
+    pub fn add_to_cfg(&self, cfg: &mut ControlFlowGraph) {
+        for statement in &self.statements {
+            add_to_cfg(statement, cfg);
+        }
+    }
+}
+
+/// Statements whose code goes where they are written. Definitions are generated elsewhere, and a
+/// nested program is made up of its own statements
+fn has_source_line(node: &ASTNodeEnum) -> bool {
+    !matches!(
+        node,
+        ASTNodeEnum::Program(_)
+            | ASTNodeEnum::FunctionDef(_)
+            | ASTNodeEnum::GenericFunction(_)
+            | ASTNodeEnum::Struct(_)
+            | ASTNodeEnum::Array(_)
+            | ASTNodeEnum::Void
+    )
 }
 
 impl AST for Program {
     fn visit_com(&self, x: &mut Variables, f: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
         for statement in &self.statements {
-            statement.borrow().visit_com(x, Rc::clone(&f), asm, call_stack);
+            let statement = statement.borrow();
+
+            let has_source_line = has_source_line(&statement.get_node());
+
+            if has_source_line {
+                asm.start_statement(statement.get_token());
+                asm.debug_line(statement.get_token());
+            }
+
+            statement.visit_com(x, Rc::clone(&f), asm, call_stack);
+
+            if has_source_line {
+                asm.end_statement();
+            }
         }
     }
 
@@ -40,6 +99,10 @@ impl AST for Program {
         for statement in &self.statements {
             let result = statement.borrow().visit(x, Rc::clone(&f), call_stack);
             last = Some(result);
+
+            if is_jumping(x) {
+                break;
+            }
         }
 
         if let Some(res) = last {
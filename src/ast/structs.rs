@@ -1,6 +1,7 @@
 use core::panic;
 use std::{cell::RefCell, rc::Rc};
 
+use crate::json::Json;
 use crate::{
     asm::asm::ASM,
     helpers::compiler_error,
@@ -15,7 +16,8 @@ use crate::{
 };
 
 use super::{
-    abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST},
+    abstract_syntax_tree::{node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST},
+    factor::coerce_float_literal,
     variable::Variable,
 };
 
@@ -53,6 +55,24 @@ impl StructDecleration {
 
         return member_order;
     }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        for member in &self.members {
+            used_names(&member.rhs, names);
+        }
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        let members = self
+            .members
+            .iter()
+            .map(|member| Json::object([("name", member.name.as_str().into()), ("value", node_json(&member.rhs))]))
+            .collect::<Vec<Json>>();
+
+        vec![("name", self.name.as_str().into()), ("members", members.into())]
+    }
 }
 
 impl AST for StructDecleration {
@@ -90,7 +110,7 @@ impl AST for StructDecleration {
 
         match &struct_type.type_ {
             VarType::Struct(_, members) => {
-                for (index, member_type) in member_types.iter().enumerate() {
+                for (index, member_type) in member_types.iter_mut().enumerate() {
                     let borrowed = members.borrow();
                     let found = borrowed.iter().find(|x| x.name == member_type.name);
 
@@ -104,6 +124,9 @@ impl AST for StructDecleration {
 
                     let found = found.unwrap();
 
+                    coerce_float_literal(&self.members[index].rhs, &found.member_type);
+                    member_type.member_type = self.members[index].rhs.borrow().get_type().0;
+
                     if !member_type.member_type.can_assign(&found.member_type) {
                         compiler_error(
                             format!(
@@ -0,0 +1,204 @@
+use std::{cell::RefCell, process::exit, rc::Rc};
+
+use crate::json::Json;
+use crate::{
+    asm::asm::ASM,
+    helpers::compiler_error,
+    interpreter::interpreter::{value_text, Functions, Variables},
+    lexer::{
+        lexer::Token,
+        tokens::{Number, TokenEnum},
+        types::VarType,
+    },
+    semantic_analyzer::semantic_analyzer::CallStack,
+    types::ASTNode,
+};
+
+use super::abstract_syntax_tree::{
+    node_json, type_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST,
+};
+
+/// x as float, (a + b) as int16
+///
+/// Converts a number from one numeric type to another. Casts to pointers only reinterpret the
+/// variable and are handled by `Variable::type_cast`
+///
+/// Numbers and strs can also be cast to a String, which formats or copies them into a new one, and
+/// a String can be cast to a str that points into it
+#[derive(Debug)]
+pub struct TypeCast {
+    pub expression: ASTNode,
+    pub from_type: VarType,
+    pub result_type: VarType,
+    token: Token,
+}
+
+impl TypeCast {
+    pub fn new(expression: ASTNode, to_type: VarType, token: Token) -> Self {
+        Self {
+            expression,
+            from_type: VarType::Unknown,
+            result_type: to_type,
+            token,
+        }
+    }
+
+    fn is_numeric(var_type: &VarType) -> bool {
+        matches!(
+            var_type,
+            VarType::Int
+                | VarType::Int8
+                | VarType::Int16
+                | VarType::Int32
+                | VarType::Char
+                | VarType::Float
+                | VarType::Float32
+        )
+    }
+
+    fn is_string_cast(&self) -> bool {
+        match (&self.from_type, &self.result_type) {
+            (VarType::OwnedStr, VarType::Str | VarType::OwnedStr) => true,
+
+            (from, VarType::OwnedStr) => {
+                matches!(from, VarType::Str) || (TypeCast::is_numeric(from) && !matches!(from, VarType::Char))
+            }
+
+            _ => false,
+        }
+    }
+
+    /// What the interpreter gets for `number as to_type`. Like the compiled casts, floats are
+    /// truncated and narrower ints keep the low bits
+    fn cast_number(number: &Number, to_type: &VarType) -> Number {
+        let int = match number {
+            Number::Integer(i) => *i as i64,
+            Number::Float(f) => *f as i64,
+        };
+
+        let float = match number {
+            Number::Integer(i) => *i as f64,
+            Number::Float(f) => *f,
+        };
+
+        match to_type {
+            VarType::Float => Number::Float(float),
+            VarType::Float32 => Number::Float(float as f32 as f64),
+            VarType::Int8 => Number::Integer(int as i8 as i32),
+            VarType::Int16 => Number::Integer(int as i16 as i32),
+            // chars are unsigned
+            VarType::Char => Number::Integer(int as u8 as i32),
+            _ => Number::Integer(int as i32),
+        }
+    }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        used_names(&self.expression, names);
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("expression", node_json(&self.expression)),
+            ("from", type_json(&self.from_type)),
+        ]
+    }
+}
+
+impl AST for TypeCast {
+    fn visit(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
+        let value = self.expression.borrow().visit(v, f, call_stack).token;
+
+        let result = match (*value, &self.result_type) {
+            // the interpreter has only one kind of string
+            (TokenEnum::StringLiteral(s), VarType::Str | VarType::OwnedStr) => TokenEnum::StringLiteral(s),
+
+            (TokenEnum::Number(number), VarType::OwnedStr) => {
+                TokenEnum::StringLiteral(value_text(&TokenEnum::Number(number)))
+            }
+
+            (TokenEnum::Number(number), to_type) => TokenEnum::Number(TypeCast::cast_number(&number, to_type)),
+
+            (TokenEnum::Bool(b), to_type) => {
+                TokenEnum::Number(TypeCast::cast_number(&Number::Integer(b as i32), to_type))
+            }
+
+            (value, to_type) => {
+                compiler_error(
+                    format!("The interpreter can't cast '{value}' to '{to_type}'"),
+                    &self.token,
+                );
+                exit(1);
+            }
+        };
+
+        VisitResult {
+            token: Box::new(result),
+        }
+    }
+
+    fn visit_com(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
+        if self.is_string_cast() {
+            // making a String is a call into the String runtime
+            let allocates =
+                matches!(self.result_type, VarType::OwnedStr) && !matches!(self.from_type, VarType::OwnedStr);
+
+            if allocates {
+                asm.function_call_prep();
+            }
+
+            self.expression.borrow().visit_com(v, f, asm, call_stack);
+
+            asm.string_cast(&self.from_type, &self.result_type, call_stack);
+            return;
+        }
+
+        self.expression.borrow().visit_com(v, f, asm, call_stack);
+
+        asm.type_cast(&self.from_type, &self.result_type);
+    }
+
+    fn semantic_visit(&mut self, call_stack: &mut CallStack, f: Rc<RefCell<Functions>>) {
+        self.expression.borrow_mut().semantic_visit(call_stack, f);
+
+        self.from_type = self.expression.borrow().get_type().0;
+
+        call_stack.symbols.expression_type(&self.token, &self.result_type);
+
+        if self.is_string_cast() {
+            return;
+        }
+
+        // an enum is converted to its discriminant, but an int can't be converted to an enum as it
+        // might not be a valid variant
+        let from_okay = TypeCast::is_numeric(&self.from_type) || matches!(self.from_type, VarType::Enum(..));
+
+        if !from_okay || !TypeCast::is_numeric(&self.result_type) {
+            compiler_error(
+                format!("Cannot cast '{}' to '{}'", self.from_type, self.result_type),
+                &self.token,
+            );
+        }
+    }
+
+    fn get_token(&self) -> &Token {
+        &self.token
+    }
+
+    fn get_node(&self) -> ASTNodeEnum {
+        ASTNodeEnum::TypeCast(self)
+    }
+
+    fn get_node_mut(&mut self) -> ASTNodeEnumMut {
+        ASTNodeEnumMut::TypeCast(self)
+    }
+
+    fn print(&self) {
+        println!("{:#?}", self);
+    }
+
+    fn get_type(&self) -> (VarType, VarType) {
+        (self.result_type.clone(), self.result_type.clone())
+    }
+}
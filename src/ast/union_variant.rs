@@ -0,0 +1,148 @@
+use std::{cell::RefCell, process::exit, rc::Rc};
+
+use crate::json::Json;
+use crate::{
+    asm::asm::ASM,
+    helpers::compiler_error,
+    interpreter::interpreter::{Functions, Variables},
+    lexer::{lexer::Token, tokens::TokenEnum, types::VarType},
+    semantic_analyzer::semantic_analyzer::CallStack,
+    types::ASTNode,
+};
+
+use super::abstract_syntax_tree::{node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST};
+use super::factor::coerce_float_literal;
+
+/// Result.Ok(value)
+#[derive(Debug)]
+pub struct UnionVariant {
+    pub union_name: String,
+    pub variant_name: String,
+    payload: ASTNode,
+    /// index of the variant in the union definition
+    tag: usize,
+    pub result_type: VarType,
+    token: Token,
+}
+
+impl UnionVariant {
+    pub fn new(union_name: String, variant_name: String, payload: ASTNode, token: Token) -> Self {
+        Self {
+            union_name,
+            variant_name,
+            payload,
+            tag: 0,
+            result_type: VarType::Unknown,
+            token,
+        }
+    }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        used_names(&self.payload, names);
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("union", self.union_name.as_str().into()),
+            ("variant", self.variant_name.as_str().into()),
+            ("payload", node_json(&self.payload)),
+        ]
+    }
+}
+
+impl AST for UnionVariant {
+    fn visit(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
+        let payload = self.payload.borrow().visit(v, f, call_stack).token;
+
+        VisitResult {
+            token: Box::new(TokenEnum::Union(
+                self.union_name.clone(),
+                self.variant_name.clone(),
+                self.tag,
+                payload,
+            )),
+        }
+    }
+
+    fn visit_com(&self, v: &mut Variables, f: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
+        self.payload.borrow().visit_com(v, f, asm, call_stack);
+
+        // the tag is popped first while assigning
+        asm.stack_push(self.tag.to_string());
+    }
+
+    fn semantic_visit(&mut self, call_stack: &mut CallStack, f: Rc<RefCell<Functions>>) {
+        self.payload.borrow_mut().semantic_visit(call_stack, f);
+
+        let union_type = call_stack.user_defined_types.iter().find(|x| x.name == self.union_name);
+
+        let union_type = match union_type {
+            Some(t) => t.type_.clone(),
+
+            None => {
+                compiler_error(format!("Type '{}' not defined", self.union_name), &self.token);
+                exit(1);
+            }
+        };
+
+        if let VarType::Union(_, variants) = &union_type {
+            let borrow = variants.borrow();
+
+            let found = borrow.iter().enumerate().find(|(_, v)| v.name == self.variant_name);
+
+            let (tag, variant) = match found {
+                Some(f) => f,
+
+                None => {
+                    compiler_error(
+                        format!("Union '{}' has no variant '{}'", self.union_name, self.variant_name),
+                        &self.token,
+                    );
+                    exit(1);
+                }
+            };
+
+            coerce_float_literal(&self.payload, &variant.member_type);
+
+            let payload_type = self.payload.borrow().get_type().0;
+
+            if !payload_type.can_assign(&variant.member_type) {
+                compiler_error(
+                    format!(
+                        "Variant '{}' of union {} holds type {}, but got {}",
+                        self.variant_name, self.union_name, variant.member_type, payload_type
+                    ),
+                    self.payload.borrow().get_token(),
+                );
+            }
+
+            self.tag = tag;
+        } else {
+            compiler_error(format!("'{}' is not a union", self.union_name), &self.token);
+        }
+
+        self.result_type = union_type;
+    }
+
+    fn get_token(&self) -> &Token {
+        &self.token
+    }
+
+    fn get_node(&self) -> ASTNodeEnum {
+        ASTNodeEnum::UnionVariant(self)
+    }
+
+    fn get_node_mut(&mut self) -> ASTNodeEnumMut {
+        ASTNodeEnumMut::UnionVariant(self)
+    }
+
+    fn print(&self) {
+        println!("{:#?}", self);
+    }
+
+    fn get_type(&self) -> (VarType, VarType) {
+        (self.result_type.clone(), self.result_type.clone())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::json::Json;
 use crate::{
     helpers::{self, compiler_error},
     lexer::types::{VarType, TYPE_FLOAT, TYPE_INT, TYPE_STRING},
@@ -7,18 +8,20 @@ use crate::{
 };
 
 use core::panic;
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, process::exit, rc::Rc};
 
 use crate::{
     asm::asm::ASM,
-    interpreter::interpreter::{Functions, Variables},
+    interpreter::interpreter::{variable_token, Functions, Variables},
     lexer::{
         lexer::Token,
         tokens::{Number, VariableEnum},
     },
 };
 
-use super::abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, VisitResult, AST};
+use super::abstract_syntax_tree::{
+    optional_node_json, used_names, ASTNodeEnum, ASTNodeEnumMut, Names, VisitResult, AST,
+};
 
 #[derive(Debug, Clone)]
 pub struct Variable {
@@ -46,11 +49,21 @@ pub struct Variable {
     pub type_cast: Option<VarType>,
     pub array_aceess_index: Option<ASTNode>,
 
+    /// string[start..end], the start is in array_aceess_index
+    pub slice_end: Option<ASTNode>,
+
     /// if it's a.b.c.d then the var_name is 'a'
     /// and member_access contains ['b', 'c']
     pub member_access: Vec<String>,
+    /// Where each of `member_access` is written
+    pub member_tokens: Vec<Token>,
 
     pub is_const: bool,
+
+    /// Whether the variable is read or assigned anywhere after it's declared. Only set on the
+    /// variable in the call stack
+    pub is_used: bool,
+    pub is_parameter: bool,
 }
 
 impl Variable {
@@ -74,8 +87,12 @@ impl Variable {
             is_memory_block: false,
             type_cast: None,
             array_aceess_index: None,
+            slice_end: None,
             member_access: vec![],
+            member_tokens: vec![],
             is_const: false,
+            is_used: false,
+            is_parameter: false,
         }
     }
 
@@ -97,32 +114,107 @@ impl Variable {
             VarType::Int16 => todo!(),
             VarType::Int32 => todo!(),
             VarType::Str => VariableEnum::String(String::from("")),
-            VarType::Float => todo!(),
+            VarType::OwnedStr => todo!(),
+            VarType::Float | VarType::Float32 => todo!(),
             VarType::Char => todo!(),
             VarType::Ptr(_) => todo!(),
             VarType::Unknown => todo!(),
             VarType::Array(..) => todo!(),
+            VarType::Slice(_) => todo!(),
             VarType::Struct(_, _) => todo!(),
             VarType::Function(_, _, _) => todo!(),
+            VarType::Enum(..) => todo!(),
+            VarType::Union(..) => todo!(),
         };
     }
 
     pub fn store_result_type(&mut self) {
         self.result_type = self.result_type.clone();
     }
+
+    /// See `ASTNodeEnum::used_names`
+    pub fn used_names(&self, names: &mut Names) {
+        names.used.push((self.var_name.clone(), (*self.token).clone()));
+
+        if self.store_address {
+            names.addressed.push(self.var_name.clone());
+        }
+
+        for index in [&self.array_aceess_index, &self.slice_end].into_iter().flatten() {
+            used_names(index, names);
+        }
+    }
+
+    /// See `ASTNodeEnum::json_fields`
+    pub fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        let members = self
+            .member_access
+            .iter()
+            .map(|m| m.as_str().into())
+            .collect::<Vec<Json>>();
+
+        vec![
+            ("name", self.var_name.as_str().into()),
+            ("members", members.into()),
+            ("index", optional_node_json(&self.array_aceess_index)),
+            ("slice_end", optional_node_json(&self.slice_end)),
+            ("dereference", self.dereference.into()),
+            ("address", self.store_address.into()),
+        ]
+    }
 }
 
 impl AST for Variable {
     fn visit_com(&self, x: &mut Variables, f: Rc<RefCell<Functions>>, asm: &mut ASM, call_stack: &mut CallStack) {
+        if let (Some(start), Some(end)) = (&self.array_aceess_index, &self.slice_end) {
+            asm.function_call_prep();
+
+            asm.gen_asm_for_var(self, Rc::clone(&f), call_stack);
+
+            for index in [start, end] {
+                index.borrow().visit_com(x, Rc::clone(&f), asm, call_stack);
+                asm.type_cast(&index.borrow().get_type().0, &VarType::Int);
+            }
+
+            asm.string_slice(&self.var_type, call_stack);
+            return;
+        }
+
         if let Some(ast_node) = &self.array_aceess_index {
             ast_node.borrow().visit_com(x, Rc::clone(&f), asm, call_stack);
+
+            if let VarType::Slice(_) = self.var_type {
+                asm.type_cast(&ast_node.borrow().get_type().0, &VarType::Int);
+            }
         }
 
         asm.gen_asm_for_var(&self, f, &call_stack);
     }
 
     fn visit(&self, v: &mut Variables, _: Rc<RefCell<Functions>>, call_stack: &mut CallStack) -> VisitResult {
-        todo!()
+        let is_plain = !self.dereference
+            && !self.store_address
+            && self.array_aceess_index.is_none()
+            && self.member_access.is_empty();
+
+        if !is_plain {
+            compiler_error(
+                format!("The interpreter can only read '{}' as it is", self.var_name),
+                &self.token,
+            );
+        }
+
+        let Some(value) = v.get(&self.var_name) else {
+            compiler_error(
+                format!("Variable with name '{}' not found in current scope", self.var_name),
+                &self.token,
+            );
+            exit(1);
+        };
+
+        VisitResult {
+            token: Box::new(variable_token(value, &self.token)),
+        }
     }
 
     fn get_token(&self) -> &Token {
@@ -136,7 +228,12 @@ impl AST for Variable {
     fn semantic_visit(&mut self, call_stack: &mut CallStack, f: Rc<RefCell<Functions>>) {
         let (variable_in_stack, _) = call_stack.get_var_with_name(&self.var_name);
 
-        if let Some(variable_in_stack) = variable_in_stack {
+        if let Some(variable_in_stack) = variable_in_stack.cloned() {
+            // the variable being declared is borrowed, it's its own definition
+            if let Ok(definition) = variable_in_stack.try_borrow() {
+                call_stack.symbols.refer(&self.token, definition.get_token());
+            }
+
             if let Some(casted_type) = &self.type_cast {
                 self.var_type = casted_type.clone();
             } else {
@@ -165,10 +262,39 @@ impl AST for Variable {
 
             self.result_type = self.var_type.clone();
 
-            if let Some(ast_node) = &self.array_aceess_index {
+            if let (Some(start), Some(end)) = (&self.array_aceess_index, &self.slice_end) {
+                for index in [start, end] {
+                    index.borrow_mut().semantic_visit(call_stack, Rc::clone(&f));
+
+                    let index_type = index.borrow().get_type().0;
+
+                    if !matches!(index_type, VarType::Int | VarType::Int8 | VarType::Int16 | VarType::Int32) {
+                        compiler_error(
+                            format!("String slice indices must be integers, got '{index_type}'"),
+                            index.borrow().get_token(),
+                        );
+                    }
+                }
+
+                if !matches!(self.result_type, VarType::Str | VarType::OwnedStr) {
+                    compiler_error(format!("Cannot slice a variable of type {}", self.result_type), &self.token);
+                }
+
+                // slices are copied into a new String
+                self.result_type = VarType::OwnedStr;
+            } else if let Some(ast_node) = &self.array_aceess_index {
                 ast_node.borrow_mut().semantic_visit(call_stack, f);
 
-                if let VarType::Array(type_, _) = &self.result_type {
+                if let VarType::Slice(type_) = &self.result_type {
+                    if matches!(
+                        **type_,
+                        VarType::Array(..) | VarType::Struct(..) | VarType::Union(..) | VarType::Function(..)
+                    ) {
+                        helpers::compiler_error(format!("Cannot index into a slice of {}", type_), &self.token);
+                    }
+                }
+
+                if let VarType::Array(type_, _) | VarType::Slice(type_) = &self.result_type {
                     // if an index is being accessed, then we have to get the underlying type
                     self.result_type = *type_.clone();
                 } else {
@@ -191,6 +317,11 @@ impl AST for Variable {
                             for m in members.borrow().iter() {
                                 if m.name == self.member_access[0] {
                                     self.result_type = m.member_type.clone();
+
+                                    if let Some(member_token) = self.member_tokens.first() {
+                                        call_stack.symbols.expression_type(member_token, &m.member_type);
+                                    }
+
                                     break;
                                 }
                             }
@@ -221,6 +352,9 @@ impl AST for Variable {
         } else {
             // This might be a function pointer
             if let Some(function) = f.borrow().get(&self.var_name) {
+                call_stack
+                    .symbols
+                    .refer(&self.token, function.func.borrow().get_token());
                 if let ASTNodeEnum::FunctionDef(fd) = function.func.borrow().get_node() {
                     self.var_type = VarType::Function(
                         self.var_name.clone(),
@@ -244,6 +378,9 @@ impl AST for Variable {
         if self.store_address {
             self.result_type = VarType::Ptr(Box::new(self.var_type.clone()))
         }
+
+        // the type of `a.b` is at `b`
+        call_stack.symbols.expression_type(&self.token, &self.var_type);
     }
 
     fn get_node(&self) -> ASTNodeEnum {
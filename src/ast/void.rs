@@ -9,7 +9,9 @@ impl AST for Void {
         f: std::rc::Rc<std::cell::RefCell<crate::interpreter::interpreter::Functions>>,
         call_stack: &mut crate::semantic_analyzer::semantic_analyzer::CallStack,
     ) -> super::abstract_syntax_tree::VisitResult {
-        todo!()
+        super::abstract_syntax_tree::VisitResult {
+            token: Box::new(crate::lexer::tokens::TokenEnum::Unknown("".into())),
+        }
     }
 
     fn visit_com(
@@ -33,7 +35,7 @@ impl AST for Void {
     }
 
     fn get_node(&self) -> super::abstract_syntax_tree::ASTNodeEnum {
-        todo!()
+        super::abstract_syntax_tree::ASTNodeEnum::Void
     }
 
     fn get_node_mut(&mut self) -> super::abstract_syntax_tree::ASTNodeEnumMut {
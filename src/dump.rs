@@ -0,0 +1,103 @@
+use std::{fs, rc::Rc};
+
+use crate::{
+    ast::abstract_syntax_tree::node_json,
+    helpers::{catch_compile_error, CompileError},
+    json::Json,
+    lexer::lexer::Lexer,
+    parser::parser::Parser,
+    semantic_analyzer::{semantic_analyzer::SemanticAnalyzer, warnings::WarningOptions},
+};
+
+/// What `--emit=tokens-json` and `--emit=ast-json` print instead of compiling the program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dump {
+    Tokens,
+    Ast,
+}
+
+/// Prints the dump of the file at `path`, returns the exit code. Errors go to stderr, so stdout
+/// only ever has the JSON
+pub fn run(path: &str, dump: Dump) -> i32 {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+
+        Err(err) => {
+            eprintln!("Failed to read `{path}`: {err}");
+            return 1;
+        }
+    };
+
+    let json = match dump {
+        Dump::Tokens => tokens_json(&source, path),
+        Dump::Ast => ast_json(&source, path),
+    };
+
+    match json {
+        Ok(json) => {
+            println!("{json}");
+            0
+        }
+
+        Err(CompileError {
+            message,
+            location: Some(location),
+        }) => {
+            eprintln!(
+                "{}:{}:{} {message}",
+                location.file, location.line_number, location.col_number
+            );
+            1
+        }
+
+        Err(CompileError {
+            message,
+            location: None,
+        }) => {
+            eprintln!("{path}: {message}");
+            1
+        }
+    }
+}
+
+/// Every token in `source`, comments included, with the text it's written as and the bytes it
+/// takes up
+pub fn tokens_json(source: &str, path: &str) -> Result<Json, CompileError> {
+    let tokens = catch_compile_error(|| Lexer::new(source.as_bytes().to_vec(), path.into()).lossless_tokens())?;
+
+    let tokens = tokens
+        .iter()
+        .map(|token| {
+            Json::object([
+                ("kind", token.token.token.kind().into()),
+                ("text", token.text.as_str().into()),
+                ("line", token.token.line_number.into()),
+                ("start", token.token.start.into()),
+                ("end", (token.token.start + token.text.len()).into()),
+            ])
+        })
+        .collect::<Vec<Json>>();
+
+    Ok(Json::object([("file", path.into()), ("tokens", tokens.into())]))
+}
+
+/// The program in `source` after semantic analysis, so every expression has its type. Global
+/// declarations come first, in the order they're initialised in
+pub fn ast_json(source: &str, path: &str) -> Result<Json, CompileError> {
+    catch_compile_error(|| {
+        let mut parser = Parser::new(source.as_bytes().to_vec(), path.into());
+        let ast = parser.parse_program();
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(
+            Rc::clone(&ast),
+            Rc::clone(&parser.functions),
+            &parser.user_defined_types,
+            &parser.type_aliases,
+            &parser.includes,
+            WarningOptions::default(),
+        );
+        semantic_analyzer.check();
+
+        node_json(&ast)
+    })
+}
@@ -0,0 +1,944 @@
+use std::{collections::HashSet, fs};
+
+use crate::{
+    helpers::catch_compile_error,
+    lexer::{
+        keywords::{
+            AS, BREAK, CONTINUE, ELIF_STATEMENT, ELSE_STATEMENT, ENUM, FROM, FUNCTION_DEFINE, IF_STATEMENT, IMPL, LOOP,
+            MATCH, STEP, STRUCT, TEST, TO, UNION, WITH,
+        },
+        lexer::Lexer,
+        lossless::SourceToken,
+        tokens::{Bracket, Comparators, LogicalOps, Operations, TokenEnum},
+    },
+};
+
+const INDENT: &str = "    ";
+
+/// What the items between a pair of curly braces are
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+    /// The whole file
+    Program,
+    Statements,
+    /// Functions in an `impl`
+    Methods,
+    /// Members of a struct, variants of an enum or a union
+    Members,
+    /// Patterns of a `match`, each followed by its block
+    Arms,
+}
+
+/// An open bracket inside a statement
+struct Group {
+    bracket: Bracket,
+    /// Written over several lines because it was in the source
+    multiline: bool,
+}
+
+/// Where a run of tokens stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Until {
+    /// The end of a statement, at a `;` or a line break that can't be inside an expression
+    StatementEnd,
+    /// The `{` of the block of an `if`, a loop, a function...
+    BlockStart,
+}
+
+/// `lang fmt [--check] FILE...` formats the files in place. With `--check` nothing is written,
+/// the files that aren't formatted are listed instead. Returns the exit code
+pub fn run(args: &[String]) -> i32 {
+    let check = args.iter().any(|arg| arg == "--check");
+    let files: Vec<&String> = args.iter().filter(|arg| *arg != "--check").collect();
+
+    if files.is_empty() {
+        eprintln!("Usage: lang fmt [--check] FILE...");
+        return 1;
+    }
+
+    let mut failed = false;
+
+    for file in files {
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+
+            Err(err) => {
+                eprintln!("Failed to read `{file}`: {err}");
+                failed = true;
+                continue;
+            }
+        };
+
+        let formatted = match format_source(&source, file) {
+            Ok(formatted) => formatted,
+
+            Err(err) => {
+                eprintln!("{err}");
+                failed = true;
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            let line = source
+                .lines()
+                .zip(formatted.lines())
+                .position(|(a, b)| a != b)
+                .unwrap_or_else(|| source.lines().count().min(formatted.lines().count()));
+
+            println!("{file}:{} is not formatted", line + 1);
+            failed = true;
+        } else if let Err(err) = fs::write(file, formatted) {
+            eprintln!("Failed to write `{file}`: {err}");
+            failed = true;
+        } else {
+            println!("Formatted {file}");
+        }
+    }
+
+    i32::from(failed)
+}
+
+/// Formats the program in `source`. Fails if the formatted program wouldn't be the same
+/// program, which is a bug in the formatter
+pub fn format_source(source: &str, file_name: &str) -> Result<String, String> {
+    let tokens = lossless_tokens(source, file_name)?;
+
+    let formatted = Formatter::new(&tokens).format();
+
+    let formatted_tokens = lossless_tokens(&formatted, file_name)?;
+
+    if significant_tokens(&tokens) != significant_tokens(&formatted_tokens) {
+        return Err(format!(
+            "{file_name}: formatting would change the program, leaving it as it is"
+        ));
+    }
+
+    Ok(formatted)
+}
+
+fn lossless_tokens(source: &str, file_name: &str) -> Result<Vec<SourceToken>, String> {
+    catch_compile_error(|| Lexer::new(source.as_bytes().to_vec(), file_name.into()).lossless_tokens())
+        .map_err(|error| format!("{file_name}: {}", error.message))
+}
+
+/// What has to stay the same when a program is formatted. Semicolons, commas between members and the
+/// brackets the formatter removes don't change the program, and comments can only move
+fn significant_tokens(tokens: &[SourceToken]) -> (Vec<String>, Vec<&str>) {
+    let optional = optional_parens(tokens);
+
+    let code: Vec<&SourceToken> = tokens
+        .iter()
+        .enumerate()
+        .filter(|(i, t)| !optional.contains(i) && !matches!(t.token.token, TokenEnum::Comment(_)))
+        .map(|(_, t)| t)
+        .collect();
+
+    // commas between members, and in struct literals, are optional
+    let mut brackets = vec![];
+
+    let code = code
+        .iter()
+        .filter(|t| match &t.token.token {
+            TokenEnum::SemiColon => false,
+            TokenEnum::Comma => brackets.last() != Some(&Bracket::LCurly),
+
+            TokenEnum::Bracket(bracket @ (Bracket::LCurly | Bracket::LParen | Bracket::LSquare)) => {
+                brackets.push(bracket.clone());
+                true
+            }
+
+            TokenEnum::Bracket(Bracket::RCurly | Bracket::RParen | Bracket::RSquare) => {
+                brackets.pop();
+                true
+            }
+
+            _ => true,
+        })
+        .map(|t| t.text.split_whitespace().collect())
+        .collect();
+
+    let comments = tokens
+        .iter()
+        .filter(|t| matches!(t.token.token, TokenEnum::Comment(_)))
+        .map(|t| t.text.as_str())
+        .collect();
+
+    (code, comments)
+}
+
+fn is_keyword(token: &SourceToken, keyword: &str) -> bool {
+    matches!(&token.token.token, TokenEnum::Keyword(k) if k == keyword)
+}
+
+fn is_bracket(token: &SourceToken, bracket: Bracket) -> bool {
+    matches!(&token.token.token, TokenEnum::Bracket(b) if *b == bracket)
+}
+
+/// Index of the bracket that closes the one at `open`
+fn matching_bracket(tokens: &[SourceToken], open: usize) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match &token.token.token {
+            TokenEnum::Bracket(Bracket::LParen | Bracket::LSquare | Bracket::LCurly) => depth += 1,
+
+            TokenEnum::Bracket(Bracket::RParen | Bracket::RSquare | Bracket::RCurly) => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Brackets around the whole condition of an `if` or `elif`, and the bounds of a loop, as in
+/// `loop from (0) to (n)`
+fn optional_parens(tokens: &[SourceToken]) -> HashSet<usize> {
+    let mut optional = HashSet::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        // what has to come right after the closing bracket
+        let followed_by: &[&str] = match &token.token.token {
+            TokenEnum::Keyword(k) if k == IF_STATEMENT || k == ELIF_STATEMENT => &["{"],
+            TokenEnum::Keyword(k) if k == FROM => &[TO],
+            TokenEnum::Keyword(k) if k == TO => &[STEP, WITH, "{"],
+            _ => continue,
+        };
+
+        if !tokens.get(i + 1).is_some_and(|t| is_bracket(t, Bracket::LParen)) {
+            continue;
+        }
+
+        let Some(close) = matching_bracket(tokens, i + 1) else {
+            continue;
+        };
+
+        // a range that starts with a bracket has to be wrapped in parens, `to ((n / 2) + 1)`
+        let is_range = is_keyword(token, FROM) || is_keyword(token, TO);
+        let starts_with_bracket = tokens
+            .get(i + 2)
+            .is_some_and(|t| matches!(t.token.token, TokenEnum::Bracket(_)));
+
+        if is_range && starts_with_bracket {
+            continue;
+        }
+
+        if tokens
+            .get(close + 1)
+            .is_some_and(|t| followed_by.contains(&t.text.as_str()))
+        {
+            optional.insert(i + 1);
+            optional.insert(close);
+        }
+    }
+
+    optional
+}
+
+/// The `<` and `>` around type arguments, as in `Pair<int, str>` or `max<T>(a, b)`
+fn type_argument_brackets(tokens: &[SourceToken]) -> HashSet<usize> {
+    let mut brackets = HashSet::new();
+
+    for (open, token) in tokens.iter().enumerate() {
+        let is_open = matches!(token.token.token, TokenEnum::Comparator(Comparators::LessThan))
+            && open > 0
+            && matches!(tokens[open - 1].token.token, TokenEnum::Variable(_));
+
+        if !is_open {
+            continue;
+        }
+
+        let mut depth = 0;
+
+        for (close, token) in tokens.iter().enumerate().skip(open) {
+            match &token.token.token {
+                TokenEnum::Comparator(Comparators::LessThan) => depth += 1,
+
+                TokenEnum::Comparator(Comparators::GreaterThan) => {
+                    depth -= 1;
+
+                    if depth > 0 {
+                        continue;
+                    }
+
+                    let next = tokens.get(close + 1);
+
+                    let ends_type = next.is_none_or(|next| {
+                        next.newlines_before > 0
+                            || matches!(
+                                next.token.token,
+                                TokenEnum::Bracket(_)
+                                    | TokenEnum::Equals
+                                    | TokenEnum::Comma
+                                    | TokenEnum::SemiColon
+                                    | TokenEnum::Dot
+                                    | TokenEnum::Comparator(Comparators::GreaterThan)
+                            )
+                    });
+
+                    if ends_type {
+                        brackets.insert(open);
+                        brackets.insert(close);
+                    }
+
+                    break;
+                }
+
+                TokenEnum::Type(_)
+                | TokenEnum::Variable(_)
+                | TokenEnum::Comma
+                | TokenEnum::Number(_)
+                | TokenEnum::Op(Operations::Multiply)
+                | TokenEnum::Bracket(Bracket::LSquare | Bracket::RSquare) => {}
+
+                _ => break,
+            }
+        }
+    }
+
+    brackets
+}
+
+/// Whether a statement can end with this token
+fn can_end_statement(token: &SourceToken) -> bool {
+    match &token.token.token {
+        TokenEnum::Variable(_)
+        | TokenEnum::Number(_)
+        | TokenEnum::StringLiteral(_)
+        | TokenEnum::Bool(_)
+        | TokenEnum::Type(_)
+        | TokenEnum::Bracket(Bracket::RParen | Bracket::RSquare | Bracket::RCurly) => true,
+
+        // `return` on its own line is followed by what it returns
+        TokenEnum::Keyword(keyword) => keyword == BREAK || keyword == CONTINUE,
+
+        _ => false,
+    }
+}
+
+/// Whether this token carries on the expression before it, even on the next line
+fn continues_expression(token: &SourceToken) -> bool {
+    match &token.token.token {
+        TokenEnum::Op(_)
+        | TokenEnum::Comparator(_)
+        | TokenEnum::LogicalOp(LogicalOps::And | LogicalOps::Or)
+        | TokenEnum::Dot
+        | TokenEnum::DotDot
+        | TokenEnum::Equals
+        | TokenEnum::PlusEquals
+        | TokenEnum::MinusEquals
+        | TokenEnum::Comma
+        | TokenEnum::Colon
+        | TokenEnum::FunctionReturnIndicator
+        | TokenEnum::Bracket(Bracket::RParen | Bracket::RSquare | Bracket::LCurly) => true,
+
+        TokenEnum::Keyword(keyword) => {
+            [AS, TO, STEP, WITH, FROM, ELSE_STATEMENT, ELIF_STATEMENT].contains(&keyword.as_str())
+        }
+
+        _ => false,
+    }
+}
+
+struct Formatter<'a> {
+    tokens: &'a [SourceToken],
+    index: usize,
+
+    optional_parens: HashSet<usize>,
+    type_argument_brackets: HashSet<usize>,
+    /// `*`, `-` and `&` that only apply to what comes after them
+    unary: HashSet<usize>,
+
+    output: String,
+    line: String,
+    indent: usize,
+    /// The last token written on the current line
+    last: Option<usize>,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(tokens: &'a [SourceToken]) -> Self {
+        let optional_parens = optional_parens(tokens);
+        let type_argument_brackets = type_argument_brackets(tokens);
+
+        let mut unary = HashSet::new();
+        let mut previous: Option<usize> = None;
+
+        for (i, token) in tokens.iter().enumerate() {
+            if let TokenEnum::Comment(_) = token.token.token {
+                continue;
+            }
+
+            let is_prefix = matches!(
+                token.token.token,
+                TokenEnum::Op(Operations::Minus | Operations::Multiply) | TokenEnum::Ampersand
+            );
+
+            // `}` ends a block, `*a = 1` after it is a new statement
+            let after_value =
+                previous.is_some_and(|p| can_end_statement(&tokens[p]) && !is_bracket(&tokens[p], Bracket::RCurly));
+
+            if is_prefix && !after_value {
+                unary.insert(i);
+            }
+
+            previous = Some(i);
+        }
+
+        Self {
+            tokens,
+            index: 0,
+            optional_parens,
+            type_argument_brackets,
+            unary,
+            output: String::new(),
+            line: String::new(),
+            indent: 0,
+            last: None,
+        }
+    }
+
+    fn format(mut self) -> String {
+        self.format_block(Block::Program);
+        self.end_line();
+
+        let formatted = self.output.trim().to_string();
+
+        if formatted.is_empty() {
+            formatted
+        } else {
+            formatted + "\n"
+        }
+    }
+
+    fn peek(&self) -> Option<&'a SourceToken> {
+        self.tokens.get(self.index)
+    }
+
+    fn peek_is(&self, token: TokenEnum) -> bool {
+        self.peek().is_some_and(|t| t.token.token == token)
+    }
+
+    fn end_line(&mut self) {
+        if !self.line.is_empty() {
+            self.output += &INDENT.repeat(self.indent);
+            self.output += self.line.trim_end();
+            self.output.push('\n');
+        }
+
+        self.line.clear();
+        self.last = None;
+    }
+
+    /// An empty line between two things, never more than one
+    fn blank_line(&mut self) {
+        self.end_line();
+
+        if !self.output.is_empty() && !self.output.ends_with("\n\n") && !self.output.ends_with("{\n") {
+            self.output.push('\n');
+        }
+    }
+
+    /// Whether a space goes between two tokens on the same line
+    fn space_between(&self, previous: usize, next: usize) -> bool {
+        let (p, n) = (&self.tokens[previous], &self.tokens[next]);
+
+        // `- -1` can't become a comment
+        if p.text.ends_with('-') && n.text.starts_with('-') {
+            return true;
+        }
+
+        if matches!(
+            n.token.token,
+            TokenEnum::Comma
+                | TokenEnum::SemiColon
+                | TokenEnum::Dot
+                | TokenEnum::DotDot
+                | TokenEnum::Colon
+                | TokenEnum::Bracket(Bracket::RParen | Bracket::RSquare)
+        ) {
+            return false;
+        }
+
+        if matches!(
+            p.token.token,
+            TokenEnum::Dot | TokenEnum::DotDot | TokenEnum::Bracket(Bracket::LParen | Bracket::LSquare)
+        ) {
+            return false;
+        }
+
+        if self.type_argument_brackets.contains(&next) {
+            return false;
+        }
+
+        if self.type_argument_brackets.contains(&previous) {
+            return match p.token.token {
+                TokenEnum::Comparator(Comparators::LessThan) => false,
+                _ => !matches!(n.token.token, TokenEnum::Bracket(Bracket::LParen | Bracket::LSquare)),
+            };
+        }
+
+        if self.unary.contains(&previous) {
+            // `- 5` isn't the same token as `-5`
+            return matches!(p.token.token, TokenEnum::Op(Operations::Minus))
+                && matches!(n.token.token, TokenEnum::Number(_));
+        }
+
+        match n.token.token {
+            // calls, and indexing into arrays
+            TokenEnum::Bracket(Bracket::LParen) => return !matches!(p.token.token, TokenEnum::Variable(_)),
+
+            TokenEnum::Bracket(Bracket::LSquare) => {
+                return !matches!(
+                    p.token.token,
+                    TokenEnum::Variable(_)
+                        | TokenEnum::Type(_)
+                        | TokenEnum::Bracket(Bracket::RSquare | Bracket::RParen)
+                )
+            }
+
+            _ => {}
+        }
+
+        // the type of a slice, `[]T`
+        let is_slice_prefix =
+            is_bracket(p, Bracket::RSquare) && previous > 0 && is_bracket(&self.tokens[previous - 1], Bracket::LSquare);
+
+        !(is_slice_prefix
+            && matches!(
+                n.token.token,
+                TokenEnum::Type(_) | TokenEnum::Variable(_) | TokenEnum::Bracket(Bracket::LSquare)
+            ))
+    }
+
+    fn write_token(&mut self, i: usize) {
+        if let Some(last) = self.last {
+            if self.space_between(last, i) {
+                self.line.push(' ');
+            }
+        }
+
+        let token = &self.tokens[i];
+
+        match token.token.token {
+            // `* int` is a single token
+            TokenEnum::Type(_) => self.line.extend(token.text.split_whitespace()),
+            _ => self.line += &token.text,
+        }
+
+        self.last = Some(i);
+    }
+
+    /// A `;` or `,` the source might not have had
+    fn write_punctuation(&mut self, punctuation: char) {
+        self.line.push(punctuation);
+    }
+
+    /// Comments after code stay on its line, the others get their own line
+    fn write_comment(&mut self, i: usize) {
+        if !self.line.is_empty() {
+            self.line.push(' ');
+        }
+
+        self.line += &self.tokens[i].text;
+        self.end_line();
+    }
+
+    /// Ends the line, after the comment on it if there's one
+    fn finish_line(&mut self) {
+        match self.peek() {
+            Some(token) if token.newlines_before == 0 && matches!(token.token.token, TokenEnum::Comment(_)) => {
+                self.write_comment(self.index);
+                self.index += 1;
+            }
+
+            _ => self.end_line(),
+        }
+    }
+
+    fn format_block(&mut self, block: Block) {
+        if block == Block::Members {
+            return self.format_members();
+        }
+
+        let mut first = true;
+        let mut previous_was_block = false;
+        let mut previous_was_comment = false;
+
+        loop {
+            // semicolons are written where they belong
+            while self.peek_is(TokenEnum::SemiColon) {
+                self.index += 1;
+            }
+
+            let Some(token) = self.peek() else {
+                return;
+            };
+
+            if is_bracket(token, Bracket::RCurly) {
+                if block != Block::Program {
+                    return;
+                }
+
+                // nothing to close at the top of the file, it stays where it is
+                self.write_token(self.index);
+                self.index += 1;
+                self.end_line();
+                continue;
+            }
+
+            let is_comment = matches!(token.token.token, TokenEnum::Comment(_));
+
+            let is_block = !is_comment
+                && matches!(&token.token.token, TokenEnum::Keyword(k) if [FUNCTION_DEFINE, TEST, STRUCT, ENUM, UNION, IMPL].contains(&k.as_str()));
+
+            // functions and types are kept apart, along with the comments right above them
+            let separate = matches!(block, Block::Program | Block::Methods)
+                && (is_block || previous_was_block)
+                && !previous_was_comment;
+
+            if !first && (token.newlines_before > 1 || separate) {
+                self.blank_line();
+            }
+
+            first = false;
+            previous_was_block = is_block;
+            previous_was_comment =
+                is_comment && self.tokens.get(self.index + 1).is_some_and(|t| t.newlines_before == 1);
+
+            if is_comment {
+                self.write_comment(self.index);
+                self.index += 1;
+                continue;
+            }
+
+            match block {
+                Block::Arms => self.format_compound(Block::Statements),
+                _ => self.format_statement(),
+            }
+        }
+    }
+
+    fn format_statement(&mut self) {
+        let Some(token) = self.peek() else {
+            return;
+        };
+
+        let body = match &token.token.token {
+            TokenEnum::Keyword(keyword) => match keyword.as_str() {
+                IF_STATEMENT | LOOP | FUNCTION_DEFINE | TEST => Some(Block::Statements),
+                MATCH => Some(Block::Arms),
+                STRUCT | ENUM | UNION => Some(Block::Members),
+                IMPL => Some(Block::Methods),
+                _ => None,
+            },
+
+            _ => None,
+        };
+
+        match body {
+            Some(body) => self.format_compound(body),
+
+            None => {
+                self.format_tokens(Until::StatementEnd);
+                self.write_punctuation(';');
+                self.finish_line();
+            }
+        }
+    }
+
+    /// Something followed by a block, and the `elif` and `else` blocks after an `if`
+    fn format_compound(&mut self, body: Block) {
+        loop {
+            self.format_tokens(Until::BlockStart);
+
+            if !self.peek().is_some_and(|t| is_bracket(t, Bracket::LCurly)) {
+                // there's no block, it was an unfinished statement
+                self.finish_line();
+                return;
+            }
+
+            self.write_token(self.index);
+            self.index += 1;
+            self.finish_line();
+
+            self.indent += 1;
+            self.format_block(body);
+            self.end_line();
+            self.indent -= 1;
+
+            if let Some(token) = self.peek().filter(|t| is_bracket(t, Bracket::RCurly)) {
+                self.write_token(self.index);
+                self.index += 1;
+            }
+
+            let continues = self
+                .peek()
+                .is_some_and(|t| is_keyword(t, ELIF_STATEMENT) || is_keyword(t, ELSE_STATEMENT));
+
+            if !continues {
+                break;
+            }
+
+            self.line.push(' ');
+            self.last = None;
+        }
+
+        // the `;` some write after a struct
+        while self.peek_is(TokenEnum::SemiColon) {
+            self.index += 1;
+        }
+
+        self.finish_line();
+    }
+
+    /// Whether the statement ending with `previous` ends before `next`
+    fn ends_statement(&self, previous: Option<usize>, next: usize, line_break: bool) -> bool {
+        let token = &self.tokens[next];
+
+        match token.token.token {
+            TokenEnum::SemiColon | TokenEnum::Bracket(Bracket::RCurly) => true,
+
+            _ => {
+                line_break
+                    && previous.is_some_and(|previous| can_end_statement(&self.tokens[previous]))
+                    && !continues_expression(token)
+            }
+        }
+    }
+
+    /// Writes tokens on the current line until `until`, breaking lines inside brackets that
+    /// were written over several lines
+    fn format_tokens(&mut self, until: Until) {
+        let mut groups: Vec<Group> = vec![];
+        // the last token that isn't a comment
+        let mut previous: Option<usize> = None;
+
+        while let Some(token) = self.peek() {
+            let i = self.index;
+
+            if let TokenEnum::Comment(_) = token.token.token {
+                let next =
+                    (i..self.tokens.len()).find(|&n| !matches!(self.tokens[n].token.token, TokenEnum::Comment(_)));
+
+                // the `;` goes before the comment at the end of a statement
+                let ends = groups.is_empty()
+                    && until == Until::StatementEnd
+                    && next.is_none_or(|next| self.ends_statement(previous, next, true));
+
+                if ends && previous.is_some() {
+                    return;
+                }
+
+                self.write_comment(i);
+                self.index += 1;
+                continue;
+            }
+
+            if self.optional_parens.contains(&i) {
+                self.index += 1;
+                continue;
+            }
+
+            if groups.is_empty() {
+                let ends = match &token.token.token {
+                    TokenEnum::SemiColon => {
+                        if until == Until::StatementEnd {
+                            self.index += 1;
+                        }
+
+                        true
+                    }
+
+                    TokenEnum::Bracket(Bracket::RCurly) => true,
+                    TokenEnum::Bracket(Bracket::LCurly) => until == Until::BlockStart,
+
+                    _ => until == Until::StatementEnd && self.ends_statement(previous, i, token.newlines_before > 0),
+                };
+
+                if ends {
+                    return;
+                }
+            }
+
+            previous = Some(i);
+
+            match &token.token.token {
+                TokenEnum::Bracket(bracket @ (Bracket::LParen | Bracket::LSquare | Bracket::LCurly)) => {
+                    let multiline = self.tokens.get(i + 1).is_some_and(|next| next.newlines_before > 0);
+
+                    self.write_token(i);
+                    self.index += 1;
+
+                    groups.push(Group {
+                        bracket: bracket.clone(),
+                        multiline,
+                    });
+
+                    if multiline {
+                        self.finish_line();
+                        self.indent += 1;
+                    }
+                }
+
+                TokenEnum::Bracket(Bracket::RParen | Bracket::RSquare | Bracket::RCurly) => {
+                    if let Some(group) = groups.pop() {
+                        if group.multiline {
+                            let ends_with_comma = self
+                                .last
+                                .is_some_and(|l| self.tokens[l].token.token == TokenEnum::Comma);
+
+                            // every member of a struct gets a comma, the brackets are on their own
+                            if group.bracket == Bracket::LCurly && !ends_with_comma && !self.line.is_empty() {
+                                self.write_punctuation(',');
+                            }
+
+                            self.end_line();
+                            self.indent -= 1;
+                        }
+                    }
+
+                    self.write_token(i);
+                    self.index += 1;
+                }
+
+                TokenEnum::Comma => {
+                    let next = self.tokens.get(i + 1);
+                    let closes_curly = next.is_some_and(|t| is_bracket(t, Bracket::RCurly));
+
+                    match groups.last() {
+                        // `A { a: 1, b: 2 }` has no comma at the end
+                        Some(group) if !group.multiline && group.bracket == Bracket::LCurly && closes_curly => {}
+
+                        Some(group) if group.multiline => {
+                            self.write_token(i);
+
+                            if group.bracket == Bracket::LCurly || next.is_some_and(|t| t.newlines_before > 0) {
+                                self.index += 1;
+                                self.finish_line();
+                                continue;
+                            }
+                        }
+
+                        _ => self.write_token(i),
+                    }
+
+                    self.index += 1;
+                }
+
+                _ => {
+                    self.write_token(i);
+                    self.index += 1;
+                }
+            }
+        }
+    }
+
+    /// The members of a struct each on their own line with a comma, and their types lined up
+    fn format_members(&mut self) {
+        // (member, comments on the same line, empty line before)
+        let mut members: Vec<(Vec<usize>, Vec<usize>, bool)> = vec![];
+        let mut current: Vec<usize> = vec![];
+
+        let tokens = self.tokens;
+
+        let end_member = |members: &mut Vec<(Vec<usize>, Vec<usize>, bool)>, current: &mut Vec<usize>| {
+            if !current.is_empty() {
+                let blank = tokens[current[0]].newlines_before > 1;
+                members.push((std::mem::take(current), vec![], blank));
+            }
+        };
+
+        while let Some(token) = self.peek() {
+            let i = self.index;
+
+            match &token.token.token {
+                TokenEnum::Bracket(Bracket::RCurly) => break,
+
+                TokenEnum::Comma => end_member(&mut members, &mut current),
+
+                TokenEnum::Comment(_) if token.newlines_before == 0 && current.is_empty() && !members.is_empty() => {
+                    members.last_mut().unwrap().1.push(i);
+                }
+
+                TokenEnum::Comment(_) => {
+                    end_member(&mut members, &mut current);
+                    members.push((vec![i], vec![], token.newlines_before > 1));
+                }
+
+                _ => {
+                    // commas between members are optional
+                    let starts_member = token.newlines_before > 0
+                        && current.last().is_some_and(|&last| can_end_statement(&tokens[last]))
+                        && !continues_expression(token);
+
+                    if starts_member {
+                        end_member(&mut members, &mut current);
+                    }
+
+                    current.push(i)
+                }
+            }
+
+            self.index += 1;
+        }
+
+        end_member(&mut members, &mut current);
+
+        // `name: type`, the types start in the same column
+        let is_named = |member: &[usize]| member.len() > 2 && tokens[member[1]].token.token == TokenEnum::Colon;
+
+        let name_width = members
+            .iter()
+            .filter(|(member, ..)| is_named(member))
+            .map(|(member, ..)| tokens[member[0]].text.len())
+            .max()
+            .unwrap_or(0);
+
+        for (n, (member, comments, blank)) in members.iter().enumerate() {
+            if n > 0 && *blank {
+                self.blank_line();
+            }
+
+            if let [comment] = member[..] {
+                if let TokenEnum::Comment(_) = self.tokens[comment].token.token {
+                    self.write_comment(comment);
+                    continue;
+                }
+            }
+
+            let mut rest = &member[..];
+
+            if is_named(member) {
+                let name = &tokens[member[0]].text;
+
+                self.line += &format!("{name}:{}", " ".repeat(name_width - name.len()));
+                self.last = Some(member[1]);
+                rest = &member[2..];
+            }
+
+            for &i in rest {
+                self.write_token(i);
+            }
+
+            self.write_punctuation(',');
+
+            for &comment in comments {
+                self.write_comment(comment);
+            }
+
+            self.end_line();
+        }
+    }
+}
@@ -1,4 +1,11 @@
-use std::{backtrace::Backtrace, fmt::Display, process::exit};
+use std::{
+    backtrace::Backtrace,
+    cell::Cell,
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+    process::exit,
+    sync::Once,
+};
 
 use crate::lexer::{lexer::Token, tokens::TokenEnum};
 
@@ -6,7 +13,10 @@ use crate::lexer::{lexer::Token, tokens::TokenEnum};
 macro_rules! trace {
     // The pattern for a single argument
     ($($arg:tt)*) => {
-        println!("{}:{}: {}", file!(), line!(), format_args!($($arg)*));
+        // stdout belongs to the editor while the language server runs
+        if !$crate::helpers::is_catching_errors() {
+            println!("{}:{}: {}", file!(), line!(), format_args!($($arg)*));
+        }
     };
 }
 
@@ -18,37 +28,54 @@ pub fn print_only_tokens(tokens: &Vec<Token>) {
     trace!("");
 }
 
-pub fn unexpected_keyword<S: AsRef<str> + Display>(token: &Token, unexpected: S, expected: Option<S>) {
-    eprintln!(
-        "{}:{}:{} Unexpected Keyword: '{}'{}",
-        token.file,
-        token.line_number,
-        token.col_number,
-        unexpected,
-        match expected {
-            Some(tok) => format!(" Expected: '{tok}'"),
-            None => "".into(),
+/// Where a token is. Unlike a token it can be sent to another thread, which panicking needs
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line_number: usize,
+    pub col_number: usize,
+    pub start: usize,
+}
+
+impl From<&Token> for SourceLocation {
+    fn from(token: &Token) -> Self {
+        Self {
+            file: token.file.clone(),
+            line_number: token.line_number,
+            col_number: token.col_number,
+            start: token.start,
         }
-    );
+    }
+}
 
-    let backtrace = std::backtrace::Backtrace::capture();
-    println!("{:#?}", backtrace);
+/// An error in the program being compiled
+#[derive(Debug, Clone)]
+pub struct CompileError {
+    pub message: String,
+    /// None if the compiler itself failed, and not the program
+    pub location: Option<SourceLocation>,
+}
 
-    exit(1);
+thread_local! {
+    /// Set while `catch_compile_error` runs
+    static CATCH_ERRORS: Cell<bool> = const { Cell::new(false) };
 }
 
-pub fn unexpected_token(unexpected: &Token, expected: Option<&TokenEnum>) {
-    eprintln!(
-        "{}:{}:{} Unexpected Token: '{}'{}",
-        unexpected.file,
-        unexpected.line_number,
-        unexpected.col_number,
-        unexpected,
-        match expected {
-            Some(tok) => format!(" Expected: '{tok}'"),
-            None => "".into(),
-        }
-    );
+pub fn is_catching_errors() -> bool {
+    CATCH_ERRORS.get()
+}
+
+/// Every error goes through here. The compiler exits on the first error, except inside
+/// `catch_compile_error` where the error is handed back to the caller
+fn report_error(message: String, token: &Token) {
+    if CATCH_ERRORS.get() {
+        panic::panic_any(CompileError {
+            message,
+            location: Some(token.into()),
+        });
+    }
+
+    eprintln!("{}:{}:{} {}", token.file, token.line_number, token.col_number, message);
 
     let backtrace = std::backtrace::Backtrace::capture();
     println!("{:#?}", backtrace);
@@ -56,21 +83,79 @@ pub fn unexpected_token(unexpected: &Token, expected: Option<&TokenEnum>) {
     exit(1);
 }
 
-pub fn unexpected_token_string(unexpected: &Token, expected: &str) {
-    eprintln!(
-        "{}:{}:{} Unexpected Token: '{}'. Expected {}",
-        unexpected.file, unexpected.line_number, unexpected.col_number, unexpected, expected
+/// Runs `f`, returning the first error instead of exiting. Anything else that stops the compiler,
+/// like a `todo!()`, becomes an error without a location
+pub fn catch_compile_error<T>(f: impl FnOnce() -> T) -> Result<T, CompileError> {
+    // the hook is shared by every thread, it only stays quiet on the ones catching errors
+    static QUIET_PANIC_HOOK: Once = Once::new();
+
+    QUIET_PANIC_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if !CATCH_ERRORS.get() {
+                hook(info);
+            }
+        }));
+    });
+
+    let catching = CATCH_ERRORS.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCH_ERRORS.set(catching);
+
+    result.map_err(|payload| match payload.downcast::<CompileError>() {
+        Ok(error) => *error,
+
+        Err(payload) => {
+            let reason = payload
+                .downcast_ref::<String>()
+                .map(String::as_str)
+                .or_else(|| payload.downcast_ref::<&str>().copied())
+                .unwrap_or("unknown reason");
+
+            CompileError {
+                message: format!("The compiler stopped: {reason}"),
+                location: None,
+            }
+        }
+    })
+}
+
+pub fn unexpected_keyword<S: AsRef<str> + Display>(token: &Token, unexpected: S, expected: Option<S>) {
+    report_error(
+        format!(
+            "Unexpected Keyword: '{}'{}",
+            unexpected,
+            match expected {
+                Some(tok) => format!(" Expected: '{tok}'"),
+                None => "".into(),
+            }
+        ),
+        token,
     );
+}
 
-    let backtrace = std::backtrace::Backtrace::capture();
-    println!("{:#?}", backtrace);
+pub fn unexpected_token(unexpected: &Token, expected: Option<&TokenEnum>) {
+    report_error(
+        format!(
+            "Unexpected Token: '{}'{}",
+            unexpected,
+            match expected {
+                Some(tok) => format!(" Expected: '{tok}'"),
+                None => "".into(),
+            }
+        ),
+        unexpected,
+    );
+}
 
-    exit(1);
+pub fn unexpected_token_string(unexpected: &Token, expected: &str) {
+    report_error(
+        format!("Unexpected Token: '{}'. Expected {}", unexpected, expected),
+        unexpected,
+    );
 }
 
 pub fn compiler_error<S: AsRef<str> + Display>(message: S, tok: &Token) {
-    eprintln!("{}:{}:{} {}", tok.file, tok.line_number, tok.col_number, message);
-    let backtrace = std::backtrace::Backtrace::capture();
-    println!("{:#?}", backtrace);
-    exit(1);
+    report_error(message.to_string(), tok);
 }
@@ -1,16 +1,22 @@
 use crate::lexer::types::VarType;
+use crate::parser::parse_test::Test;
 use crate::semantic_analyzer::semantic_analyzer::CallStack;
 use crate::trace;
 use crate::types::ASTNode;
+use crate::options::CompilerOptions;
 
 use core::panic;
 use std::io::prelude::*;
+use std::process::exit;
 use std::{cell::RefCell, collections::HashMap, fs::File, rc::Rc};
 
-use crate::lexer::tokens::VariableEnum;
+use crate::helpers::compiler_error;
+use crate::lexer::keywords::{BREAK, CONTINUE, RETURN};
+use crate::lexer::lexer::Token;
+use crate::lexer::tokens::{TokenEnum, VariableEnum};
 use crate::{
-    asm::asm::ASM,
-    ast::abstract_syntax_tree::{VisitResult, AST},
+    asm::{asm::ASM, debug_info::DebugInfo},
+    ast::abstract_syntax_tree::{ASTNodeEnum, VisitResult, AST},
     lexer::tokens::Number,
 };
 
@@ -24,9 +30,74 @@ pub struct FunctionHashMapValue {
     pub is_extern_func: bool,
 }
 
+/// Where the code generated for the program starts and ends, for the debug info
+const TEXT_START: &str = "..@text_start";
+const TEXT_END: &str = "..@text_end";
+
 pub type Variables = HashMap<String, VariableEnum>;
 pub type Functions = HashMap<String, FunctionHashMapValue>;
 
+/// A `return`, `break` or `continue` that's been interpreted is kept in the variables under its
+/// keyword, which can't be the name of a variable, and the statements after it are skipped until
+/// the function or the loop it belongs to is reached
+pub fn is_jumping(variables: &Variables) -> bool {
+    [RETURN, BREAK, CONTINUE]
+        .iter()
+        .any(|keyword| variables.contains_key(*keyword))
+}
+
+/// What a variable holds after it's given the result of visiting an expression
+pub fn variable_value(value: &TokenEnum, token: &Token) -> VariableEnum {
+    match value {
+        TokenEnum::Number(n) => VariableEnum::Number(n.clone()),
+        TokenEnum::StringLiteral(s) => VariableEnum::String(s.clone()),
+        TokenEnum::Bool(b) => VariableEnum::Number(Number::Integer(*b as i32)),
+        TokenEnum::Union(union_name, variant_name, tag, payload) => VariableEnum::Union(
+            union_name.clone(),
+            variant_name.clone(),
+            *tag,
+            Box::new(variable_value(payload, token)),
+        ),
+
+        t => {
+            compiler_error(format!("'{t}' can't be stored in a variable by the interpreter"), token);
+            exit(1);
+        }
+    }
+}
+
+/// The text of a value the interpreter computed, strings have their escapes replaced
+pub fn value_text(value: &TokenEnum) -> String {
+    match value {
+        TokenEnum::Number(Number::Integer(i)) => i.to_string(),
+        TokenEnum::Number(Number::Float(f)) => f.to_string(),
+        TokenEnum::StringLiteral(s) => s.replace("\\n", "\n").replace("\\r", "\r").replace("\\0", "\0"),
+        TokenEnum::Union(union_name, variant_name, _, payload) => {
+            format!("{union_name}.{variant_name}({})", value_text(payload))
+        }
+        value => value.to_string(),
+    }
+}
+
+/// The result of visiting a variable
+pub fn variable_token(value: &VariableEnum, token: &Token) -> TokenEnum {
+    match value {
+        VariableEnum::Number(n) => TokenEnum::Number(n.clone()),
+        VariableEnum::String(s) => TokenEnum::StringLiteral(s.clone()),
+        VariableEnum::Union(union_name, variant_name, tag, payload) => TokenEnum::Union(
+            union_name.clone(),
+            variant_name.clone(),
+            *tag,
+            Box::new(variable_token(payload, token)),
+        ),
+
+        VariableEnum::Pointer(_) => {
+            compiler_error("Pointers can't be read by the interpreter", token);
+            exit(1);
+        }
+    }
+}
+
 pub struct Interpreter {
     ast: ASTNode,
     pub variables: Variables,
@@ -35,19 +106,16 @@ pub struct Interpreter {
 }
 
 impl Interpreter {
-    pub fn new(ast: ASTNode, functions: Rc<RefCell<Functions>>) -> Self {
+    pub fn new(ast: ASTNode, functions: Rc<RefCell<Functions>>, options: &CompilerOptions) -> Self {
         Self {
             ast,
-            variables: HashMap::from([
-                ("argc".into(), VariableEnum::Number(Number::Integer(0))),
-                ("argv".into(), VariableEnum::String("".into())),
-            ]),
+            variables: HashMap::new(),
             functions,
-            asm: ASM::default(),
+            asm: ASM::new(options),
         }
     }
 
-    fn write_nasm(&self) -> Result<(), std::io::Error> {
+    fn write_nasm(&mut self) -> Result<(), std::io::Error> {
         let file_name = "generated/output.asm";
 
         println!("pwd {:?}", std::env::current_dir());
@@ -72,11 +140,24 @@ impl Interpreter {
                     file.write(b"\n\n")?;
                 }
 
+                if !self.asm.rodata.is_empty() {
+                    // write .rodata section
+                    file.write_all(b"section .rodata\n\t")?;
+                    file.write_all(self.asm.rodata.join("\n\t").as_bytes())?;
+                    file.write_all(b"\n\n")?;
+                }
+
                 // write .text section
                 file.write(b"section .text\n\t")?;
                 file.write_all(self.asm.text.join("\n\t").as_bytes())?;
                 file.write(b"\n\n")?;
 
+                let debug_info = self.asm.debug_info.is_some();
+
+                if debug_info {
+                    file.write_all(format!("{TEXT_START}:\n\n").as_bytes())?;
+                }
+
                 for label in &self.asm.labels {
                     let mut file_bytes = format!("{}:\n\t", &label.name);
                     file_bytes += &label.code.join("\n\t");
@@ -84,11 +165,25 @@ impl Interpreter {
                     file.write_all(file_bytes.as_bytes())?;
 
                     if label.name == "_start" {
-                        file.write(b"\n\texit 0")?;
+                        if self.asm.debug_alloc {
+                            file.write_all(b"\n\tcall _heapReportLeaks")?;
+                        }
+
+                        file.write(b"\n\texit [exitCode]")?;
+                    }
+
+                    if debug_info {
+                        file.write_all(format!("\n{}:", DebugInfo::end_label(&label.name)).as_bytes())?;
                     }
 
                     file.write(b"\n\n")?;
                 }
+
+                if let Some(debug_info) = &mut self.asm.debug_info {
+                    file.write_all(format!("{TEXT_END}:\n").as_bytes())?;
+                    file.write_all(debug_info.sections(TEXT_START, TEXT_END).join("\n\t").as_bytes())?;
+                    file.write_all(b"\n")?;
+                }
             }
 
             Err(e) => {
@@ -99,13 +194,39 @@ impl Interpreter {
         return Ok(());
     }
 
+    /// Initialises the globals, then runs main
     pub fn interpret(&mut self, call_stack: &mut CallStack) -> VisitResult {
-        return self
-            .ast
+        self.evaluate(&Rc::clone(&self.ast), call_stack);
+
+        let main = Rc::clone(&self.functions.borrow()["main"].func);
+        let main = main.borrow();
+
+        match main.get_node() {
+            ASTNodeEnum::FunctionDef(fd) => {
+                fd.call(vec![], &mut self.variables, Rc::clone(&self.functions), call_stack)
+            }
+            _ => unreachable!("Found non function_definition node inside functions hash map"),
+        }
+    }
+
+    /// Visits `node` with the variables set by everything interpreted before it
+    pub fn evaluate(&mut self, node: &ASTNode, call_stack: &mut CallStack) -> VisitResult {
+        return node
             .borrow()
             .visit(&mut self.variables, Rc::clone(&self.functions), call_stack);
     }
 
+    /// Whether main takes the command line arguments and whether it returns the exit code
+    fn main_signature(&self) -> (bool, bool) {
+        let functions = self.functions.borrow();
+        let func = functions["main"].func.borrow();
+
+        match func.get_node() {
+            ASTNodeEnum::FunctionDef(fd) => (!fd.parameters.is_empty(), matches!(fd.return_type, VarType::Int)),
+            _ => unreachable!("Found non function_definition node inside functions hash map"),
+        }
+    }
+
     pub fn compile(&mut self, call_stack: &mut CallStack) {
         self.ast.borrow().visit_com(
             &mut self.variables,
@@ -114,6 +235,27 @@ impl Interpreter {
             call_stack,
         );
 
+        let (takes_args, returns_int) = self.main_signature();
+        self.asm.call_main(takes_args, returns_int);
+
+        self.finish_compiling();
+    }
+
+    /// Like `compile`, but the program runs the tests instead of main, see `ASM::run_tests`
+    pub fn compile_tests(&mut self, call_stack: &mut CallStack, tests: &[Test]) {
+        self.ast.borrow().visit_com(
+            &mut self.variables,
+            Rc::clone(&self.functions),
+            &mut self.asm,
+            call_stack,
+        );
+
+        self.asm.run_tests(tests);
+
+        self.finish_compiling();
+    }
+
+    fn finish_compiling(&mut self) {
         if self.asm.get_used_registers().len() > 0 {
             trace!("Used Registers: {:#?}", self.asm.get_used_registers());
             // trace!("Labels: {:#?}", self.asm.labels);
@@ -0,0 +1,335 @@
+use std::fmt::Display;
+
+/// Just enough JSON for the language server protocol and the `--emit=*-json` dumps
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Keys stay in the order they were written
+    Object(Vec<(String, Json)>),
+}
+
+static NULL: Json = Json::Null;
+
+impl Json {
+    pub fn parse(text: &str) -> Result<Json, String> {
+        let mut parser = JsonParser {
+            chars: text.chars().collect(),
+            index: 0,
+        };
+
+        let value = parser.parse_value()?;
+
+        parser.skip_whitespace();
+
+        if parser.index < parser.chars.len() {
+            return Err(format!("Unexpected '{}' after the value", parser.chars[parser.index]));
+        }
+
+        Ok(value)
+    }
+
+    pub fn object<const N: usize>(members: [(&str, Json); N]) -> Json {
+        Json::Object(members.into_iter().map(|(key, value)| (key.into(), value)).collect())
+    }
+
+    /// The member called `key`, or null if this isn't an object or there's no such member
+    pub fn get(&self, key: &str) -> &Json {
+        match self {
+            Json::Object(members) => members.iter().find(|(k, _)| k == key).map_or(&NULL, |(_, v)| v),
+            _ => &NULL,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Json::Number(number) if *number >= 0.0 && number.fract() == 0.0 => Some(*number as u64),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(bool) => Some(*bool),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> &[Json] {
+        match self {
+            Json::Array(array) => array,
+            _ => &[],
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Json::Null)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.into())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<Vec<Json>> for Json {
+    fn from(value: Vec<Json>) -> Self {
+        Json::Array(value)
+    }
+}
+
+fn write_string(f: &mut std::fmt::Formatter<'_>, string: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+
+    for c in string.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(bool) => write!(f, "{bool}"),
+            Json::Number(number) if number.fract() == 0.0 && number.abs() < 1e15 => write!(f, "{}", *number as i64),
+            Json::Number(number) => write!(f, "{number}"),
+            Json::String(string) => write_string(f, string),
+
+            Json::Array(array) => {
+                write!(f, "[")?;
+
+                for (i, value) in array.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write!(f, "{value}")?;
+                }
+
+                write!(f, "]")
+            }
+
+            Json::Object(members) => {
+                write!(f, "{{")?;
+
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+struct JsonParser {
+    chars: Vec<char>,
+    index: usize,
+}
+
+impl JsonParser {
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.index).is_some_and(|c| c.is_whitespace()) {
+            self.index += 1;
+        }
+    }
+
+    fn next(&mut self) -> Result<char, String> {
+        let c = self.chars.get(self.index).copied().ok_or("Unexpected end of JSON")?;
+        self.index += 1;
+        Ok(c)
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        for e in expected.chars() {
+            if self.next()? != e {
+                return Err(format!("Expected '{expected}'"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+
+        match self.chars.get(self.index).copied().ok_or("Unexpected end of JSON")? {
+            'n' => self.expect("null").map(|_| Json::Null),
+            't' => self.expect("true").map(|_| Json::Bool(true)),
+            'f' => self.expect("false").map(|_| Json::Bool(false)),
+            '"' => self.parse_string().map(Json::String),
+            '[' => self.parse_array(),
+            '{' => self.parse_object(),
+            c if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            c => Err(format!("Unexpected '{c}'")),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Json, String> {
+        let start = self.index;
+
+        while self
+            .chars
+            .get(self.index)
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            self.index += 1;
+        }
+
+        let number: String = self.chars[start..self.index].iter().collect();
+
+        number
+            .parse()
+            .map(Json::Number)
+            .map_err(|_| format!("Invalid number '{number}'"))
+    }
+
+    fn parse_hex(&mut self) -> Result<u32, String> {
+        let mut value = 0;
+
+        for _ in 0..4 {
+            let digit = self.next()?.to_digit(16).ok_or("Invalid \\u escape")?;
+            value = value * 16 + digit;
+        }
+
+        Ok(value)
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+
+        let mut string = String::new();
+
+        loop {
+            match self.next()? {
+                '"' => return Ok(string),
+
+                '\\' => match self.next()? {
+                    'n' => string.push('\n'),
+                    'r' => string.push('\r'),
+                    't' => string.push('\t'),
+                    'b' => string.push('\u{8}'),
+                    'f' => string.push('\u{c}'),
+
+                    'u' => {
+                        let mut code = self.parse_hex()?;
+
+                        // characters outside the basic plane are written as two UTF-16 surrogates
+                        if (0xd800..0xdc00).contains(&code) {
+                            self.expect("\\u")?;
+                            let low = self.parse_hex()?;
+                            code = 0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+                        }
+
+                        string.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+
+                    c => string.push(c),
+                },
+
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Json, String> {
+        self.expect("[")?;
+
+        let mut array = vec![];
+
+        self.skip_whitespace();
+
+        if self.chars.get(self.index) == Some(&']') {
+            self.index += 1;
+            return Ok(Json::Array(array));
+        }
+
+        loop {
+            array.push(self.parse_value()?);
+
+            self.skip_whitespace();
+
+            match self.next()? {
+                ',' => continue,
+                ']' => return Ok(Json::Array(array)),
+                c => return Err(format!("Expected ',' or ']', found '{c}'")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Json, String> {
+        self.expect("{")?;
+
+        let mut members = vec![];
+
+        self.skip_whitespace();
+
+        if self.chars.get(self.index) == Some(&'}') {
+            self.index += 1;
+            return Ok(Json::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+
+            let key = self.parse_string()?;
+
+            self.skip_whitespace();
+            self.expect(":")?;
+
+            members.push((key, self.parse_value()?));
+
+            self.skip_whitespace();
+
+            match self.next()? {
+                ',' => continue,
+                '}' => return Ok(Json::Object(members)),
+                c => return Err(format!("Expected ',' or '}}', found '{c}'")),
+            }
+        }
+    }
+}
@@ -26,6 +26,7 @@ impl Lexer {
             token: TokenEnum::EOF,
             line_number: 0,
             index: 0,
+            start: 0,
             col_number: 0,
             file: self.file_name.to_string(),
         };
@@ -35,7 +36,7 @@ impl Lexer {
         while i < n {
             token = self.advance_to_next_token();
 
-            if let TokenEnum::Comment = token.token {
+            if let TokenEnum::Comment(_) = token.token {
                 index = self.index;
                 col_number = self.col_number;
                 line_number = self.line_number;
@@ -58,6 +59,7 @@ impl Lexer {
 
         while self.index < self.file.len() {
             let character = self.file[self.index] as char;
+            let start = self.index;
 
             let token = match character {
                 ' ' | '\t' => {
@@ -74,7 +76,14 @@ impl Lexer {
                     continue;
                 }
 
-                '.' => TokenEnum::Dot,
+                '.' => {
+                    if self.file.get(self.index + 1) == Some(&b'.') {
+                        self.index += 1;
+                        TokenEnum::DotDot
+                    } else {
+                        TokenEnum::Dot
+                    }
+                }
 
                 '+' => {
                     self.index += 1;
@@ -87,11 +96,12 @@ impl Lexer {
 
                 // TODO: Handle negative integers
                 '-' => {
-                    if self.is_comment() {
+                    if let Some(comment) = self.comment() {
                         return Token {
-                            token: TokenEnum::Comment,
+                            token: TokenEnum::Comment(comment),
                             line_number: self.line_number,
                             index: self.index,
+                            start,
                             col_number: self.col_number,
                             file: self.file_name.clone().into(),
                         };
@@ -107,13 +117,20 @@ impl Lexer {
                                 TokenEnum::Comparator(Comparators::GreaterThan) => TokenEnum::FunctionReturnIndicator,
 
                                 // Also handle things like -var_name
+                                // get_next_token leaves the index after the number, but it's
+                                // incremented once more at the end
                                 TokenEnum::Number(num) => match num {
                                     Number::Integer(int) => {
                                         self.get_next_token();
+                                        self.index -= 1;
                                         TokenEnum::Number(Number::Integer(-int))
                                     }
 
-                                    Number::Float(_) => todo!(),
+                                    Number::Float(float) => {
+                                        self.get_next_token();
+                                        self.index -= 1;
+                                        TokenEnum::Number(Number::Float(-float))
+                                    }
                                 },
 
                                 _ => {
@@ -199,7 +216,10 @@ impl Lexer {
                     self.index += 1;
 
                     match self.peek_next_token().token {
-                        TokenEnum::Equals => TokenEnum::Comparator(Comparators::GreaterThanEq),
+                        // only if there's no whitespace in between, as in `def a: Pair<int, int> = ...`
+                        TokenEnum::Equals if self.file[self.index] == b'=' => {
+                            TokenEnum::Comparator(Comparators::GreaterThanEq)
+                        }
 
                         TokenEnum::Comparator(com) => match com {
                             Comparators::GreaterThan => TokenEnum::Op(Operations::ShiftRight),
@@ -235,7 +255,7 @@ impl Lexer {
 
                 // only handle ASCII for now
                 _ => match self.file[self.index] {
-                    65..=90 | 97..=122 => self.construct_word(),
+                    65..=90 | 95 | 97..=122 => self.construct_word(),
 
                     48..=57 => self.construct_number(),
 
@@ -250,6 +270,7 @@ impl Lexer {
                 line_number: self.line_number,
                 col_number: self.col_number,
                 index: self.index,
+                start,
                 file: self.file_name.clone().into(),
             };
 
@@ -261,6 +282,7 @@ impl Lexer {
             line_number: self.line_number,
             col_number: self.col_number,
             index: self.index,
+            start: self.index,
             file: self.file_name.clone().into(),
         };
     }
@@ -24,11 +24,18 @@ pub const AS: &str = "as";
 
 pub const MEM: &str = "mem";
 pub const STRUCT: &str = "struct";
+pub const ENUM: &str = "enum";
+pub const UNION: &str = "union";
+pub const MATCH: &str = "match";
+pub const IMPL: &str = "impl";
 
 pub const INCLUDE: &str = "include";
 pub const TYPE_DEF: &str = "type";
 
-pub const KEYWORDS: [&str; 24] = [
+/// test "name" { ... }, run by `lang test`
+pub const TEST: &str = "test";
+
+pub const KEYWORDS: [&str; 29] = [
     VAR_DEFINE,
     CONST_VAR_DEFINE,
     IF_STATEMENT,
@@ -50,9 +57,14 @@ pub const KEYWORDS: [&str; 24] = [
     MEM,
     AS,
     STRUCT,
+    ENUM,
+    UNION,
+    MATCH,
+    IMPL,
     INCLUDE,
     TYPE_DEF,
     EXTERN,
+    TEST,
 ];
 
 // Predefined functions
@@ -61,3 +73,56 @@ pub const KEYWORDS: [&str; 24] = [
 pub const FUNC_WRITE: &str = "write";
 pub const FUNC_EXIT: &str = "exit";
 pub const FUNC_SYSCALL: &str = "syscall";
+
+// Formatted output, implemented in generated/format.asm
+pub const FUNC_PRINT: &str = "print";
+pub const FUNC_FPRINT: &str = "fprint";
+
+// Buffered stdout and line reading, implemented in generated/io.asm
+pub const FUNC_FLUSH: &str = "flush";
+pub const FUNC_READ_LINE: &str = "read_line";
+
+// Environment variables, implemented in generated/process.asm
+pub const FUNC_ENV: &str = "env";
+
+// Math functions on floats, lowered to SSE instructions
+pub const FUNC_SQRT: &str = "sqrt";
+pub const FUNC_ABS: &str = "abs";
+pub const FUNC_FLOOR: &str = "floor";
+pub const FUNC_CEIL: &str = "ceil";
+pub const FUNC_MIN: &str = "min";
+pub const FUNC_MAX: &str = "max";
+
+// Heap allocation, implemented in generated/alloc.asm
+pub const FUNC_ALLOC: &str = "alloc";
+pub const FUNC_FREE: &str = "free";
+pub const FUNC_REALLOC: &str = "realloc";
+
+/// Exits with an error saying where the assertion is if its condition is false
+pub const FUNC_ASSERT: &str = "assert";
+
+/// Length of a str, a String or a slice
+pub const FUNC_LEN: &str = "len";
+
+/// Calls to these never reach a function of the program, so none can be defined with these names
+pub const BUILTIN_FUNCTIONS: [&str; 19] = [
+    FUNC_WRITE,
+    FUNC_EXIT,
+    FUNC_SYSCALL,
+    FUNC_PRINT,
+    FUNC_FPRINT,
+    FUNC_FLUSH,
+    FUNC_READ_LINE,
+    FUNC_ENV,
+    FUNC_SQRT,
+    FUNC_ABS,
+    FUNC_FLOOR,
+    FUNC_CEIL,
+    FUNC_MIN,
+    FUNC_MAX,
+    FUNC_ALLOC,
+    FUNC_FREE,
+    FUNC_REALLOC,
+    FUNC_ASSERT,
+    FUNC_LEN,
+];
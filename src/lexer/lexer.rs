@@ -5,7 +5,7 @@ use std::{
 };
 
 use crate::{
-    lexer::types::{TYPE_FLOAT, TYPE_STRING},
+    lexer::types::{TYPE_FLOAT, TYPE_FLOAT32, TYPE_FLOAT64, TYPE_OWNED_STRING, TYPE_STRING},
     trace,
 };
 
@@ -21,6 +21,8 @@ pub struct Token {
     pub line_number: usize,
     pub col_number: usize,
     pub index: usize,
+    /// Byte offset of the first character of the token in its file
+    pub start: usize,
     pub file: String,
 }
 
@@ -40,7 +42,7 @@ impl Debug for Token {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Lexer {
     pub line_number: usize,
     pub col_number: usize,
@@ -81,6 +83,11 @@ impl Lexer {
                 break;
             }
 
+            // 1..5 is a range and not a float
+            if char == '.' && self.file.get(self.index + 1) == Some(&b'.') {
+                break;
+            }
+
             if is_hex && !char.is_ascii_hexdigit() {
                 break;
             }
@@ -102,7 +109,7 @@ impl Lexer {
                 i32::from_str_radix(&int_string, if is_hex { 16 } else { 10 }).unwrap(),
             ))
         } else {
-            TokenEnum::Number(Number::Float(int_string.parse::<f32>().unwrap()))
+            TokenEnum::Number(Number::Float(int_string.parse::<f64>().unwrap()))
         }
     }
 
@@ -146,8 +153,10 @@ impl Lexer {
                 TYPE_INT8 => VarType::Int8,
                 TYPE_INT16 => VarType::Int16,
                 TYPE_INT32 => VarType::Int32,
-                TYPE_FLOAT => VarType::Float,
+                TYPE_FLOAT | TYPE_FLOAT64 => VarType::Float,
+                TYPE_FLOAT32 => VarType::Float32,
                 TYPE_STRING => VarType::Str,
+                TYPE_OWNED_STRING => VarType::OwnedStr,
                 TYPE_CHAR => VarType::Char,
 
                 _ => {
@@ -187,27 +196,33 @@ impl Lexer {
         return TokenEnum::StringLiteral(string_literal);
     }
 
-    fn parse_comment(&mut self) {
+    /// Returns the text of the comment, `start` is where the first '-' is
+    fn parse_comment(&mut self, start: usize) -> String {
         while self.index < self.file.len() && self.file[self.index] != b'\n' {
             self.index += 1;
         }
+
+        let comment = String::from_utf8_lossy(&self.file[start..self.index]);
+
+        comment.trim_end().to_string()
     }
 
-    pub fn is_comment(&mut self) -> bool {
+    /// If there's a comment at the '-' the lexer is at, skips past it and returns its text
+    pub fn comment(&mut self) -> Option<String> {
+        let start = self.index;
+
         self.index += 1;
 
-        if self.file[self.index] == b'-' {
+        if self.file.get(self.index) == Some(&b'-') {
             self.index += 1;
 
             // sleep(Duration::from_millis(100));
 
             // we have found a comment
-            self.parse_comment();
-
-            return true;
+            return Some(self.parse_comment(start));
         }
 
         self.index -= 1;
-        return false;
+        return None;
     }
 }
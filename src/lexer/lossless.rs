@@ -0,0 +1,74 @@
+use super::{
+    lexer::{Lexer, Token},
+    tokens::TokenEnum,
+};
+
+/// A token and exactly how it's written, so the file can be put back together from its tokens.
+/// Only whitespace is left out
+#[derive(Debug, Clone)]
+pub struct SourceToken {
+    pub token: Token,
+    pub text: String,
+    /// Line breaks between the previous token and this one
+    pub newlines_before: usize,
+}
+
+impl Lexer {
+    /// Every token in the file up to EOF, comments included
+    pub fn lossless_tokens(&mut self) -> Vec<SourceToken> {
+        let mut tokens = vec![];
+        let mut end = self.index;
+
+        loop {
+            let token = self.get_next_token();
+
+            if let TokenEnum::EOF = token.token {
+                break;
+            }
+
+            // one past the last character, or the line break after a comment
+            let token_end = self.index;
+
+            let gap = String::from_utf8_lossy(&self.file[end..token.start]).into_owned();
+            let skipped = gap.trim();
+
+            let mut newlines_before = gap.matches('\n').count();
+
+            // nothing but whitespace should be between two tokens, anything else is kept as it is
+            if !skipped.is_empty() {
+                let leading = gap.find(skipped).unwrap_or(0);
+
+                tokens.push(SourceToken {
+                    token: Token {
+                        token: TokenEnum::Unknown(skipped.into()),
+                        start: end + leading,
+                        ..token.clone()
+                    },
+                    text: skipped.into(),
+                    newlines_before: gap[..leading].matches('\n').count(),
+                });
+
+                newlines_before = gap[leading + skipped.len()..].matches('\n').count();
+            }
+
+            end = token_end;
+
+            let text = String::from_utf8_lossy(&self.file[token.start..token_end])
+                .trim()
+                .to_string();
+
+            // like a '\r', which the lexer doesn't skip
+            if text.is_empty() {
+                continue;
+            }
+
+            tokens.push(SourceToken {
+                token,
+                text,
+                newlines_before,
+            });
+        }
+
+        tokens
+    }
+}
@@ -1,6 +1,7 @@
 pub mod advance;
 pub mod keywords;
 pub mod lexer;
+pub mod lossless;
 pub mod registers;
 pub mod tokens;
 pub mod types;
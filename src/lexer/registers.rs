@@ -188,6 +188,10 @@ impl VarType {
             Self::Int8 => get_register_name_for_bits(&register, 8),
             Self::Char => get_register_name_for_bits(&register, 8),
             Self::Float => get_register_name_for_bits(&register, 64),
+            Self::Float32 => get_register_name_for_bits(&register, 32),
+            Self::Enum(..) => get_register_name_for_bits(&register, 64),
+            Self::OwnedStr => get_register_name_for_bits(&register, 64),
+            Self::Slice(_) => get_register_name_for_bits(&register, 64),
 
             v => panic!("get_register_name not implemented for '{}'", v),
         };
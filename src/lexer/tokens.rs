@@ -9,6 +9,8 @@ pub enum VariableEnum {
     String(String),
     /// Pointer(TypeName) -> Pointer("INT") etc..
     Pointer(String),
+    /// (Union name, Variant name, Tag, Payload)
+    Union(String, String, usize, Box<VariableEnum>),
 }
 
 impl VariableEnum {
@@ -25,6 +27,9 @@ impl VariableEnum {
 
             // Pointer will always consume 8 bytes
             VariableEnum::Pointer(..) => 8,
+
+            // the tag followed by the payload
+            VariableEnum::Union(.., payload) => 8 + payload.size(),
         }
     }
 }
@@ -66,7 +71,7 @@ impl Display for Operations {
 #[derive(Debug, Clone)]
 pub enum Number {
     Integer(i32),
-    Float(f32),
+    Float(f64),
 }
 
 impl Display for Number {
@@ -191,8 +196,10 @@ pub enum TokenEnum {
     SemiColon,
     Ampersand,
     FunctionReturnIndicator,
-    Comment,
+    /// `-- text` up to the end of the line, including the dashes
+    Comment(String),
     Dot,
+    DotDot,
 
     Number(Number),
     Bracket(Bracket),
@@ -207,6 +214,9 @@ pub enum TokenEnum {
 
     StringLiteral(String),
 
+    /// Result.Ok(1) as the interpreter evaluates it. (Union name, Variant name, Tag, Payload)
+    Union(String, String, usize, Box<TokenEnum>),
+
     Unknown(String),
     EOF,
 }
@@ -240,10 +250,14 @@ impl Display for TokenEnum {
             TokenEnum::Variable(token) => write!(f, "{}", token),
             TokenEnum::Type(token) => write!(f, "{}", token),
             TokenEnum::StringLiteral(token) => write!(f, "{}", token),
+            TokenEnum::Union(union_name, variant_name, _, payload) => {
+                write!(f, "{union_name}.{variant_name}({payload})")
+            }
             TokenEnum::Unknown(token) => write!(f, "{}", token),
             TokenEnum::EOF => write!(f, "{}", "EOF"),
-            TokenEnum::Comment => write!(f, "{}", "Comment"),
+            TokenEnum::Comment(comment) => write!(f, "{}", comment),
             TokenEnum::Dot => write!(f, "{}", "."),
+            TokenEnum::DotDot => write!(f, ".."),
         }
     }
 }
@@ -302,7 +316,7 @@ impl TokenEnum {
         }
     }
 
-    pub fn new_float(f: f32) -> TokenEnum {
+    pub fn new_float(f: f64) -> TokenEnum {
         return TokenEnum::Number(Number::Float(f));
     }
 
@@ -310,6 +324,36 @@ impl TokenEnum {
         return TokenEnum::Number(Number::Integer(f));
     }
 
+    /// The name of the variant, without what's in it
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TokenEnum::Equals => "Equals",
+            TokenEnum::PlusEquals => "PlusEquals",
+            TokenEnum::MinusEquals => "MinusEquals",
+            TokenEnum::Comma => "Comma",
+            TokenEnum::Colon => "Colon",
+            TokenEnum::SemiColon => "SemiColon",
+            TokenEnum::Ampersand => "Ampersand",
+            TokenEnum::FunctionReturnIndicator => "FunctionReturnIndicator",
+            TokenEnum::Comment(_) => "Comment",
+            TokenEnum::Dot => "Dot",
+            TokenEnum::DotDot => "DotDot",
+            TokenEnum::Number(_) => "Number",
+            TokenEnum::Bracket(_) => "Bracket",
+            TokenEnum::Op(_) => "Op",
+            TokenEnum::Comparator(_) => "Comparator",
+            TokenEnum::LogicalOp(_) => "LogicalOp",
+            TokenEnum::Bool(_) => "Bool",
+            TokenEnum::Keyword(_) => "Keyword",
+            TokenEnum::Variable(_) => "Variable",
+            TokenEnum::Type(_) => "Type",
+            TokenEnum::StringLiteral(_) => "StringLiteral",
+            TokenEnum::Union(..) => "Union",
+            TokenEnum::Unknown(_) => "Unknown",
+            TokenEnum::EOF => "EOF",
+        }
+    }
+
     pub fn get_assignment_type(&self) -> AssignmentTypes {
         return match self {
             TokenEnum::PlusEquals => AssignmentTypes::PlusEquals,
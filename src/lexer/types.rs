@@ -19,6 +19,15 @@ pub struct StructMemberType {
     pub offset: usize,
 }
 
+/// The tag of a union is always stored as a 64 bit integer
+pub const UNION_TAG_SIZE: usize = 8;
+
+#[derive(Debug, Clone)]
+pub struct EnumVariantType {
+    pub name: String,
+    pub discriminant: i32,
+}
+
 #[derive(Debug, Clone)]
 pub enum VarType {
     // int64
@@ -27,14 +36,27 @@ pub enum VarType {
     Int16,
     Int32,
     Str,
+    /// Heap allocated, growable string. A pointer to a block with the length, the capacity and
+    /// then the bytes
+    OwnedStr,
+    // float64
     Float,
+    Float32,
     Char,
     Ptr(Box<VarType>),
     /// (InnerType, num elements)
     Array(Box<VarType>, usize),
+    /// `[]T`, a pointer to the address of the first element followed by the number of elements
+    Slice(Box<VarType>),
     Struct(String, Rc<RefCell<Vec<StructMemberType>>>), // string = name of struct
     /// (Name, Parameters, ReturnType)
     Function(String, Vec<VarType>, Box<VarType>),
+    /// (Name, Variants)
+    Enum(String, Vec<EnumVariantType>),
+    /// (Name, Variants)
+    /// A tag followed by the payload of the largest variant. The tag of a variant is its index,
+    /// and every variant's payload is a member at the same offset, right after the tag
+    Union(String, Rc<RefCell<Vec<StructMemberType>>>),
     Unknown,
 }
 
@@ -46,10 +68,12 @@ impl PartialEq for VarType {
             | (VarType::Int16, VarType::Int16)
             | (VarType::Int32, VarType::Int32)
             | (VarType::Float, VarType::Float)
+            | (VarType::Float32, VarType::Float32)
             | (VarType::Char, VarType::Char)
             | (VarType::Unknown, VarType::Unknown) => true,
 
             (VarType::Str, VarType::Str) => true,
+            (VarType::OwnedStr, VarType::OwnedStr) => true,
 
             (VarType::Str, VarType::Ptr(boxed)) | (VarType::Ptr(boxed), VarType::Str) => {
                 matches!(**boxed, VarType::Char)
@@ -58,6 +82,7 @@ impl PartialEq for VarType {
             (VarType::Ptr(a), VarType::Ptr(b)) => a == b,
 
             (VarType::Array(a, s1), VarType::Array(b, s2)) => a == b && s1 == s2,
+            (VarType::Slice(a), VarType::Slice(b)) => a == b,
 
             (VarType::Struct(name1, members1), VarType::Struct(name2, members2)) => {
                 if name1 != name2 {
@@ -103,6 +128,10 @@ impl PartialEq for VarType {
                 return true;
             }
 
+            // Variants are fixed at definition, so the name is enough
+            (VarType::Enum(name1, _), VarType::Enum(name2, _)) => name1 == name2,
+            (VarType::Union(name1, _), VarType::Union(name2, _)) => name1 == name2,
+
             (VarType::Ptr(boxed), other2) | (other2, VarType::Ptr(boxed)) => **boxed == *other2,
 
             _ => false,
@@ -157,11 +186,17 @@ impl VarType {
 
             Str => *other == Str,
 
+            OwnedStr => matches!(other, OwnedStr),
+
             Float => matches!(other, Float),
 
+            Float32 => matches!(other, Float32),
+
             Char => *other == Char || *other == Int8,
 
             Ptr(inner) => match other {
+                // alloc returns a pointer to Unknown, which can be assigned to any pointer
+                Ptr(inner2) if matches!(**inner, Unknown) || matches!(**inner2, Unknown) => true,
                 Ptr(inner2) => inner.can_assign(inner2),
                 Function(..) => inner.can_assign(other),
 
@@ -173,6 +208,11 @@ impl VarType {
                 _ => false,
             },
 
+            Slice(inner1) => match other {
+                Slice(inner2) => inner1 == inner2,
+                _ => false,
+            },
+
             Struct(name1, members1) => match other {
                 Struct(name2, members2) => {
                     let mem1borrow = members1.borrow();
@@ -219,6 +259,16 @@ impl VarType {
                 _ => false,
             },
 
+            Enum(name1, _) => match other {
+                Enum(name2, _) => name1 == name2,
+                _ => false,
+            },
+
+            Union(name1, _) => match other {
+                Union(name2, _) => name1 == name2,
+                _ => false,
+            },
+
             Unknown => todo!(),
         };
     }
@@ -254,6 +304,7 @@ impl VarType {
 
             // No matter what the op is, the result will always be an float
             (Float, Float) => Float,
+            (Float32, Float32) => Float32,
 
             // Incrementing a pointer
             // char is represented as an int so this should be fine
@@ -276,6 +327,29 @@ impl VarType {
 
             (Ptr(ptr1), Ptr(ptr2)) => ptr1.figure_out_type(ptr2, op),
 
+            // Concatenation makes a new String, comparisons compare the bytes
+            (OwnedStr, OwnedStr | Str) | (Str, OwnedStr) => match op {
+                AllOperations::Op(Plus) => OwnedStr,
+                AllOperations::Comparator(..) => Int,
+
+                _ => panic!("'{op}' not defined for '{self}' and '{other}'"),
+            },
+
+            (Enum(name1, _), Enum(name2, _)) => {
+                let is_allowed = name1 == name2
+                    && matches!(
+                        op,
+                        AllOperations::Comparator(DoubleEquals) | AllOperations::Comparator(NotEquals)
+                    );
+
+                if !is_allowed {
+                    panic!("'{op}' not defined for '{self}' and '{other}'")
+                }
+
+                // result of comparison is always an int
+                Int
+            }
+
             (Char, Char) | (Int8, Char) | (Char, Int8) => {
                 let is_allowed = matches!(
                     op,
@@ -308,6 +382,13 @@ impl VarType {
                 Int
             }
 
+            (Float | Float32, Int | Int8 | Int16 | Int32 | Char)
+            | (Int | Int8 | Int16 | Int32 | Char, Float | Float32)
+            | (Float, Float32)
+            | (Float32, Float) => {
+                panic!("'{op}' not defined for '{self}' and '{other}'. Convert one of them with 'as'")
+            }
+
             (..) => {
                 panic!("'{op}' not defined for '{self}' and '{other}'")
             }
@@ -323,8 +404,10 @@ impl VarType {
             VarType::Int8 => 1,
             // 8 bytes for length + 8 bytes for pointer to the start of the string
             VarType::Str => 16,
-            // We only have float64
+            // pointer to the heap block
+            VarType::OwnedStr => 8,
             VarType::Float => 8,
+            VarType::Float32 => 4,
             // char is only 1 byte
             VarType::Char => 1,
             // Pointer will always consume 8 bytes
@@ -333,6 +416,9 @@ impl VarType {
 
             VarType::Array(type_, elements) => type_.get_size() * elements,
 
+            // pointer to the address and the length
+            VarType::Slice(_) => 8,
+
             VarType::Struct(_, members) => {
                 let size = members
                     .borrow()
@@ -348,6 +434,16 @@ impl VarType {
 
             // Doesn't matter how large the definition is, it's always just a pointer
             VarType::Function(_, _, _) => 8,
+
+            // Only the discriminant is stored
+            VarType::Enum(..) => 8,
+
+            VarType::Union(..) => {
+                let payload_size = self.get_union_payload_size();
+
+                // tag + largest payload, padded so the next variable stays 8 byte aligned
+                UNION_TAG_SIZE + payload_size + (8 - payload_size % 8) % 8
+            }
         };
     }
 
@@ -358,10 +454,13 @@ impl VarType {
             VarType::Int16 => 2,
             VarType::Int32 => 4,
             VarType::Str => 16,
+            VarType::OwnedStr => 8,
             VarType::Float => 8,
+            VarType::Float32 => 4,
             VarType::Char => 1,
             VarType::Ptr(_) => 8,
             VarType::Array(inner_type, _) => inner_type.get_mem_alignment(),
+            VarType::Slice(_) => 8,
 
             VarType::Struct(name, members) => {
                 let mut max = 8;
@@ -379,6 +478,8 @@ impl VarType {
             }
 
             VarType::Function(_, _, _) => 8,
+            VarType::Enum(..) => 8,
+            VarType::Union(..) => 8,
             VarType::Unknown => todo!(),
         }
     }
@@ -420,10 +521,25 @@ impl VarType {
         };
     }
 
+    /// Size of the largest payload of a union
+    pub fn get_union_payload_size(&self) -> usize {
+        return match self {
+            VarType::Union(_, variants) => variants
+                .borrow()
+                .iter()
+                .map(|variant| variant.member_type.get_size())
+                .max()
+                .unwrap_or(0),
+
+            _ => unreachable!("get_union_payload_size called on non union type {self}"),
+        };
+    }
+
     pub fn get_underlying_type_size(&self) -> usize {
         return match self {
             VarType::Ptr(type_) => type_.get_size(),
             VarType::Array(type_, _) => type_.get_size(),
+            VarType::Slice(type_) => type_.get_size(),
 
             _ => self.get_size(),
         };
@@ -435,17 +551,36 @@ impl VarType {
             VarType::Int8 => "BYTE",
             VarType::Int16 => "WORD",
             VarType::Int32 => "DWORD",
+            VarType::Enum(..) => "QWORD",
+            VarType::OwnedStr => "QWORD",
+            VarType::Union(..) => todo!(),
+
+            VarType::Float => "QWORD",
+            VarType::Float32 => "DWORD",
 
             VarType::Str => todo!(),
-            VarType::Float => todo!(),
             VarType::Char => todo!(),
             VarType::Ptr(_) => todo!(),
             VarType::Array(_, _) => todo!(),
+            VarType::Slice(_) => "QWORD",
             VarType::Struct(_, _) => todo!(),
             VarType::Function(_, _, _) => todo!(),
             VarType::Unknown => todo!(),
         }
     }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, VarType::Float | VarType::Float32)
+    }
+
+    /// Suffix of the SSE instruction for this float type. movsd, addsd for float64 and movss,
+    /// addss for float32
+    pub fn get_float_suffix(&self) -> &str {
+        match self {
+            VarType::Float32 => "ss",
+            _ => "sd",
+        }
+    }
 }
 
 impl Display for VarType {
@@ -456,15 +591,20 @@ impl Display for VarType {
             VarType::Int16 => "Integer16".to_string(),
             VarType::Int8 => "Integer8".to_string(),
             VarType::Str => "String".to_string(),
+            VarType::OwnedStr => "Owned String".to_string(),
             VarType::Float => "Floating Point".to_string(),
+            VarType::Float32 => "Floating Point32".to_string(),
             VarType::Ptr(var_type) => format!("Pointer -> {}", *var_type),
             VarType::Char => "Character".to_string(),
             VarType::Unknown => "Unknown".to_string(),
             VarType::Struct(name, _) => name.into(),
             VarType::Array(var_type, sz) => format!("Array of {} of size {sz}", *var_type),
+            VarType::Slice(var_type) => format!("Slice of {}", *var_type),
             VarType::Function(name, params, return_type) => {
                 format!("Function type < Name: {name}, params: {params:?}, return_type: {return_type} >")
             }
+            VarType::Enum(name, _) => name.into(),
+            VarType::Union(name, _) => name.into(),
         };
 
         write!(f, "{}", msg)
@@ -477,15 +617,21 @@ pub const TYPE_INT8: &str = "int8";
 pub const TYPE_INT16: &str = "int16";
 pub const TYPE_INT32: &str = "int32";
 pub const TYPE_FLOAT: &str = "float";
+pub const TYPE_FLOAT32: &str = "float32";
+pub const TYPE_FLOAT64: &str = "float64";
 pub const TYPE_STRING: &str = "str";
+pub const TYPE_OWNED_STRING: &str = "String";
 pub const TYPE_CHAR: &str = "char";
 
-pub const PREDEFINED_TYPES: [&str; 7] = [
+pub const PREDEFINED_TYPES: [&str; 10] = [
     TYPE_INT,
     TYPE_INT8,
     TYPE_INT16,
     TYPE_INT32,
     TYPE_FLOAT,
+    TYPE_FLOAT32,
+    TYPE_FLOAT64,
     TYPE_STRING,
+    TYPE_OWNED_STRING,
     TYPE_CHAR,
 ];
@@ -0,0 +1,173 @@
+use std::rc::Rc;
+
+use crate::{
+    helpers::{catch_compile_error, SourceLocation},
+    lexer::types::VarType,
+    parser::parser::Parser,
+    semantic_analyzer::{semantic_analyzer::SemanticAnalyzer, symbols::Symbols, warnings::WarningOptions},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// None if the compiler itself failed
+    pub location: Option<SourceLocation>,
+    pub message: String,
+    pub severity: Severity,
+}
+
+#[derive(Debug)]
+pub struct Analysis {
+    pub diagnostics: Vec<Diagnostic>,
+    /// None if the program has an error, the symbols of a program that's half analysed would be
+    /// missing most of it
+    pub symbols: Option<Symbols>,
+}
+
+/// Parses and analyses `text` as if it were the file at `path`, collecting what the compiler
+/// would've printed instead of exiting
+pub fn analyze(text: &str, path: &str) -> Analysis {
+    let mut parser = Parser::new(text.as_bytes().to_vec(), path.into());
+
+    let result = catch_compile_error(|| {
+        let ast = parser.parse_program();
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(
+            ast,
+            Rc::clone(&parser.functions),
+            &parser.user_defined_types,
+            &parser.type_aliases,
+            &parser.includes,
+            WarningOptions::default(),
+        );
+        semantic_analyzer.check();
+
+        let warnings = semantic_analyzer.call_stack.warnings.messages().to_vec();
+
+        (warnings, std::mem::take(&mut semantic_analyzer.call_stack.symbols))
+    });
+
+    match result {
+        Ok((warnings, mut symbols)) => {
+            symbols.extend(std::mem::take(&mut parser.symbols));
+
+            Analysis {
+                diagnostics: warnings
+                    .into_iter()
+                    .map(|warning| Diagnostic {
+                        location: Some(warning.location),
+                        message: warning.message,
+                        severity: Severity::Warning,
+                    })
+                    .collect(),
+                symbols: Some(symbols),
+            }
+        }
+
+        Err(error) => Analysis {
+            diagnostics: vec![Diagnostic {
+                location: error.location,
+                message: error.message,
+                severity: Severity::Error,
+            }],
+            symbols: None,
+        },
+    }
+}
+
+/// LSP positions count lines from 0 and characters in UTF-16 code units
+pub fn offset_to_position(text: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(text.len());
+    let before = text.get(..offset).unwrap_or(text);
+
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let character = before[line_start..].encode_utf16().count();
+
+    (line, character)
+}
+
+pub fn position_to_offset(text: &str, line: usize, character: usize) -> usize {
+    let mut line_start = 0;
+
+    for _ in 0..line {
+        match text[line_start..].find('\n') {
+            Some(newline) => line_start += newline + 1,
+            None => return text.len(),
+        }
+    }
+
+    let mut units = 0;
+
+    for (i, c) in text[line_start..].char_indices() {
+        if units >= character || c == '\n' {
+            return line_start + i;
+        }
+
+        units += c.len_utf16();
+    }
+
+    text.len()
+}
+
+const TWO_CHAR_OPERATORS: [&str; 14] = [
+    "==", "!=", "<=", ">=", "->", "<<", ">>", "&&", "||", "+=", "-=", "*=", "/=", "::",
+];
+
+/// Tokens only know where they start, this is how many bytes the one at `start` takes
+pub fn token_length(text: &str, start: usize) -> usize {
+    let Some(rest) = text.get(start..) else {
+        return 0;
+    };
+
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+
+    match rest.chars().next() {
+        None => 0,
+
+        Some('"') => rest[1..].find('"').map_or(rest.len(), |end| end + 2),
+
+        Some(c) if c.is_ascii_digit() => rest.find(|c: char| !is_identifier(c) && c != '.').unwrap_or(rest.len()),
+
+        Some(c) if is_identifier(c) => rest.find(|c: char| !is_identifier(c)).unwrap_or(rest.len()),
+
+        Some(c) if TWO_CHAR_OPERATORS.iter().any(|op| rest.starts_with(op)) => 2,
+
+        Some(c) => c.len_utf8(),
+    }
+}
+
+/// A type the way it's written in a program, `VarType`'s Display is meant for error messages
+pub fn source_type_name(var_type: &VarType) -> String {
+    match var_type {
+        VarType::Int => "int".into(),
+        VarType::Int8 => "int8".into(),
+        VarType::Int16 => "int16".into(),
+        VarType::Int32 => "int32".into(),
+        VarType::Str => "str".into(),
+        VarType::OwnedStr => "String".into(),
+        VarType::Float => "float".into(),
+        VarType::Float32 => "float32".into(),
+        VarType::Char => "char".into(),
+        VarType::Ptr(inner) => format!("*{}", source_type_name(inner)),
+        VarType::Array(inner, size) => format!("{}[{size}]", source_type_name(inner)),
+        VarType::Slice(inner) => format!("[]{}", source_type_name(inner)),
+        VarType::Struct(name, _) | VarType::Enum(name, _) | VarType::Union(name, _) => name.clone(),
+
+        VarType::Function(_, params, return_type) => {
+            let params: Vec<String> = params.iter().map(source_type_name).collect();
+
+            match **return_type {
+                VarType::Unknown => format!("def ({})", params.join(", ")),
+                ref return_type => format!("def ({}) -> {}", params.join(", "), source_type_name(return_type)),
+            }
+        }
+
+        VarType::Unknown => "unknown".into(),
+    }
+}
@@ -0,0 +1,559 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Read, Write},
+};
+
+use crate::{
+    json::Json,
+    lexer::{lexer::Token, types::VarType},
+    semantic_analyzer::symbols::{SymbolKind, Symbols},
+};
+
+use super::analysis::{analyze, offset_to_position, position_to_offset, source_type_name, token_length, Severity};
+
+const METHOD_NOT_FOUND: i32 = -32601;
+const PARSE_ERROR: i32 = -32700;
+
+/// LSP's CompletionItemKind.Field
+const COMPLETION_KIND_FIELD: usize = 5;
+
+#[derive(Debug)]
+struct Document {
+    text: String,
+    /// What the compiler calls the file, tokens in it have this as their `file`
+    path: String,
+    /// From the last time the document had no errors, so most of the features keep working while
+    /// it's being edited
+    symbols: Option<Symbols>,
+}
+
+/// A language server talking to one editor. Messages are read from `input` and written to
+/// `output`, which are stdin and stdout for `lang lsp`
+pub struct Server<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    /// Open documents by their uri
+    documents: HashMap<String, Document>,
+    shutdown_requested: bool,
+}
+
+/// Starts the server on stdin and stdout, returns the exit code
+pub fn run() -> i32 {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    Server::new(stdin.lock(), stdout.lock()).run()
+}
+
+pub fn uri_to_path(uri: &str) -> String {
+    let path = uri.strip_prefix("file://").unwrap_or(uri);
+
+    let mut bytes = vec![];
+    let mut chars = path.bytes();
+
+    while let Some(byte) = chars.next() {
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+
+        let hex: Vec<u8> = chars.by_ref().take(2).collect();
+
+        match std::str::from_utf8(&hex)
+            .ok()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            Some(decoded) => bytes.push(decoded),
+            None => {
+                bytes.push(b'%');
+                bytes.extend(hex);
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+pub fn path_to_uri(path: &str) -> String {
+    let mut uri = String::from("file://");
+
+    for byte in path.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    uri
+}
+
+fn range(text: &str, start: usize, length: usize) -> Json {
+    let position = |offset| {
+        let (line, character) = offset_to_position(text, offset);
+        Json::object([("line", line.into()), ("character", character.into())])
+    };
+
+    Json::object([("start", position(start)), ("end", position(start + length))])
+}
+
+fn is_same_place(a: &Token, b: &Token) -> bool {
+    a.start == b.start && a.file == b.file
+}
+
+/// The members that can come after a `.` on a value of this type
+fn members(var_type: &VarType) -> Vec<(String, VarType)> {
+    match var_type {
+        VarType::Struct(_, members) => members
+            .borrow()
+            .iter()
+            .map(|member| (member.name.clone(), member.member_type.clone()))
+            .collect(),
+
+        // members are accessed through pointers the same way
+        VarType::Ptr(inner) => members(inner),
+
+        _ => vec![],
+    }
+}
+
+impl<R: BufRead, W: Write> Server<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self {
+            input,
+            output,
+            documents: HashMap::new(),
+            shutdown_requested: false,
+        }
+    }
+
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Handles messages until `exit`, or until the input ends. Exits with 0 only if the client
+    /// asked to shut down first
+    pub fn run(&mut self) -> i32 {
+        while let Some(message) = self.read_message() {
+            match Json::parse(&message) {
+                Ok(message) => {
+                    if !self.handle(&message) {
+                        break;
+                    }
+                }
+
+                Err(error) => self.respond_error(&Json::Null, PARSE_ERROR, &error),
+            }
+        }
+
+        if self.shutdown_requested {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Messages are a `Content-Length` header, an empty line and then the JSON
+    fn read_message(&mut self) -> Option<String> {
+        let mut content_length = None;
+
+        loop {
+            let mut header = String::new();
+
+            if self.input.read_line(&mut header).ok()? == 0 {
+                return None;
+            }
+
+            let header = header.trim_end();
+
+            if header.is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("Content-Length") {
+                    content_length = value.trim().parse::<usize>().ok();
+                }
+            }
+        }
+
+        let mut content = vec![0; content_length?];
+        self.input.read_exact(&mut content).ok()?;
+
+        String::from_utf8(content).ok()
+    }
+
+    fn send(&mut self, message: Json) {
+        let content = message.to_string();
+
+        // the editor has gone away if this fails, and `exit` will never come
+        let _ = write!(self.output, "Content-Length: {}\r\n\r\n{content}", content.len());
+        let _ = self.output.flush();
+    }
+
+    fn respond(&mut self, id: &Json, result: Json) {
+        self.send(Json::object([
+            ("jsonrpc", "2.0".into()),
+            ("id", id.clone()),
+            ("result", result),
+        ]));
+    }
+
+    fn respond_error(&mut self, id: &Json, code: i32, message: &str) {
+        let error = Json::object([("code", Json::Number(code as f64)), ("message", message.into())]);
+
+        self.send(Json::object([
+            ("jsonrpc", "2.0".into()),
+            ("id", id.clone()),
+            ("error", error),
+        ]));
+    }
+
+    fn notify(&mut self, method: &str, params: Json) {
+        self.send(Json::object([
+            ("jsonrpc", "2.0".into()),
+            ("method", method.into()),
+            ("params", params),
+        ]));
+    }
+
+    /// Returns false once the client has said `exit`
+    fn handle(&mut self, message: &Json) -> bool {
+        let method = message.get("method").as_str().unwrap_or_default();
+        let params = message.get("params");
+        let id = message.get("id");
+
+        let result = match method {
+            "initialize" => Some(Self::capabilities()),
+
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Some(Json::Null)
+            }
+
+            "exit" => return false,
+
+            "textDocument/didOpen" => {
+                let document = params.get("textDocument");
+
+                if let (Some(uri), Some(text)) = (document.get("uri").as_str(), document.get("text").as_str()) {
+                    self.update_document(uri, text.into());
+                }
+
+                None
+            }
+
+            "textDocument/didChange" => {
+                let uri = params.get("textDocument").get("uri").as_str();
+
+                // the whole document is sent on every change, the last one is the newest
+                let text = params
+                    .get("contentChanges")
+                    .as_array()
+                    .last()
+                    .and_then(|change| change.get("text").as_str());
+
+                if let (Some(uri), Some(text)) = (uri, text) {
+                    self.update_document(uri, text.into());
+                }
+
+                None
+            }
+
+            "textDocument/didClose" => {
+                if let Some(uri) = params.get("textDocument").get("uri").as_str() {
+                    self.documents.remove(uri);
+                    self.notify(
+                        "textDocument/publishDiagnostics",
+                        Json::object([("uri", uri.into()), ("diagnostics", Json::Array(vec![]))]),
+                    );
+                }
+
+                None
+            }
+
+            "textDocument/definition" => Some(self.definition(params)),
+            "textDocument/references" => Some(self.references(params)),
+            "textDocument/hover" => Some(self.hover(params)),
+            "textDocument/completion" => Some(self.completion(params)),
+
+            // notifications the server doesn't care about, like `initialized`
+            _ if id.is_null() => None,
+
+            _ => {
+                self.respond_error(id, METHOD_NOT_FOUND, &format!("Unknown method '{method}'"));
+                None
+            }
+        };
+
+        if let Some(result) = result {
+            self.respond(id, result);
+        }
+
+        true
+    }
+
+    fn capabilities() -> Json {
+        Json::object([
+            (
+                "capabilities",
+                Json::object([
+                    // the whole document is sent on every change
+                    ("textDocumentSync", 1.into()),
+                    ("hoverProvider", true.into()),
+                    ("definitionProvider", true.into()),
+                    ("referencesProvider", true.into()),
+                    (
+                        "completionProvider",
+                        Json::object([("triggerCharacters", vec![Json::from(".")].into())]),
+                    ),
+                ]),
+            ),
+            ("serverInfo", Json::object([("name", "lang".into())])),
+        ])
+    }
+
+    /// Analyses the new text of the document and sends the editor its errors and warnings
+    fn update_document(&mut self, uri: &str, text: String) {
+        let path = uri_to_path(uri);
+        let analysis = analyze(&text, &path);
+
+        let diagnostics = analysis
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let (range, message) = match &diagnostic.location {
+                    Some(location) if location.file == path => (
+                        range(&text, location.start, token_length(&text, location.start)),
+                        diagnostic.message.clone(),
+                    ),
+
+                    // in an included file, shown at the top of the document
+                    Some(location) => (
+                        range(&text, 0, 0),
+                        format!("{}:{}: {}", location.file, location.line_number, diagnostic.message),
+                    ),
+
+                    None => (range(&text, 0, 0), diagnostic.message.clone()),
+                };
+
+                let severity = match diagnostic.severity {
+                    Severity::Error => 1,
+                    Severity::Warning => 2,
+                };
+
+                Json::object([
+                    ("range", range),
+                    ("severity", severity.into()),
+                    ("source", "lang".into()),
+                    ("message", message.into()),
+                ])
+            })
+            .collect();
+
+        let previous_symbols = self.documents.remove(uri).and_then(|document| document.symbols);
+
+        self.documents.insert(
+            uri.into(),
+            Document {
+                text,
+                path,
+                symbols: analysis.symbols.or(previous_symbols),
+            },
+        );
+
+        self.notify(
+            "textDocument/publishDiagnostics",
+            Json::object([("uri", uri.into()), ("diagnostics", Json::Array(diagnostics))]),
+        );
+    }
+
+    /// The document and the byte offset a request is about
+    fn document_and_offset(&self, params: &Json) -> Option<(&Document, usize)> {
+        let document = self.documents.get(params.get("textDocument").get("uri").as_str()?)?;
+
+        let position = params.get("position");
+        let line = position.get("line").as_u64()? as usize;
+        let character = position.get("character").as_u64()? as usize;
+
+        Some((document, position_to_offset(&document.text, line, character)))
+    }
+
+    /// Whether the cursor is on `token`, including right after its last character
+    fn is_at(document: &Document, token: &Token, offset: usize) -> bool {
+        token.file == document.path
+            && token.start <= offset
+            && offset <= token.start + token_length(&document.text, token.start)
+    }
+
+    /// The definition of the name under the cursor
+    fn definition_at(&self, params: &Json) -> Option<(&Document, Token)> {
+        let (document, offset) = self.document_and_offset(params)?;
+
+        let (_, definition) = document
+            .symbols
+            .as_ref()?
+            .references
+            .iter()
+            .find(|(used, _)| Self::is_at(document, used, offset))?;
+
+        Some((document, definition.clone()))
+    }
+
+    fn location(document: &Document, token: &Token) -> Option<Json> {
+        let (uri, text) = if token.file == document.path {
+            (None, document.text.clone())
+        } else {
+            let path = std::fs::canonicalize(&token.file).ok()?;
+            let path = path.to_string_lossy();
+
+            (Some(path_to_uri(&path)), std::fs::read_to_string(path.as_ref()).ok()?)
+        };
+
+        let uri = uri.unwrap_or_else(|| path_to_uri(&document.path));
+
+        Some(Json::object([
+            ("uri", uri.into()),
+            ("range", range(&text, token.start, token_length(&text, token.start))),
+        ]))
+    }
+
+    fn definition(&self, params: &Json) -> Json {
+        self.definition_at(params)
+            .and_then(|(document, definition)| Self::location(document, &definition))
+            .unwrap_or(Json::Null)
+    }
+
+    fn references(&self, params: &Json) -> Json {
+        let Some((document, definition)) = self.definition_at(params) else {
+            return Json::Null;
+        };
+
+        let include_declaration = params
+            .get("context")
+            .get("includeDeclaration")
+            .as_bool()
+            .unwrap_or(true);
+
+        let Some(symbols) = &document.symbols else {
+            return Json::Null;
+        };
+
+        let mut uses: Vec<&Token> = symbols
+            .references
+            .iter()
+            .filter(|(used, defined)| {
+                is_same_place(defined, &definition) && (include_declaration || !is_same_place(used, defined))
+            })
+            .map(|(used, _)| used)
+            .collect();
+
+        uses.sort_by(|a, b| (&a.file, a.start).cmp(&(&b.file, b.start)));
+
+        uses.into_iter()
+            .filter_map(|used| Self::location(document, used))
+            .collect::<Vec<Json>>()
+            .into()
+    }
+
+    fn hover(&self, params: &Json) -> Json {
+        let Some((document, offset)) = self.document_and_offset(params) else {
+            return Json::Null;
+        };
+
+        let Some(symbols) = &document.symbols else {
+            return Json::Null;
+        };
+
+        let Some((token, var_type)) = symbols
+            .types
+            .iter()
+            .find(|(token, _)| Self::is_at(document, token, offset))
+        else {
+            return Json::Null;
+        };
+
+        let length = token_length(&document.text, token.start);
+        let written = &document.text[token.start..token.start + length];
+
+        let value = if written.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            format!("{written}: {}", source_type_name(var_type))
+        } else {
+            source_type_name(var_type)
+        };
+
+        Json::object([
+            (
+                "contents",
+                Json::object([("kind", "plaintext".into()), ("value", value.into())]),
+            ),
+            ("range", range(&document.text, token.start, length)),
+        ])
+    }
+
+    /// Members of the struct before the `.` the cursor is after. The variable the chain starts
+    /// with is the closest one with that name defined before the cursor
+    fn completion(&self, params: &Json) -> Json {
+        let empty = Json::Array(vec![]);
+
+        let Some((document, offset)) = self.document_and_offset(params) else {
+            return empty;
+        };
+
+        let Some(symbols) = &document.symbols else {
+            return empty;
+        };
+
+        // `a.b.c` in `a.b.c.d`, the member being written doesn't matter
+        let before = &document.text[..offset];
+        let before = before.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_');
+
+        let Some(before) = before.strip_suffix('.') else {
+            return empty;
+        };
+
+        let chain_start = before
+            .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+            .map_or(0, |i| i + 1);
+
+        let mut names = before[chain_start..].split('.');
+
+        let Some(root) = names.next() else {
+            return empty;
+        };
+
+        let Some(root) = symbols
+            .definitions
+            .iter()
+            .filter(|d| {
+                d.kind == SymbolKind::Variable
+                    && d.name == root
+                    && d.token.file == document.path
+                    && d.token.start < offset
+            })
+            .max_by_key(|d| d.token.start)
+        else {
+            return empty;
+        };
+
+        let mut var_type = root.var_type.clone();
+
+        for name in names {
+            match members(&var_type).into_iter().find(|(member, _)| member == name) {
+                Some((_, member_type)) => var_type = member_type,
+                None => return empty,
+            }
+        }
+
+        members(&var_type)
+            .into_iter()
+            .map(|(name, member_type)| {
+                Json::object([
+                    ("label", name.into()),
+                    ("kind", COMPLETION_KIND_FIELD.into()),
+                    ("detail", source_type_name(&member_type).into()),
+                ])
+            })
+            .collect::<Vec<Json>>()
+            .into()
+    }
+}
@@ -11,22 +11,44 @@ use std::{
 use lexer::types::VarType;
 use parser::parser::Parser;
 
-use crate::{interpreter::interpreter::Interpreter, semantic_analyzer::semantic_analyzer::SemanticAnalyzer};
+use crate::{
+    dump::Dump,
+    interpreter::interpreter::Interpreter,
+    options::{CompilerOptions, MAX_FLOAT_PRECISION},
+    semantic_analyzer::{
+        semantic_analyzer::SemanticAnalyzer,
+        warnings::{Warning, WarningOptions},
+    },
+};
 
 mod asm;
 mod ast;
 mod constants;
+mod dump;
+mod formatter;
 mod helpers;
 mod interpreter;
+mod json;
 mod lexer;
+mod lsp;
+mod options;
 mod parser;
+mod repl;
 mod semantic_analyzer;
+mod testing;
 mod tests;
 mod types;
 
-pub fn generate_asm(linker_flags: &Vec<String>) -> io::Result<()> {
+pub fn generate_asm(linker_flags: &Vec<String>, debug_info: bool) -> io::Result<()> {
     let mut nasm = std::process::Command::new("nasm");
-    nasm.args(["-f", "elf64", "-g", "-o", "output.o", "output.asm"]);
+    nasm.args(["-f", "elf64", "-o", "output.o", "output.asm"]);
+
+    // NASM's own debug info points at output.asm. With -g the source lines are in output.asm
+    // already, and the two would clash
+    if !debug_info {
+        nasm.arg("-g");
+    }
+
     let mut spawn = nasm.spawn()?;
     spawn.wait()?;
 
@@ -57,6 +79,7 @@ pub fn parse_input_file(
     run_asm: bool,
     is_test: bool,
     linker_flags: &Vec<String>,
+    options: &CompilerOptions,
 ) -> Option<(ChildStdout, ChildStderr)> {
     println!("Parsing file {path}");
 
@@ -68,7 +91,7 @@ pub fn parse_input_file(
         }
     };
 
-    let mut parser = Parser::new(file, path);
+    let mut parser = Parser::new(file, path.clone());
     let ast = parser.parse_program();
 
     let mut semantic_analyzer = SemanticAnalyzer::new(
@@ -76,21 +99,40 @@ pub fn parse_input_file(
         Rc::clone(&parser.functions),
         &parser.user_defined_types,
         &parser.type_aliases,
+        &parser.includes,
+        options.warnings.clone(),
     );
     semantic_analyzer.analyze();
 
-    let mut interpreter = Interpreter::new(ast.clone(), parser.functions.clone());
+    if !parser.functions.borrow().contains_key("main") {
+        eprintln!("{path} No 'main' function found, the program starts at 'main'");
+        exit(1);
+    }
+
+    let mut interpreter = Interpreter::new(ast.clone(), parser.functions.clone(), options);
 
-    let mut semantic_analyzer =
-        SemanticAnalyzer::new(ast, parser.functions, &parser.user_defined_types, &parser.type_aliases);
+    // only used for its call stack, the warnings have been reported already
+    let mut semantic_analyzer = SemanticAnalyzer::new(
+        ast,
+        parser.functions,
+        &parser.user_defined_types,
+        &parser.type_aliases,
+        &parser.includes,
+        WarningOptions::default(),
+    );
 
     if compile_mode {
         let _result = interpreter.compile(&mut semantic_analyzer.call_stack);
 
+        if options.emit_asm {
+            println!("Wrote generated/output.asm. Not assembling the program");
+            return None;
+        }
+
         let current_dir = std::env::current_dir().unwrap();
         std::env::set_current_dir("./generated").unwrap();
 
-        match generate_asm(linker_flags) {
+        match generate_asm(linker_flags, options.debug_info) {
             Ok(_) => {
                 println!("Successful!{}", if run_asm { "" } else { " Not running the program" });
             }
@@ -157,6 +199,10 @@ fn main() {
 
     let mut linker_flags: Vec<String> = vec![];
 
+    let mut options = CompilerOptions::default();
+
+    let mut dump = None;
+
     let mut iterartor = cmd_args.iter().skip(1);
 
     while let Some(arg) = iterartor.next() {
@@ -164,6 +210,10 @@ fn main() {
             "com" => COMPILE_MODE = true,
             "int" => COMPILE_MODE = false,
             "-r" => RUN_PROGRAM = true,
+            "lsp" => exit(lsp::server::run()),
+            "repl" => exit(repl::repl::run()),
+            "fmt" => exit(formatter::formatter::run(&iterartor.cloned().collect::<Vec<String>>())),
+            "test" => exit(testing::testing::run(&iterartor.cloned().collect::<Vec<String>>())),
             "-f" => file_name_next = true,
 
             e if e.starts_with("-L") => {
@@ -188,6 +238,40 @@ fn main() {
                 }
             }
 
+            "-Werror" => options.warnings.as_errors = true,
+
+            "-W" | "-A" => {
+                let enable = arg == "-W";
+
+                match iterartor.next() {
+                    Some(name) if options.warnings.set(name, enable) => {}
+
+                    _ => {
+                        let names: Vec<&str> = Warning::ALL.iter().map(|w| w.name()).collect();
+
+                        eprintln!("{arg} expects 'all' or one of: {}", names.join(", "));
+                        exit(1);
+                    }
+                }
+            }
+
+            "-g" => options.debug_info = true,
+            "--emit=asm" => options.emit_asm = true,
+            "--emit=tokens-json" => dump = Some(Dump::Tokens),
+            "--emit=ast-json" => dump = Some(Dump::Ast),
+
+            "--checked-casts" => options.checked_casts = true,
+            "--debug-alloc" => options.debug_alloc = true,
+
+            "--float-precision" => match iterartor.next().map(|p| p.parse::<usize>()) {
+                Some(Ok(precision)) if precision <= MAX_FLOAT_PRECISION => options.float_precision = precision,
+
+                _ => {
+                    eprintln!("--float-precision expects a number between 0 and {MAX_FLOAT_PRECISION}");
+                    exit(1);
+                }
+            },
+
             e => {
                 if !file_name_next {
                     println!("Unrecognised arg {e}");
@@ -199,7 +283,18 @@ fn main() {
         };
     }
 
-    if let Some(ref mut stdout) = parse_input_file(file_name.into(), COMPILE_MODE, RUN_PROGRAM, false, &linker_flags) {
+    if let Some(dump) = dump {
+        exit(dump::run(file_name, dump));
+    }
+
+    if let Some(ref mut stdout) = parse_input_file(
+        file_name.into(),
+        COMPILE_MODE,
+        RUN_PROGRAM,
+        false,
+        &linker_flags,
+        &options,
+    ) {
         let mut str = String::new();
         stdout.0.read_to_string(&mut str);
         println!("{:?}", str);
@@ -0,0 +1,36 @@
+use crate::semantic_analyzer::warnings::WarningOptions;
+
+pub const DEFAULT_FLOAT_PRECISION: usize = 6;
+
+/// 10 ^ precision has to fit in a 64 bit integer
+pub const MAX_FLOAT_PRECISION: usize = 18;
+
+/// Options passed on the command line that change the generated code
+#[derive(Debug, Clone)]
+pub struct CompilerOptions {
+    /// Number of digits printed after the decimal point when writing a float
+    pub float_precision: usize,
+    /// Exit at runtime when a cast loses information, i.e. the value doesn't fit in the new type
+    pub checked_casts: bool,
+    /// Exit on a double free and report the heap blocks that were never freed when the program ends
+    pub debug_alloc: bool,
+    /// Write DWARF debug info so gdb can step through the source and print variables
+    pub debug_info: bool,
+    /// Only write output.asm, with the source line above the instructions it compiles to
+    pub emit_asm: bool,
+    /// Which warnings are reported, and whether they fail the compilation
+    pub warnings: WarningOptions,
+}
+
+impl Default for CompilerOptions {
+    fn default() -> Self {
+        Self {
+            float_precision: DEFAULT_FLOAT_PRECISION,
+            checked_casts: false,
+            debug_alloc: false,
+            debug_info: false,
+            emit_asm: false,
+            warnings: WarningOptions::default(),
+        }
+    }
+}
@@ -2,16 +2,22 @@ pub mod parse_assignment;
 pub mod parse_comparison;
 pub mod parse_conditionals;
 pub mod parse_declaration;
+pub mod parse_enum;
 pub mod parse_expression;
 pub mod parse_factors;
 pub mod parse_function_call;
 pub mod parse_function_def;
+pub mod parse_generics;
+pub mod parse_impl;
 pub mod parse_logical_exp;
 pub mod parse_loop;
+pub mod parse_match;
 pub mod parse_memory_alloc;
 pub mod parse_return;
 pub mod parse_struct;
 pub mod parse_term;
+pub mod parse_test;
 pub mod parse_tokens;
 pub mod parse_typedef;
+pub mod parse_union;
 pub mod parser;
@@ -21,6 +21,7 @@ impl Parser {
         array_access_index: Option<ASTNode>,
     ) -> ASTNode {
         let mut member_access = vec![];
+        let mut member_tokens = vec![];
 
         while matches!(self.peek_next_token().token, TokenEnum::Dot) {
             // this is a struct member assignment
@@ -30,9 +31,10 @@ impl Parser {
 
             let next = self.get_next_token();
 
-            match next.token {
+            match &next.token {
                 TokenEnum::Variable(name) => {
-                    member_access.push(name);
+                    member_access.push(name.clone());
+                    member_tokens.push(next.clone());
                 }
 
                 _ => {
@@ -58,6 +60,7 @@ impl Parser {
 
         variable.array_aceess_index = array_access_index;
         variable.member_access = member_access;
+        variable.member_tokens = member_tokens;
 
         return Rc::new(RefCell::new(Box::new(AssignmentStatement::new(
             variable,
@@ -6,6 +6,7 @@ use crate::{
     ast::conditionals::{ConditionalStatement, ElseStatement, IfStatement},
     lexer::{
         keywords::{ELIF_STATEMENT, ELSE_STATEMENT},
+        lexer::Token,
         tokens::{Bracket, TokenEnum},
     },
 };
@@ -14,7 +15,7 @@ use super::parser::Parser;
 
 impl Parser {
     /// CONDITIONAL_STATEMENT -> if LPAREN* COMPARISON_EXPRESSION RPAREN* LCURLY STATEMENT[]* RCURLY ELSE_STATEMENT*
-    pub fn parse_conditional_statement(&mut self) -> ASTNode {
+    pub fn parse_conditional_statement(&mut self, if_token: &Token) -> ASTNode {
         // we get here after 'if' has been consumed
 
         // parse the if statements
@@ -57,6 +58,7 @@ impl Parser {
             if_statement,
             elif_ladder,
             else_statement,
+            if_token.clone(),
         ))));
     }
 
@@ -2,7 +2,7 @@ use crate::{
     helpers::{compiler_error, unexpected_token},
     lexer::{
         lexer::Token,
-        tokens::{Bracket, Number, Operations},
+        tokens::{Bracket, Comparators, Number, Operations},
         types::VarType,
     },
     trace,
@@ -38,6 +38,28 @@ impl Parser {
         }
     }
 
+    /// VAR_NAME (TYPE_ARGUMENTS)*
+    ///
+    /// We get here after the name of the type has been consumed
+    fn parse_user_defined_type(&mut self, token: &Token, var_type_name: &String) -> VarType {
+        if self.is_generic_struct(var_type_name) {
+            return self.parse_generic_struct_instance(token, var_type_name);
+        }
+
+        match self.get_user_defined_type(var_type_name) {
+            Some(var_type) => {
+                self.symbols.refer_to_type(token, var_type_name);
+                var_type
+            }
+
+            None => {
+                // FIXME: This shouldn't be here but in semantic analysis phase
+                compiler_error(format!("No such type '{}'", var_type_name), token);
+                exit(1);
+            }
+        }
+    }
+
     pub fn parse_var_type(&mut self) -> (Token, VarType) {
         let token = self.peek_next_token();
 
@@ -54,20 +76,10 @@ impl Parser {
 
             // This could be a user defined type
             TokenEnum::Variable(var_type_name) => {
-                let var_name_clone = var_type_name.clone();
-
-                let found = self.user_defined_types.iter().find(|var| var.name == *var_name_clone);
-
-                let var_type = if let Some(t) = found {
-                    t.type_.clone()
-                } else {
-                    // FIXME: This shouldn't be here but in semantic analysis phase
-                    compiler_error(format!("No such type '{}'", var_type_name), &token);
-                    exit(1);
-                };
-
                 let type_token = self.get_next_token();
 
+                let var_type = self.parse_user_defined_type(&token, var_type_name);
+
                 let mut actual_var_type = var_type.clone();
 
                 self.check_if_array_type(&mut actual_var_type, &var_type);
@@ -85,16 +97,7 @@ impl Parser {
                 let next_token = self.get_next_token();
 
                 if let TokenEnum::Variable(var_type_name) = &next_token.token {
-                    let var_name_clone = var_type_name.clone();
-
-                    let found = self.user_defined_types.iter().find(|var| var.name == *var_name_clone);
-
-                    if found.is_none() {
-                        compiler_error(format!("No such type '{}'", var_type_name), &token);
-                        exit(1);
-                    }
-
-                    let var_type = found.unwrap().type_.clone();
+                    let var_type = self.parse_user_defined_type(&next_token, var_type_name);
 
                     let mut actual_var_type = var_type.clone();
 
@@ -107,6 +110,16 @@ impl Parser {
                 }
             }
 
+            // []TYPE, a slice
+            TokenEnum::Bracket(Bracket::LSquare) => {
+                self.get_next_token();
+                self.validate_token(TokenEnum::Bracket(Bracket::RSquare));
+
+                let (type_token, var_type) = self.parse_var_type();
+
+                (type_token, VarType::Slice(Box::new(var_type)))
+            }
+
             tok => {
                 unexpected_token(&token, None);
                 exit(1);
@@ -118,19 +131,19 @@ impl Parser {
     pub fn parse_variable(&mut self) -> Variable {
         let token = self.get_next_token();
 
-        match token.token {
+        match &token.token {
             TokenEnum::Variable(var_name) => {
-                let token = self.get_next_token();
+                let colon = self.get_next_token();
 
-                match token.token {
+                match colon.token {
                     // : after variable name, so can only be VAR_NAME: VAR_TYPE
                     TokenEnum::Colon => {
-                        let (token, var_type) = self.parse_var_type();
-                        return Variable::new(Box::new(token), var_type, var_name.into(), false, false, 0);
+                        let (_, var_type) = self.parse_var_type();
+                        return Variable::new(Box::new(token.clone()), var_type, var_name.into(), false, false, 0);
                     }
 
                     _ => {
-                        unexpected_token(&token, Some(&TokenEnum::Colon));
+                        unexpected_token(&colon, Some(&TokenEnum::Colon));
                         exit(1);
                     }
                 }
@@ -143,11 +156,27 @@ impl Parser {
         }
     }
 
-    /// VARIABLE_DECLARATION -> def VAR_NAME: (*)* VAR_TYPE (= ASSIGNED_STATEMENT)*
+    /// VARIABLE_DECLARATION -> def VAR_NAME (: (*)* VAR_TYPE)* (= ASSIGNED_STATEMENT)*
     pub fn parse_declaration_statement(&mut self, is_const: bool) -> ASTNode {
         // we get here after consuming 'def'
 
-        let mut left = self.parse_variable();
+        // def a = 5;
+        // The type is inferred from the RHS during semantic analysis
+        let infer_type = matches!(self.peek_nth_token(2).token, TokenEnum::Equals);
+
+        let mut left = if infer_type {
+            let token = self.get_next_token();
+
+            let TokenEnum::Variable(var_name) = &token.token else {
+                unexpected_token(&token, Some(&TokenEnum::Variable("".into())));
+                exit(1);
+            };
+
+            Variable::new(Box::new(token.clone()), VarType::Unknown, var_name.clone(), false, false, 0)
+        } else {
+            self.parse_variable()
+        };
+
         left.is_const = is_const;
 
         let next_token = self.peek_next_token();
@@ -172,7 +201,8 @@ impl Parser {
 
             // as we could also enter this if condition with the following assignment
             // def a: int = array[4];
-            parse_struct = matches!(next_token, TokenEnum::Bracket(Bracket::LCurly));
+            parse_struct = matches!(next_token, TokenEnum::Bracket(Bracket::LCurly))
+                || (matches!(next_token, TokenEnum::Comparator(Comparators::LessThan)) && self.is_generic_struct(var_peeked));
         }
 
         let right = if parse_struct {
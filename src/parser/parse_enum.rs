@@ -0,0 +1,175 @@
+use std::{cell::RefCell, process::exit, rc::Rc};
+
+use crate::{
+    ast::factor::Factor,
+    helpers::{compiler_error, unexpected_token},
+    lexer::{
+        lexer::Token,
+        tokens::{Bracket, Number, Operations, TokenEnum},
+        types::{EnumVariantType, VarType},
+    },
+    types::ASTNode,
+};
+
+use super::parser::{Parser, UserDefinedType};
+
+impl Parser {
+    /// ENUM_DEFINITION -> enum NAME LCURLY (VARIANT (= (-)* INTEGER)* COMMA*)* RCURLY
+    ///
+    /// We get here after 'enum' has been consumed
+    pub fn parse_enum_definition(&mut self) {
+        let mut name = String::from("");
+
+        let next_token = self.get_next_token();
+
+        if let TokenEnum::Variable(var_name) = &next_token.token {
+            name = var_name.into();
+        } else {
+            unexpected_token(&next_token, Some(&TokenEnum::Variable("".into())));
+        }
+
+        if self.user_defined_types.iter().any(|t| t.name == name) {
+            compiler_error(format!("Type '{name}' is already defined"), &next_token);
+        }
+
+        self.validate_token(TokenEnum::Bracket(Bracket::LCurly));
+
+        let mut variants: Vec<EnumVariantType> = vec![];
+
+        // discriminants start from 0 and are incremented by 1 unless explicitly specified
+        let mut next_discriminant = 0;
+
+        loop {
+            if matches!(self.peek_next_token().token, TokenEnum::Bracket(Bracket::RCurly)) {
+                break;
+            }
+
+            let variant_token = self.get_next_token();
+
+            let variant_name = if let TokenEnum::Variable(variant_name) = &variant_token.token {
+                variant_name.clone()
+            } else {
+                unexpected_token(&variant_token, Some(&TokenEnum::Variable("".into())));
+                exit(1);
+            };
+
+            if variants.iter().any(|v| v.name == variant_name) {
+                compiler_error(
+                    format!("Variant '{variant_name}' is defined multiple times in enum '{name}'"),
+                    &variant_token,
+                );
+            }
+
+            if matches!(self.peek_next_token().token, TokenEnum::Equals) {
+                self.get_next_token();
+                next_discriminant = self.parse_enum_discriminant();
+            }
+
+            if let Some(v) = variants.iter().find(|v| v.discriminant == next_discriminant) {
+                compiler_error(
+                    format!(
+                        "Variant '{variant_name}' has the same value '{next_discriminant}' as variant '{}'",
+                        v.name
+                    ),
+                    &variant_token,
+                );
+            }
+
+            variants.push(EnumVariantType {
+                name: variant_name,
+                discriminant: next_discriminant,
+            });
+
+            next_discriminant += 1;
+
+            if matches!(self.peek_next_token().token, TokenEnum::Comma) {
+                self.get_next_token();
+            }
+        }
+
+        self.validate_token(TokenEnum::Bracket(Bracket::RCurly));
+
+        if variants.is_empty() {
+            compiler_error(format!("Enum '{name}' must have at least one variant"), &next_token);
+        }
+
+        self.user_defined_types.push(UserDefinedType {
+            name: name.clone(),
+            type_: VarType::Enum(name.clone(), variants),
+        });
+
+        self.define_type(&name, &next_token);
+    }
+
+    fn parse_enum_discriminant(&mut self) -> i32 {
+        let mut negative = false;
+
+        if matches!(self.peek_next_token().token, TokenEnum::Op(Operations::Minus)) {
+            self.get_next_token();
+            negative = true;
+        }
+
+        let token = self.get_next_token();
+
+        if let TokenEnum::Number(Number::Integer(int)) = token.token {
+            if negative {
+                -int
+            } else {
+                int
+            }
+        } else {
+            unexpected_token(&token, Some(&TokenEnum::Number(Number::Integer(0))));
+            exit(1);
+        }
+    }
+
+    /// Type parameters of the generic being instantiated shadow all other types
+    pub fn get_user_defined_type(&self, name: &String) -> Option<VarType> {
+        self.type_arguments
+            .iter()
+            .chain(self.user_defined_types.iter())
+            .find(|t| t.name == *name)
+            .map(|t| t.type_.clone())
+    }
+
+    /// ENUM_VARIANT -> ENUM_NAME DOT VARIANT
+    ///
+    /// We get here after ENUM_NAME has been consumed. Variants are constants, so they are
+    /// lowered to an integer factor that carries the enum type
+    pub fn parse_enum_variant(&mut self, enum_token: &Token, enum_type: VarType) -> ASTNode {
+        self.validate_token(TokenEnum::Dot);
+
+        let variant_token = self.get_next_token();
+
+        let variant_name = if let TokenEnum::Variable(variant_name) = &variant_token.token {
+            variant_name.clone()
+        } else {
+            unexpected_token(&variant_token, Some(&TokenEnum::Variable("".into())));
+            exit(1);
+        };
+
+        let discriminant = match &enum_type {
+            VarType::Enum(enum_name, variants) => match variants.iter().find(|v| v.name == variant_name) {
+                Some(variant) => variant.discriminant,
+
+                None => {
+                    compiler_error(
+                        format!("Enum '{enum_name}' has no variant '{variant_name}'"),
+                        &variant_token,
+                    );
+                    exit(1);
+                }
+            },
+
+            _ => unreachable!("parse_enum_variant called with non enum type {enum_type}"),
+        };
+
+        let mut token = enum_token.clone();
+        token.token = TokenEnum::Number(Number::Integer(discriminant));
+
+        let mut factor = Factor::new(Box::new(token));
+        factor.result_type = enum_type;
+
+        return Rc::new(RefCell::new(Box::new(factor)));
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     ast::{
         abstract_syntax_tree::{ASTNodeEnum, ASTNodeEnumMut, AST},
         array::Array,
+        type_cast::TypeCast,
         variable::Variable,
     },
     helpers::unexpected_token,
@@ -16,13 +17,16 @@ use std::{cell::RefCell, process::exit, rc::Rc};
 use crate::{
     ast::factor::Factor,
     constants, helpers,
-    lexer::tokens::{Bracket, Number, TokenEnum},
+    lexer::tokens::{Bracket, Comparators, Number, TokenEnum},
 };
 
 use super::parser::Parser;
 
 impl Parser {
-    /// VARIABLE (as type)*
+    /// VARIABLE (as *type)*
+    ///
+    /// Casting a variable to a pointer reinterprets it, so it's kept on the variable. Numeric casts
+    /// are handled by parse_type_cast
     fn parse_variable_factor(&mut self, var_token: &Token, var_name: &String) -> ASTNode {
         let mut variable = Variable::new(
             Box::new(var_token.clone()),
@@ -34,7 +38,7 @@ impl Parser {
         );
 
         if let TokenEnum::Keyword(word) = self.peek_next_token().token {
-            if word == AS {
+            if word == AS && matches!(self.peek_nth_token(2).token, TokenEnum::Type(VarType::Ptr(..))) {
                 // consume 'as'
                 self.get_next_token();
 
@@ -51,37 +55,84 @@ impl Parser {
             }
         }
 
-        // check if this is an array access
+        // check if this is an array access or a slice
         if let TokenEnum::Bracket(Bracket::LSquare) = self.peek_next_token().token {
             self.get_next_token();
 
             variable.array_aceess_index = Some(self.parse_logical_expression());
 
+            // string[start..end]
+            if let TokenEnum::DotDot = self.peek_next_token().token {
+                self.get_next_token();
+                variable.slice_end = Some(self.parse_logical_expression());
+            }
+
             self.validate_token(TokenEnum::Bracket(Bracket::RSquare));
         }
 
         let mut member_access = vec![];
+        let mut member_tokens = vec![];
 
         while let TokenEnum::Dot = self.peek_next_token().token {
             self.get_next_token();
 
             let next_token = self.peek_next_token();
 
-            if let TokenEnum::Variable(member_name) = next_token.token {
+            if let TokenEnum::Variable(member_name) = &next_token.token {
                 self.get_next_token();
-                member_access.push(member_name);
+
+                // point.len()
+                if let TokenEnum::Bracket(Bracket::LParen) = self.peek_next_token().token {
+                    variable.member_access = member_access;
+                    variable.member_tokens = member_tokens;
+                    return self.parse_method_call(variable, &next_token, member_name.clone(), true);
+                }
+
+                member_access.push(member_name.clone());
+                member_tokens.push(next_token.clone());
             } else {
                 unexpected_token(&next_token, Some(&TokenEnum::Variable("".into())))
             }
         }
 
         variable.member_access = member_access;
+        variable.member_tokens = member_tokens;
 
         return Rc::new(RefCell::new(Box::new(variable)));
     }
 
-    /// FACTOR -> (*|&)* INTEGER | FLOAT | VARIABLE (as type)* | STRING_LITERAL | LPAREN EXPRESSION RPAREN | FUNCTION_CALL
+    /// FACTOR (as VAR_TYPE)*
+    fn parse_type_cast(&mut self, factor: ASTNode) -> ASTNode {
+        let mut result = factor;
+
+        while let TokenEnum::Keyword(word) = self.peek_next_token().token {
+            if word != AS {
+                break;
+            }
+
+            let as_token = self.get_next_token();
+
+            let type_token = self.get_next_token();
+
+            let TokenEnum::Type(to_type) = type_token.token else {
+                unexpected_token(&type_token, Some(&TokenEnum::Type(VarType::Unknown)));
+                exit(1);
+            };
+
+            result = Rc::new(RefCell::new(Box::new(TypeCast::new(result, to_type, as_token))));
+        }
+
+        result
+    }
+
+    /// FACTOR -> (*|&)* INTEGER | FLOAT | VARIABLE (as *type)* | STRING_LITERAL | LPAREN EXPRESSION RPAREN | FUNCTION_CALL
     pub fn parse_factor(&mut self) -> ASTNode {
+        let factor = self.parse_factor_without_cast();
+
+        self.parse_type_cast(factor)
+    }
+
+    fn parse_factor_without_cast(&mut self) -> ASTNode {
         let next_token = self.peek_next_token();
 
         match &next_token.token {
@@ -111,9 +162,30 @@ impl Parser {
                         // This will only be called from a declaration statement or an
                         // assignment_statement which means this is assigned to a value.
                         // simple function call without assignment is handled in parse statements
-                        self.parse_function_call(var_name.into(), true)
+                        self.parse_function_call(&var_token, var_name.into(), true)
+                    }
+
+                    // max<int>(a, b)
+                    TokenEnum::Comparator(Comparators::LessThan) if self.is_generic_function(var_name) => {
+                        let instance = self.parse_generic_function_instance(&var_token, var_name);
+                        self.parse_function_call(&var_token, instance, true)
                     }
 
+                    // Method.GET or Result.Ok(value)
+                    TokenEnum::Dot => match self.get_user_defined_type(var_name) {
+                        Some(enum_type @ VarType::Enum(..)) => {
+                            self.symbols.refer_to_type(&var_token, var_name);
+                            self.parse_enum_variant(&var_token, enum_type)
+                        }
+
+                        Some(VarType::Union(..)) => {
+                            self.symbols.refer_to_type(&var_token, var_name);
+                            self.parse_union_variant(&var_token, var_name)
+                        }
+
+                        _ => self.parse_variable_factor(&var_token, var_name),
+                    },
+
                     _ => self.parse_variable_factor(&var_token, var_name),
                 }
             }
@@ -172,6 +244,12 @@ impl Parser {
 
                     let mut members = vec![];
 
+                    // Empty arrays are an error, but that's reported during semantic analysis
+                    if let TokenEnum::Bracket(Bracket::RSquare) = self.peek_next_token().token {
+                        self.get_next_token();
+                        return Rc::new(RefCell::new(Box::new(Array::new(members, bracket_token))));
+                    }
+
                     loop {
                         members.push(self.parse_logical_expression());
 
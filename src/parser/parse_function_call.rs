@@ -4,14 +4,32 @@ use std::{cell::RefCell, process::exit, rc::Rc};
 
 use crate::{
     ast::function_call::FunctionCall,
-    lexer::tokens::{Bracket, TokenEnum},
+    lexer::{
+        lexer::Token,
+        tokens::{Bracket, TokenEnum},
+    },
 };
 
 use super::parser::Parser;
 
 impl Parser {
     /// FUNCTION_CALL -> VAR_NAME LPAREN (COMPARISON_EXPRESSION)* RPAREN
-    pub fn parse_function_call(&mut self, name: String, is_assigned: bool) -> ASTNode {
+    pub fn parse_function_call(&mut self, name_token: &Token, name: String, is_assigned: bool) -> ASTNode {
+        let (tok, arguments) = self.parse_function_call_arguments();
+
+        return Rc::new(RefCell::new(Box::new(FunctionCall::new(
+            name,
+            name_token.clone(),
+            tok,
+            arguments,
+            is_assigned,
+        ))));
+    }
+
+    /// LPAREN (COMPARISON_EXPRESSION)* RPAREN
+    ///
+    /// Returns the LPAREN token along with the arguments
+    pub fn parse_function_call_arguments(&mut self) -> (Token, Vec<ASTNode>) {
         // We parse from the LPAREN
         // consume the LPAREN
         let tok = self.get_next_token();
@@ -64,11 +82,6 @@ impl Parser {
             };
         }
 
-        return Rc::new(RefCell::new(Box::new(FunctionCall::new(
-            name,
-            tok,
-            arguments,
-            is_assigned,
-        ))));
+        return (tok, arguments);
     }
 }
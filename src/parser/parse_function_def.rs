@@ -1,6 +1,10 @@
 use crate::{
-    ast::void::Void, helpers::unexpected_token, interpreter::interpreter::FunctionHashMapValue, lexer::types::VarType,
-    trace, types::ASTNode,
+    ast::void::Void,
+    helpers::{compiler_error, unexpected_token},
+    interpreter::interpreter::FunctionHashMapValue,
+    lexer::{keywords::BUILTIN_FUNCTIONS, lexer::Token, tokens::Comparators, types::VarType},
+    trace,
+    types::ASTNode,
 };
 
 use std::{cell::RefCell, process::exit, rc::Rc};
@@ -37,7 +41,8 @@ impl Parser {
                 }
 
                 _ => {
-                    let variable = self.parse_variable();
+                    let mut variable = self.parse_variable();
+                    variable.is_parameter = true;
                     parameters.push(Rc::new(RefCell::new(variable)));
                 }
             };
@@ -46,13 +51,13 @@ impl Parser {
         return parameters;
     }
 
-    /// FUNCTION_DEF -> fun VAR_NAME LPAREN (VAR_NAME : VAR_TYPE)* RPAREN (-> VarType)* LCURLY (STATEMENT[] - FUNCTION_DEF) RCURLY
+    /// FUNCTION_DEF -> fun VAR_NAME (TYPE_PARAMETERS)* LPAREN (VAR_NAME : VAR_TYPE)* RPAREN (-> VarType)* LCURLY (STATEMENT[] - FUNCTION_DEF) RCURLY
     pub fn parse_function_definition(&mut self, f: ParserFunctions, is_extern_function_definition: bool) -> ASTNode {
         // we get here after consuming 'fun'
         let func_name_token = self.get_next_token();
 
         let function_name = match &func_name_token.token {
-            TokenEnum::Variable(n) => n,
+            TokenEnum::Variable(n) => n.clone(),
 
             _ => {
                 unexpected_token(&func_name_token, None);
@@ -60,6 +65,33 @@ impl Parser {
             }
         };
 
+        if let TokenEnum::Comparator(Comparators::LessThan) = self.peek_next_token().token {
+            if is_extern_function_definition {
+                compiler_error("Extern functions cannot be generic", &func_name_token);
+            }
+
+            return self.parse_generic_function_definition(&function_name, &func_name_token);
+        }
+
+        // generic functions are called with their type arguments, so they can't be confused with these
+        if BUILTIN_FUNCTIONS.contains(&function_name.as_str()) {
+            compiler_error(
+                format!("'{function_name}' is a builtin function and can't be redefined"),
+                &func_name_token,
+            );
+        }
+
+        return self.parse_function(function_name, func_name_token, f, is_extern_function_definition);
+    }
+
+    /// Everything in FUNCTION_DEF after the name and type parameters
+    pub fn parse_function(
+        &mut self,
+        function_name: String,
+        func_name_token: Token,
+        f: ParserFunctions,
+        is_extern_function_definition: bool,
+    ) -> ASTNode {
         self.current_function_being_parsed = Some(function_name.clone());
 
         self.validate_token(TokenEnum::Bracket(Bracket::LParen));
@@ -0,0 +1,402 @@
+use std::{cell::RefCell, process::exit, rc::Rc};
+
+use crate::{
+    ast::generic_function::GenericFunction,
+    helpers::{compiler_error, unexpected_token},
+    lexer::{
+        keywords::FUNCTION_DEFINE,
+        lexer::{Lexer, Token},
+        tokens::{Bracket, Comparators, TokenEnum},
+        types::{
+            VarType, TYPE_CHAR, TYPE_FLOAT, TYPE_FLOAT32, TYPE_INT, TYPE_INT16, TYPE_INT32, TYPE_INT8,
+            TYPE_OWNED_STRING, TYPE_STRING,
+        },
+    },
+    types::ASTNode,
+};
+
+use super::parser::{Parser, ParserFunctions, UserDefinedType};
+
+/// A generic function or struct. Templates are not type checked, only their instances are
+#[derive(Debug)]
+pub struct GenericTemplate {
+    pub name: String,
+    type_parameters: Vec<String>,
+    token: Token,
+    /// Positioned right after the type parameters. Every instance is parsed again from here
+    lexer: Lexer,
+    /// Names of all the instances that have been, or are being, parsed
+    instance_names: Vec<String>,
+    /// Function definitions of the instances. Always empty for structs
+    instances: Rc<RefCell<Vec<ASTNode>>>,
+}
+
+fn mangle_type(var_type: &VarType) -> String {
+    match var_type {
+        VarType::Int => TYPE_INT.into(),
+        VarType::Int8 => TYPE_INT8.into(),
+        VarType::Int16 => TYPE_INT16.into(),
+        VarType::Int32 => TYPE_INT32.into(),
+        VarType::Float => TYPE_FLOAT.into(),
+        VarType::Float32 => TYPE_FLOAT32.into(),
+        VarType::Str => TYPE_STRING.into(),
+        VarType::OwnedStr => TYPE_OWNED_STRING.into(),
+        VarType::Char => TYPE_CHAR.into(),
+        VarType::Ptr(inner) => format!("ptr_{}", mangle_type(inner)),
+        VarType::Array(inner, size) => format!("arr{size}_{}", mangle_type(inner)),
+        VarType::Slice(inner) => format!("slice_{}", mangle_type(inner)),
+
+        VarType::Struct(name, _) | VarType::Enum(name, _) | VarType::Union(name, _) | VarType::Function(name, _, _) => {
+            name.into()
+        }
+
+        VarType::Unknown => unreachable!("Type argument with unknown type"),
+    }
+}
+
+/// max<int, *str> -> max$int$ptr_str
+fn instance_name(name: &str, type_arguments: &[VarType]) -> String {
+    let mangled: Vec<String> = type_arguments.iter().map(mangle_type).collect();
+
+    return format!("{name}${}", mangled.join("$"));
+}
+
+impl Parser {
+    pub fn is_generic_function(&self, name: &String) -> bool {
+        self.generic_functions.iter().any(|template| template.name == *name)
+    }
+
+    pub fn is_generic_struct(&self, name: &String) -> bool {
+        self.generic_structs.iter().any(|template| template.name == *name)
+    }
+
+    /// TYPE_PARAMETERS -> < VAR_NAME (, VAR_NAME)* >
+    fn parse_type_parameters(&mut self, name_token: &Token) -> Vec<String> {
+        self.validate_token(TokenEnum::Comparator(Comparators::LessThan));
+
+        let mut type_parameters: Vec<String> = vec![];
+
+        loop {
+            let token = self.get_next_token();
+
+            match &token.token {
+                TokenEnum::Comparator(Comparators::GreaterThan) => break,
+
+                TokenEnum::Comma => continue,
+
+                TokenEnum::Variable(name) => {
+                    if type_parameters.contains(name) {
+                        compiler_error(format!("Type parameter '{name}' defined more than once"), &token);
+                    }
+
+                    type_parameters.push(name.clone());
+                }
+
+                _ => {
+                    unexpected_token(&token, Some(&TokenEnum::Variable("".into())));
+                    exit(1);
+                }
+            }
+        }
+
+        if type_parameters.is_empty() {
+            compiler_error("Expected at least one type parameter", name_token);
+        }
+
+        return type_parameters;
+    }
+
+    /// TYPE_ARGUMENTS -> < VAR_TYPE (, VAR_TYPE)* >
+    fn parse_type_arguments(&mut self, name: &String, type_parameters: &[String], name_token: &Token) -> Vec<VarType> {
+        self.validate_token(TokenEnum::Comparator(Comparators::LessThan));
+
+        let mut type_arguments = vec![];
+
+        loop {
+            match self.peek_next_token().token {
+                TokenEnum::Comparator(Comparators::GreaterThan) => {
+                    self.get_next_token();
+                    break;
+                }
+
+                TokenEnum::Comma => {
+                    self.get_next_token();
+                }
+
+                _ => type_arguments.push(self.parse_var_type().1),
+            }
+        }
+
+        if type_arguments.len() != type_parameters.len() {
+            compiler_error(
+                format!(
+                    "'{name}' takes {} type argument(s) but {} were given",
+                    type_parameters.len(),
+                    type_arguments.len()
+                ),
+                name_token,
+            );
+        }
+
+        return type_arguments;
+    }
+
+    /// Skips everything up to and including the closing curly bracket of the template's body, as the
+    /// body can only be parsed once the type parameters are known
+    fn skip_template_body(&mut self) {
+        let mut depth = 0;
+
+        loop {
+            let token = self.get_next_token();
+
+            match token.token {
+                TokenEnum::Bracket(Bracket::LCurly) => depth += 1,
+
+                TokenEnum::Bracket(Bracket::RCurly) => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        break;
+                    }
+                }
+
+                TokenEnum::EOF => {
+                    unexpected_token(&token, Some(&TokenEnum::Bracket(Bracket::RCurly)));
+                    exit(1);
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_template(&mut self, name: &str, name_token: &Token) -> GenericTemplate {
+        let type_parameters = self.parse_type_parameters(name_token);
+
+        let lexer = self.lexer.borrow().as_ref().clone();
+
+        self.skip_template_body();
+
+        return GenericTemplate {
+            name: name.into(),
+            type_parameters,
+            token: name_token.clone(),
+            lexer,
+            instance_names: vec![],
+            instances: Rc::new(RefCell::new(vec![])),
+        };
+    }
+
+    /// Makes the templates of the generic functions defined in the file being parsed, so they can
+    /// be called before their definition. Reads the file from a copy of the lexer
+    pub fn find_generic_functions(&mut self) {
+        let lexer = self.lexer.borrow().as_ref().clone();
+        let file_lexer = std::mem::replace(&mut self.lexer, Rc::new(RefCell::new(Box::new(lexer))));
+
+        let mut depth = 0;
+
+        loop {
+            let token = self.get_next_token();
+
+            match &token.token {
+                TokenEnum::Bracket(Bracket::LCurly) => depth += 1,
+                TokenEnum::Bracket(Bracket::RCurly) => depth -= 1,
+
+                TokenEnum::Keyword(keyword) if keyword == FUNCTION_DEFINE && depth == 0 => {
+                    let name_token = self.get_next_token();
+
+                    if let TokenEnum::Variable(name) = &name_token.token {
+                        if let TokenEnum::Comparator(Comparators::LessThan) = self.peek_next_token().token {
+                            let template = self.parse_template(name, &name_token);
+                            self.generic_functions.push(template);
+                        }
+                    }
+                }
+
+                TokenEnum::EOF => break,
+
+                _ => {}
+            }
+        }
+
+        self.lexer = file_lexer;
+    }
+
+    /// FUNCTION_DEF -> fun VAR_NAME TYPE_PARAMETERS LPAREN (VAR_NAME : VAR_TYPE)* RPAREN (-> VarType)* LCURLY (STATEMENT[] - FUNCTION_DEF) RCURLY
+    ///
+    /// We get here after the function name has been consumed
+    pub fn parse_generic_function_definition(&mut self, name: &str, name_token: &Token) -> ASTNode {
+        let found = self
+            .generic_functions
+            .iter()
+            .find(|t| t.token.start == name_token.start && t.token.file == name_token.file);
+
+        // `find_generic_functions` already made the template, only its body needs skipping
+        let instances = match found {
+            Some(template) => {
+                let instances = Rc::clone(&template.instances);
+
+                self.parse_type_parameters(name_token);
+                self.skip_template_body();
+
+                instances
+            }
+
+            None => {
+                let template = self.parse_template(name, name_token);
+                let instances = Rc::clone(&template.instances);
+
+                self.generic_functions.push(template);
+
+                instances
+            }
+        };
+
+        return Rc::new(RefCell::new(Box::new(GenericFunction::new(
+            name.into(),
+            instances,
+            name_token.clone(),
+        ))));
+    }
+
+    /// STRUCT -> struct VAR_NAME TYPE_PARAMETERS LCURLY (VAR_NAME : VAR_TYPE,)* RCURLY
+    ///
+    /// We get here after the struct name has been consumed
+    pub fn parse_generic_struct_definition(&mut self, name: &str, name_token: &Token) {
+        let template = self.parse_template(name, name_token);
+
+        self.generic_structs.push(template);
+    }
+
+    /// Parses the template again from its lexer with the type parameters bound to `type_arguments`.
+    /// Any state that describes where we currently are in the file is saved, and restored afterwards
+    fn parse_template_instance<T>(
+        &mut self,
+        lexer: Lexer,
+        type_parameters: &[String],
+        type_arguments: Vec<VarType>,
+        parse: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let bindings = type_parameters
+            .iter()
+            .zip(type_arguments)
+            .map(|(name, type_)| UserDefinedType {
+                name: name.clone(),
+                type_,
+            })
+            .collect();
+
+        let current_lexer = std::mem::replace(&mut self.lexer, Rc::new(RefCell::new(Box::new(lexer))));
+        let current_type_arguments = std::mem::replace(&mut self.type_arguments, bindings);
+        let current_bracket_stack = std::mem::take(&mut self.bracket_stack);
+        let current_function = self.current_function_being_parsed.take();
+
+        let depths = (
+            self.inside_loop_depth,
+            self.inside_function_depth,
+            self.inside_if_else_depth,
+        );
+        let current_loop_number = self.inside_current_loop_number;
+
+        (
+            self.inside_loop_depth,
+            self.inside_function_depth,
+            self.inside_if_else_depth,
+        ) = (0, 0, 0);
+        self.inside_current_loop_number = -1;
+
+        let result = parse(self);
+
+        self.lexer = current_lexer;
+        self.type_arguments = current_type_arguments;
+        self.bracket_stack = current_bracket_stack;
+        self.current_function_being_parsed = current_function;
+
+        (
+            self.inside_loop_depth,
+            self.inside_function_depth,
+            self.inside_if_else_depth,
+        ) = depths;
+        self.inside_current_loop_number = current_loop_number;
+
+        return result;
+    }
+
+    /// VAR_NAME TYPE_ARGUMENTS
+    ///
+    /// We get here after the function name has been consumed. Returns the name of the instance
+    /// which is what ends up in the functions map
+    pub fn parse_generic_function_instance(&mut self, name_token: &Token, name: &String) -> String {
+        let index = self.generic_functions.iter().position(|t| t.name == *name).unwrap();
+
+        let template = &self.generic_functions[index];
+        let lexer = template.lexer.clone();
+        let type_parameters = template.type_parameters.clone();
+        let template_token = template.token.clone();
+
+        let type_arguments = self.parse_type_arguments(name, &type_parameters, name_token);
+
+        let peeked = self.peek_next_token();
+
+        if !matches!(peeked.token, TokenEnum::Bracket(Bracket::LParen)) {
+            unexpected_token(&peeked, Some(&TokenEnum::Bracket(Bracket::LParen)));
+        }
+
+        let instance = instance_name(name, &type_arguments);
+
+        // Also true while the instance itself is being parsed, i.e. for recursive calls
+        if self.generic_functions[index].instance_names.contains(&instance) {
+            return instance;
+        }
+
+        self.generic_functions[index].instance_names.push(instance.clone());
+
+        let functions: ParserFunctions = Rc::clone(&self.functions);
+
+        let function = self.parse_template_instance(lexer, &type_parameters, type_arguments, |parser| {
+            parser.parse_function(instance.clone(), template_token, functions, false)
+        });
+
+        self.generic_functions[index].instances.borrow_mut().push(function);
+
+        return instance;
+    }
+
+    /// VAR_NAME TYPE_ARGUMENTS
+    ///
+    /// We get here after the struct name has been consumed
+    pub fn parse_generic_struct_instance(&mut self, name_token: &Token, name: &String) -> VarType {
+        let index = self.generic_structs.iter().position(|t| t.name == *name).unwrap();
+
+        if !matches!(
+            self.peek_next_token().token,
+            TokenEnum::Comparator(Comparators::LessThan)
+        ) {
+            compiler_error(format!("Generic struct '{name}' needs type arguments"), name_token);
+        }
+
+        let template = &self.generic_structs[index];
+        let lexer = template.lexer.clone();
+        let type_parameters = template.type_parameters.clone();
+
+        let type_arguments = self.parse_type_arguments(name, &type_parameters, name_token);
+
+        let instance = instance_name(name, &type_arguments);
+
+        if let Some(struct_type) = self.user_defined_types.iter().find(|t| t.name == instance) {
+            return struct_type.type_.clone();
+        }
+
+        if self.generic_structs[index].instance_names.contains(&instance) {
+            compiler_error(format!("Generic struct '{name}' cannot contain itself"), name_token);
+        }
+
+        self.generic_structs[index].instance_names.push(instance.clone());
+
+        self.parse_template_instance(lexer, &type_parameters, type_arguments, |parser| {
+            parser.parse_struct_members(instance.clone())
+        });
+
+        return self.get_user_defined_type(&instance).unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+use std::{cell::RefCell, process::exit, rc::Rc};
+
+use crate::{
+    ast::{abstract_syntax_tree::ASTNodeEnum, function_call::FunctionCall, program::Program, variable::Variable},
+    helpers::{compiler_error, unexpected_token, unexpected_token_string},
+    lexer::{
+        keywords::FUNCTION_DEFINE,
+        lexer::Token,
+        tokens::{Bracket, Comparators, TokenEnum},
+        types::VarType,
+    },
+    types::ASTNode,
+};
+
+use super::parser::Parser;
+
+pub const METHOD_RECEIVER: &str = "self";
+
+/// Methods are regular functions with the struct name prepended, so methods with the same name on
+/// different structs don't collide. Point.len -> Point$len
+pub fn get_method_name(struct_name: &String, method_name: &String) -> String {
+    return format!("{struct_name}${method_name}");
+}
+
+impl Parser {
+    /// IMPL -> impl VAR_NAME LCURLY (FUNCTION_DEF)* RCURLY
+    ///
+    /// We get here after 'impl' has been consumed
+    pub fn parse_impl_block(&mut self) -> ASTNode {
+        let name_token = self.get_next_token();
+
+        let struct_name = match &name_token.token {
+            TokenEnum::Variable(name) => name.clone(),
+
+            _ => {
+                unexpected_token(&name_token, Some(&TokenEnum::Variable("".into())));
+                exit(1);
+            }
+        };
+
+        let struct_type = match self.get_user_defined_type(&struct_name) {
+            Some(struct_type @ VarType::Struct(..)) => struct_type,
+
+            _ => {
+                compiler_error(format!("'{struct_name}' is not a struct"), &name_token);
+                exit(1);
+            }
+        };
+
+        self.validate_token(TokenEnum::Bracket(Bracket::LCurly));
+
+        let mut methods = vec![];
+
+        loop {
+            let token = self.get_next_token();
+
+            match &token.token {
+                TokenEnum::Bracket(Bracket::RCurly) => break,
+
+                TokenEnum::SemiColon | TokenEnum::Comment(_) => continue,
+
+                TokenEnum::Keyword(keyword) if keyword == FUNCTION_DEFINE => {
+                    methods.push(self.parse_method_definition(&struct_name, &struct_type));
+                }
+
+                _ => {
+                    unexpected_token_string(&token, "'fun' or '}'");
+                    exit(1);
+                }
+            }
+        }
+
+        return Rc::new(RefCell::new(Box::new(Program::new(methods))));
+    }
+
+    /// We get here after 'fun' has been consumed
+    fn parse_method_definition(&mut self, struct_name: &String, struct_type: &VarType) -> ASTNode {
+        let name_token = self.get_next_token();
+
+        let method_name = match &name_token.token {
+            TokenEnum::Variable(name) => name.clone(),
+
+            _ => {
+                unexpected_token(&name_token, None);
+                exit(1);
+            }
+        };
+
+        if let TokenEnum::Comparator(Comparators::LessThan) = self.peek_next_token().token {
+            compiler_error("Methods cannot be generic", &name_token);
+        }
+
+        let method = self.parse_function(
+            get_method_name(struct_name, &method_name),
+            name_token.clone(),
+            Rc::clone(&self.functions),
+            false,
+        );
+
+        if let ASTNodeEnum::FunctionDef(function_def) = method.borrow().get_node() {
+            let has_receiver = match function_def.parameters.first() {
+                Some(param) => {
+                    let param = param.borrow();
+                    param.var_name == METHOD_RECEIVER && param.var_type == VarType::Ptr(Box::new(struct_type.clone()))
+                }
+
+                None => false,
+            };
+
+            if !has_receiver {
+                compiler_error(
+                    format!(
+                        "The first parameter of method '{method_name}' has to be '{METHOD_RECEIVER}: *{struct_name}'"
+                    ),
+                    &name_token,
+                );
+            }
+        }
+
+        return method;
+    }
+
+    /// Whether the tokens starting at the next one are VAR_NAME (DOT VAR_NAME)+ LPAREN
+    pub fn is_method_call(&mut self) -> bool {
+        let mut n = 2;
+
+        loop {
+            if !matches!(self.peek_nth_token(n).token, TokenEnum::Dot) {
+                return false;
+            }
+
+            if !matches!(self.peek_nth_token(n + 1).token, TokenEnum::Variable(..)) {
+                return false;
+            }
+
+            if matches!(self.peek_nth_token(n + 2).token, TokenEnum::Bracket(Bracket::LParen)) {
+                return true;
+            }
+
+            n += 2;
+        }
+    }
+
+    /// METHOD_CALL -> VAR_NAME (DOT VAR_NAME)* DOT VAR_NAME LPAREN (COMPARISON_EXPRESSION)* RPAREN
+    ///
+    /// We get here after the receiver's name has been consumed
+    pub fn parse_method_call_statement(&mut self, var_token: &Token, var_name: &String) -> ASTNode {
+        let mut receiver = Variable::new(
+            Box::new(var_token.clone()),
+            VarType::Unknown,
+            var_name.into(),
+            false,
+            false,
+            0,
+        );
+
+        loop {
+            self.validate_token(TokenEnum::Dot);
+
+            let name_token = self.get_next_token();
+
+            let TokenEnum::Variable(name) = &name_token.token else {
+                unexpected_token(&name_token, Some(&TokenEnum::Variable("".into())));
+                exit(1);
+            };
+
+            if let TokenEnum::Bracket(Bracket::LParen) = self.peek_next_token().token {
+                return self.parse_method_call(receiver, &name_token, name.clone(), false);
+            }
+
+            receiver.member_access.push(name.clone());
+            receiver.member_tokens.push(name_token.clone());
+        }
+    }
+
+    /// The struct the method belongs to is only known once the receiver's type is, so the call is
+    /// resolved during semantic analysis
+    pub fn parse_method_call(
+        &mut self,
+        receiver: Variable,
+        name_token: &Token,
+        method_name: String,
+        is_assigned: bool,
+    ) -> ASTNode {
+        let (tok, arguments) = self.parse_function_call_arguments();
+
+        if !receiver.member_access.is_empty() {
+            compiler_error(
+                format!(
+                    "Method '{method_name}' can only be called on a variable, not on '{}.{}'",
+                    receiver.var_name,
+                    receiver.member_access.join(".")
+                ),
+                &tok,
+            );
+        }
+
+        return Rc::new(RefCell::new(Box::new(FunctionCall::new_method_call(
+            method_name,
+            name_token.clone(),
+            tok,
+            Rc::new(RefCell::new(Box::new(receiver))),
+            arguments,
+            is_assigned,
+        ))));
+    }
+}
@@ -24,7 +24,7 @@ use super::parser::Parser;
 
 impl Parser {
     /// LOOP -> loop (from LPAREN* EXPRESSION to EXPRESSION (step EXPRESSION)* RPAREN* (with VAR_NAME)*)* LCURLY STATEMENT[] RCURLY
-    pub fn parse_loop(&mut self) -> ASTNode {
+    pub fn parse_loop(&mut self, loop_token: &Token) -> ASTNode {
         // we get here after consuming the 'loop' keyword
         if self.inside_function_depth == 0 {
             compiler_error("Loop cannot be outside a function", &self.peek_next_token());
@@ -55,6 +55,7 @@ impl Parser {
                 None,
                 block,
                 current_loop_number,
+                loop_token.clone(),
             ))));
         };
 
@@ -92,6 +93,7 @@ impl Parser {
             token: TokenEnum::Number(Number::Integer(1)),
             line_number: 0,
             index: 0,
+            start: 0,
             col_number: 0,
             file: "".into(),
         })))));
@@ -174,6 +176,7 @@ impl Parser {
             with_var,
             block,
             current_loop_number,
+            loop_token.clone(),
         ))));
     }
 }
@@ -0,0 +1,128 @@
+use std::{cell::RefCell, process::exit, rc::Rc};
+
+use crate::{
+    ast::match_statement::{MatchArm, MatchPattern, MatchStatement},
+    helpers::{unexpected_token, unexpected_token_string},
+    lexer::{
+        lexer::Token,
+        tokens::{Bracket, Number, Operations, TokenEnum},
+        types::VarType,
+    },
+    types::ASTNode,
+};
+
+use super::parser::Parser;
+
+impl Parser {
+    /// MATCH_STATEMENT -> match LOGICAL_EXPRESSION LCURLY (MATCH_PATTERN LCURLY STATEMENT[]* RCURLY)* RCURLY
+    ///
+    /// We get here after 'match' has been consumed
+    pub fn parse_match_statement(&mut self, match_token: &Token) -> ASTNode {
+        let scrutinee = self.parse_logical_expression();
+
+        self.validate_token(TokenEnum::Bracket(Bracket::LCurly));
+
+        let mut arms = vec![];
+
+        loop {
+            if matches!(self.peek_next_token().token, TokenEnum::Bracket(Bracket::RCurly)) {
+                break;
+            }
+
+            let (pattern, token) = self.parse_match_pattern();
+
+            self.validate_token(TokenEnum::Bracket(Bracket::LCurly));
+
+            self.inside_if_else_depth += 1;
+            let block = self.parse_program();
+            self.inside_if_else_depth -= 1;
+
+            self.validate_token(TokenEnum::Bracket(Bracket::RCurly));
+
+            arms.push(MatchArm::new(pattern, token, block));
+        }
+
+        self.validate_token(TokenEnum::Bracket(Bracket::RCurly));
+
+        return Rc::new(RefCell::new(Box::new(MatchStatement::new(
+            scrutinee,
+            arms,
+            match_token.clone(),
+        ))));
+    }
+
+    /// (LPAREN VAR_NAME RPAREN)*
+    ///
+    /// The variable the payload of a union variant is bound to. `_` ignores the payload
+    fn parse_match_binding(&mut self) -> Option<String> {
+        if !matches!(self.peek_next_token().token, TokenEnum::Bracket(Bracket::LParen)) {
+            return None;
+        }
+
+        self.get_next_token();
+
+        let binding_token = self.get_next_token();
+
+        let binding = match &binding_token.token {
+            TokenEnum::Variable(name) if name == "_" => None,
+            TokenEnum::Variable(name) => Some(name.clone()),
+
+            _ => {
+                unexpected_token(&binding_token, Some(&TokenEnum::Variable("".into())));
+                exit(1);
+            }
+        };
+
+        self.validate_token(TokenEnum::Bracket(Bracket::RParen));
+
+        return binding;
+    }
+
+    /// MATCH_PATTERN -> _ | (-)* INTEGER | ENUM_NAME DOT VARIANT | UNION_NAME DOT VARIANT (LPAREN VAR_NAME RPAREN)*
+    fn parse_match_pattern(&mut self) -> (MatchPattern, Token) {
+        let token = self.get_next_token();
+
+        let pattern = match &token.token {
+            TokenEnum::Variable(name) if name == "_" => MatchPattern::Wildcard,
+
+            TokenEnum::Variable(enum_name) => {
+                self.validate_token(TokenEnum::Dot);
+
+                let variant_token = self.get_next_token();
+
+                let variant_name = if let TokenEnum::Variable(variant_name) = &variant_token.token {
+                    variant_name.clone()
+                } else {
+                    unexpected_token(&variant_token, Some(&TokenEnum::Variable("".into())));
+                    exit(1);
+                };
+
+                if let Some(VarType::Union(..)) = self.get_user_defined_type(enum_name) {
+                    MatchPattern::UnionVariant(enum_name.clone(), variant_name, self.parse_match_binding())
+                } else {
+                    MatchPattern::EnumVariant(enum_name.clone(), variant_name)
+                }
+            }
+
+            TokenEnum::Number(Number::Integer(int)) => MatchPattern::Integer(*int),
+
+            TokenEnum::Op(Operations::Minus) => {
+                let int_token = self.get_next_token();
+
+                if let TokenEnum::Number(Number::Integer(int)) = int_token.token {
+                    MatchPattern::Integer(-int)
+                } else {
+                    unexpected_token(&int_token, Some(&TokenEnum::Number(Number::Integer(0))));
+                    exit(1);
+                }
+            }
+
+            _ => {
+                unexpected_token_string(&token, "'_', an integer or an enum variant");
+                exit(1);
+            }
+        };
+
+        return (pattern, token);
+    }
+}
@@ -2,12 +2,13 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     ast::{
+        abstract_syntax_tree::AST,
         structs::{StructDecleration, StructMember},
         variable::Variable,
     },
-    helpers::{unexpected_token, unexpected_token_string},
+    helpers::{compiler_error, unexpected_token, unexpected_token_string},
     lexer::{
-        tokens::{Bracket, TokenEnum},
+        tokens::{Bracket, Comparators, TokenEnum},
         types::{StructMemberType, VarType},
     },
     trace,
@@ -22,12 +23,23 @@ impl Parser {
 
         let next_token = self.get_next_token();
 
-        if let TokenEnum::Variable(var_name) = next_token.token {
-            name = var_name;
+        if let TokenEnum::Variable(ref var_name) = next_token.token {
+            name = var_name.into();
         } else {
             unexpected_token(&next_token, Some(&TokenEnum::Variable("".into())));
         }
 
+        if let TokenEnum::Comparator(Comparators::LessThan) = self.peek_next_token().token {
+            self.parse_generic_struct_definition(&name, &next_token);
+            return;
+        }
+
+        self.parse_struct_members(name.clone());
+        self.define_type(&name, &next_token);
+    }
+
+    /// LCURLY (VAR_NAME : VAR_TYPE,)* RCURLY
+    pub fn parse_struct_members(&mut self, name: String) {
         self.validate_token(TokenEnum::Bracket(Bracket::LCurly));
 
         let mut members = vec![];
@@ -44,6 +56,15 @@ impl Parser {
 
             let var = self.parse_variable();
 
+            // a struct literal doesn't know which variant a member is given, so it can't lay out
+            // the payload. A pointer to the union works
+            if let VarType::Union(union_name, _) = &var.var_type {
+                compiler_error(
+                    format!("Struct members can't be unions, use a pointer to '{union_name}' instead"),
+                    var.get_token(),
+                );
+            }
+
             members.push(StructMemberType {
                 name: var.var_name,
                 member_type: var.var_type,
@@ -71,6 +92,12 @@ impl Parser {
 
         if let TokenEnum::Variable(ref name) = name_token.token {
             struct_name = name.into();
+
+            self.symbols.refer_to_type(&name_token, name);
+
+            if self.is_generic_struct(name) {
+                struct_name = self.parse_generic_struct_instance(&name_token, name).to_string();
+            }
         } else {
             unexpected_token_string(&name_token, "Struct name");
         }
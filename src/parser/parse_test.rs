@@ -0,0 +1,102 @@
+use std::{cell::RefCell, process::exit, rc::Rc};
+
+use crate::{
+    ast::function_def::FunctionDefinition,
+    helpers::{compiler_error, unexpected_token},
+    interpreter::interpreter::FunctionHashMapValue,
+    lexer::{
+        lexer::Token,
+        tokens::{Bracket, TokenEnum},
+        types::VarType,
+    },
+    types::ASTNode,
+};
+
+use super::parser::Parser;
+
+/// test "name" { ... }
+#[derive(Debug)]
+pub struct Test {
+    pub name: String,
+    /// The function the block of the test was turned into
+    pub function: String,
+    /// The name of the test
+    pub token: Token,
+}
+
+/// Tests are functions that can't be called from the program, as `$` can't be in the name of a
+/// function. The first test -> test$0
+pub fn get_test_function_name(test_number: usize) -> String {
+    format!("test${test_number}")
+}
+
+pub fn is_test_function(function_name: &str) -> bool {
+    function_name.starts_with("test$")
+}
+
+impl Parser {
+    /// TEST -> test STRING_LITERAL LCURLY (STATEMENT[] - FUNCTION_DEF) RCURLY
+    ///
+    /// We get here after 'test' has been consumed. The block becomes a function that takes and
+    /// returns nothing
+    pub fn parse_test(&mut self, test_token: &Token) -> ASTNode {
+        if self.inside_function_depth != 0 {
+            compiler_error("Tests can only be defined outside of functions", test_token);
+        }
+
+        let name_token = self.get_next_token();
+
+        let name = match &name_token.token {
+            TokenEnum::StringLiteral(name) => name.clone(),
+
+            _ => {
+                unexpected_token(&name_token, Some(&TokenEnum::StringLiteral("".into())));
+                exit(1);
+            }
+        };
+
+        if self.tests.iter().any(|test| test.name == name) {
+            compiler_error(format!("Test \"{name}\" is defined more than once"), &name_token);
+        }
+
+        let function_name = get_test_function_name(self.tests.len());
+
+        self.current_function_being_parsed = Some(function_name.clone());
+
+        self.validate_token(TokenEnum::Bracket(Bracket::LCurly));
+
+        self.inside_function_depth += 1;
+        let block = self.parse_program();
+        self.inside_function_depth -= 1;
+
+        self.validate_token(TokenEnum::Bracket(Bracket::RCurly));
+
+        self.current_function_being_parsed = None;
+
+        let function: ASTNode = Rc::new(RefCell::new(Box::new(FunctionDefinition::new(
+            function_name.clone(),
+            vec![],
+            block,
+            VarType::Unknown,
+            name_token.clone(),
+            false,
+        ))));
+
+        self.functions.borrow_mut().insert(
+            function_name.clone(),
+            FunctionHashMapValue {
+                func: Rc::clone(&function),
+                return_type: VarType::Unknown,
+                is_extern_func: false,
+            },
+        );
+
+        self.tests.push(Test {
+            name,
+            function: function_name,
+            token: name_token,
+        });
+
+        function
+    }
+}
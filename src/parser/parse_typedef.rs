@@ -71,8 +71,8 @@ impl Parser {
 
         let next_token = self.get_next_token();
 
-        if let TokenEnum::Variable(var_name) = next_token.token {
-            type_name = var_name;
+        if let TokenEnum::Variable(var_name) = &next_token.token {
+            type_name = var_name.clone();
         } else {
             unexpected_token(&next_token, Some(&TokenEnum::Variable("".into())));
         }
@@ -86,10 +86,8 @@ impl Parser {
             TokenEnum::Type(type_) => type_.clone(),
 
             // Struct typedef alias
-            TokenEnum::Variable(type_name) => {
-                println!("TokenEnum::Variable {type_name}");
-                todo!()
-            }
+            // nothing can be printed here, the language server talks to the editor over stdout
+            TokenEnum::Variable(type_name) => todo!("Typedef of the user defined type '{type_name}'"),
 
             // Function typedef
             TokenEnum::Keyword(..) => {
@@ -108,9 +106,11 @@ impl Parser {
         self.get_next_token();
 
         self.user_defined_types.push(UserDefinedType {
-            name: type_name,
+            name: type_name.clone(),
             type_: typedef_type,
         });
+
+        self.define_type(&type_name, &next_token);
     }
 
     // we arrive here after parsing the 'extren' keyword
@@ -0,0 +1,120 @@
+use std::{cell::RefCell, process::exit, rc::Rc};
+
+use crate::{
+    ast::union_variant::UnionVariant,
+    helpers::{compiler_error, unexpected_token},
+    lexer::{
+        lexer::Token,
+        tokens::{Bracket, TokenEnum},
+        types::{StructMemberType, VarType, UNION_TAG_SIZE},
+    },
+    types::ASTNode,
+};
+
+use super::parser::{Parser, UserDefinedType};
+
+impl Parser {
+    /// UNION_DEFINITION -> union NAME LCURLY (VARIANT LPAREN VAR_TYPE RPAREN COMMA*)+ RCURLY
+    ///
+    /// We get here after 'union' has been consumed
+    pub fn parse_union_definition(&mut self) {
+        let mut name = String::from("");
+
+        let next_token = self.get_next_token();
+
+        if let TokenEnum::Variable(var_name) = &next_token.token {
+            name = var_name.into();
+        } else {
+            unexpected_token(&next_token, Some(&TokenEnum::Variable("".into())));
+        }
+
+        if self.user_defined_types.iter().any(|t| t.name == name) {
+            compiler_error(format!("Type '{name}' is already defined"), &next_token);
+        }
+
+        self.validate_token(TokenEnum::Bracket(Bracket::LCurly));
+
+        let mut variants: Vec<StructMemberType> = vec![];
+
+        loop {
+            if matches!(self.peek_next_token().token, TokenEnum::Bracket(Bracket::RCurly)) {
+                break;
+            }
+
+            // union Result {
+            //     Ok(int),
+            //     Err(str),
+            // }
+            let variant_token = self.get_next_token();
+
+            let variant_name = if let TokenEnum::Variable(variant_name) = &variant_token.token {
+                variant_name.clone()
+            } else {
+                unexpected_token(&variant_token, Some(&TokenEnum::Variable("".into())));
+                exit(1);
+            };
+
+            if variants.iter().any(|v| v.name == variant_name) {
+                compiler_error(
+                    format!("Variant '{variant_name}' is defined multiple times in union '{name}'"),
+                    &variant_token,
+                );
+            }
+
+            self.validate_token(TokenEnum::Bracket(Bracket::LParen));
+            let (_, payload_type) = self.parse_var_type();
+            self.validate_token(TokenEnum::Bracket(Bracket::RParen));
+
+            // All payloads live at the same place, right after the tag
+            variants.push(StructMemberType {
+                name: variant_name,
+                member_type: payload_type,
+                offset: UNION_TAG_SIZE,
+            });
+
+            if matches!(self.peek_next_token().token, TokenEnum::Comma) {
+                self.get_next_token();
+            }
+        }
+
+        self.validate_token(TokenEnum::Bracket(Bracket::RCurly));
+
+        if variants.is_empty() {
+            compiler_error(format!("Union '{name}' must have at least one variant"), &next_token);
+        }
+
+        self.user_defined_types.push(UserDefinedType {
+            name: name.clone(),
+            type_: VarType::Union(name.clone(), Rc::new(RefCell::new(variants))),
+        });
+
+        self.define_type(&name, &next_token);
+    }
+
+    /// UNION_VARIANT -> UNION_NAME DOT VARIANT LPAREN LOGICAL_EXPRESSION RPAREN
+    ///
+    /// We get here after UNION_NAME has been consumed
+    pub fn parse_union_variant(&mut self, union_token: &Token, union_name: &str) -> ASTNode {
+        self.validate_token(TokenEnum::Dot);
+
+        let variant_token = self.get_next_token();
+
+        let variant_name = if let TokenEnum::Variable(variant_name) = &variant_token.token {
+            variant_name.clone()
+        } else {
+            unexpected_token(&variant_token, Some(&TokenEnum::Variable("".into())));
+            exit(1);
+        };
+
+        self.validate_token(TokenEnum::Bracket(Bracket::LParen));
+        let payload = self.parse_logical_expression();
+        self.validate_token(TokenEnum::Bracket(Bracket::RParen));
+
+        return Rc::new(RefCell::new(Box::new(UnionVariant::new(
+            union_name.into(),
+            variant_name,
+            payload,
+            variant_token,
+        ))));
+    }
+}
@@ -2,8 +2,10 @@ use crate::{
     ast::{abstract_syntax_tree::AST, typedef::Typedef, void::Void},
     helpers::{self, compiler_error, unexpected_token},
     lexer::{
-        keywords::{CONST_VAR_DEFINE, CONTINUE, EXTERN, INCLUDE, MEM, STRUCT, TYPE_DEF},
-        tokens::{Number, Operations},
+        keywords::{
+            CONST_VAR_DEFINE, CONTINUE, ENUM, EXTERN, IMPL, INCLUDE, MATCH, MEM, STRUCT, TEST, TYPE_DEF, UNION,
+        },
+        tokens::{Comparators, Number, Operations},
         types::VarType,
     },
     trace,
@@ -13,6 +15,8 @@ use crate::{
 use core::panic;
 use std::{cell::RefCell, collections::HashMap, fs, path::Path, process::exit, rc::Rc};
 
+use super::{parse_generics::GenericTemplate, parse_test::Test};
+
 use crate::{
     ast::{
         jump::{Jump, JumpType},
@@ -24,10 +28,38 @@ use crate::{
         lexer::{Lexer, Token},
         tokens::{Bracket, TokenEnum},
     },
+    semantic_analyzer::symbols::{SymbolKind, Symbols},
 };
 
 pub type ParserFunctions = Rc<RefCell<Functions>>;
 
+/// Keywords that start a declaration, the only statements allowed outside of a function
+const TOP_LEVEL_KEYWORDS: [&str; 12] = [
+    VAR_DEFINE,
+    CONST_VAR_DEFINE,
+    MEM,
+    TYPE_DEF,
+    FUNCTION_DEFINE,
+    EXTERN,
+    STRUCT,
+    ENUM,
+    UNION,
+    IMPL,
+    INCLUDE,
+    TEST,
+];
+
+/// include "file.cy"
+#[derive(Debug)]
+pub struct Include {
+    /// The path of the included file
+    pub token: Token,
+    /// Name of the file the tokens of the included file have
+    pub file: String,
+    /// Whether the file defines structs, enums or unions
+    pub declares_types: bool,
+}
+
 #[derive(Debug)]
 pub struct UserDefinedType {
     pub name: String,
@@ -56,13 +88,27 @@ pub struct Parser {
     pub user_defined_types: Vec<UserDefinedType>,
 
     pub type_aliases: Vec<Typedef>,
+
+    pub generic_functions: Vec<GenericTemplate>,
+    pub generic_structs: Vec<GenericTemplate>,
+
+    /// Type parameters of the generic currently being instantiated, bound to the type arguments
+    pub type_arguments: Vec<UserDefinedType>,
+
+    pub includes: Vec<Include>,
+
+    /// Tests of this file and of the files it includes, in the order they're defined in
+    pub tests: Vec<Test>,
+
+    /// Definitions of types and where they're used, for the language server
+    pub symbols: Symbols,
 }
 
 impl Parser {
     pub fn new(file: Vec<u8>, file_name: String) -> Self {
         let lexer = Lexer::new(file, file_name);
 
-        Self {
+        let mut parser = Self {
             lexer: Rc::new(RefCell::new(Box::new(lexer))),
             bracket_stack: vec![],
             functions: Rc::new(RefCell::new(HashMap::new())),
@@ -82,6 +128,28 @@ impl Parser {
             current_function_being_parsed: None,
             user_defined_types: vec![],
             type_aliases: vec![],
+
+            generic_functions: vec![],
+            generic_structs: vec![],
+            type_arguments: vec![],
+
+            includes: vec![],
+
+            tests: vec![],
+
+            symbols: Symbols::default(),
+        };
+
+        parser.find_generic_functions();
+
+        parser
+    }
+
+    /// Adds the type called `name` the parser has just defined to the symbols
+    pub fn define_type(&mut self, name: &str, token: &Token) {
+        if let Some(user_defined_type) = self.user_defined_types.iter().find(|t| t.name == name) {
+            self.symbols
+                .define(name, SymbolKind::Type, token, &user_defined_type.type_);
         }
     }
 
@@ -126,6 +194,21 @@ impl Parser {
 
         // println!("parse_statements current_token {:#?}", current_token);
 
+        // The program starts at main, global variables are initialised before it's called
+        if self.inside_function_depth == 0 {
+            let is_declaration = matches!(
+                &current_token.token,
+                TokenEnum::Keyword(keyword) if TOP_LEVEL_KEYWORDS.contains(&keyword.as_str())
+            );
+
+            if !is_declaration {
+                compiler_error(
+                    "Only declarations can be outside a function, the program starts at 'main'",
+                    &current_token,
+                );
+            }
+        }
+
         match &current_token.token {
             TokenEnum::Keyword(keyword) => {
                 self.get_next_token();
@@ -139,9 +222,9 @@ impl Parser {
 
                     CONST_VAR_DEFINE => self.parse_declaration_statement(true),
 
-                    IF_STATEMENT => self.parse_conditional_statement(),
+                    IF_STATEMENT => self.parse_conditional_statement(&current_token),
 
-                    LOOP => self.parse_loop(),
+                    LOOP => self.parse_loop(&current_token),
 
                     FUNCTION_DEFINE => {
                         if self.inside_function_depth != 0 {
@@ -198,6 +281,30 @@ impl Parser {
                         Rc::new(RefCell::new(Box::new(Void)))
                     }
 
+                    ENUM => {
+                        self.parse_enum_definition();
+
+                        Rc::new(RefCell::new(Box::new(Void)))
+                    }
+
+                    UNION => {
+                        self.parse_union_definition();
+
+                        Rc::new(RefCell::new(Box::new(Void)))
+                    }
+
+                    MATCH => self.parse_match_statement(&current_token),
+
+                    IMPL => {
+                        if self.inside_function_depth != 0 {
+                            compiler_error("Defining methods inside functions is not allowed", &current_token);
+                        }
+
+                        self.parse_impl_block()
+                    }
+
+                    TEST => self.parse_test(&current_token),
+
                     INCLUDE => {
                         if self.inside_loop_depth != 0 || self.inside_function_depth != 0 {
                             compiler_error("`include` can only be used at the beginning of a file", &current_token)
@@ -207,9 +314,9 @@ impl Parser {
 
                         let mut file_path = String::new();
 
-                        if let TokenEnum::StringLiteral(fp) = included_file_tok.token {
+                        if let TokenEnum::StringLiteral(fp) = &included_file_tok.token {
                             self.get_next_token();
-                            file_path = fp;
+                            file_path = fp.clone();
                         } else {
                             unexpected_token(&included_file_tok, Some(&TokenEnum::StringLiteral("".into())));
                         }
@@ -231,11 +338,20 @@ impl Parser {
                         let new_file_lexer = Lexer::new(file_contents, file_path.to_str().unwrap().into());
                         let new_lexer = Rc::new(RefCell::new(Box::new(new_file_lexer)));
                         self.lexer = new_lexer.clone();
+                        self.find_generic_functions();
+
+                        let num_types = self.user_defined_types.len();
 
                         let ast = self.parse_program();
 
                         self.lexer = current_lexer;
 
+                        self.includes.push(Include {
+                            token: included_file_tok,
+                            file: file_path.to_str().unwrap().into(),
+                            declares_types: self.user_defined_types.len() > num_types,
+                        });
+
                         ast
                     }
 
@@ -270,8 +386,8 @@ impl Parser {
                         match b {
                             Bracket::LParen => {
                                 // function invocation
-                                self.get_next_token();
-                                self.parse_function_call(var.to_string(), false)
+                                let var_token = self.get_next_token();
+                                self.parse_function_call(&var_token, var.to_string(), false)
                             }
 
                             Bracket::LSquare => {
@@ -295,6 +411,21 @@ impl Parser {
                         }
                     }
 
+                    // generic function invocation
+                    // max<int>(a, b)
+                    TokenEnum::Comparator(Comparators::LessThan) if self.is_generic_function(var) => {
+                        let var_token = self.get_next_token();
+                        let instance = self.parse_generic_function_instance(&var_token, var);
+                        self.parse_function_call(&var_token, instance, false)
+                    }
+
+                    // method call
+                    // point.move(1, 2)
+                    TokenEnum::Dot if self.is_method_call() => {
+                        let var_token = self.get_next_token();
+                        self.parse_method_call_statement(&var_token, var)
+                    }
+
                     TokenEnum::Equals | TokenEnum::MinusEquals | TokenEnum::PlusEquals | TokenEnum::Dot => {
                         // variable assignment
                         let var_token = self.get_next_token();
@@ -353,8 +484,9 @@ impl Parser {
             TokenEnum::Comma => todo!(),
             TokenEnum::SemiColon => todo!(),
             TokenEnum::FunctionReturnIndicator => todo!(),
-            TokenEnum::Comment => todo!(),
+            TokenEnum::Comment(_) => todo!(),
             TokenEnum::Dot => todo!(),
+            TokenEnum::DotDot => todo!(),
 
             TokenEnum::Unknown(..) => {
                 panic!("Unknown token: {:?}", &current_token);
@@ -363,6 +495,8 @@ impl Parser {
             TokenEnum::EOF => {
                 unreachable!("Reached EOF");
             }
+
+            TokenEnum::Union(..) => unreachable!("The lexer doesn't make union values"),
         }
     }
 
@@ -395,7 +529,7 @@ impl Parser {
                     _ => statements.push(self.parse_statements()),
                 },
 
-                TokenEnum::Comment => continue,
+                TokenEnum::Comment(_) => continue,
 
                 _ => {
                     statements.push(self.parse_statements());
@@ -0,0 +1,481 @@
+use std::{
+    cell::RefCell,
+    io::{self, BufRead, Write},
+    rc::Rc,
+};
+
+use crate::{
+    asm::asm::ASM,
+    ast::{abstract_syntax_tree::ASTNodeEnum, program::Program},
+    helpers::{catch_compile_error, unexpected_token, CompileError},
+    interpreter::interpreter::{value_text, Functions, Interpreter, Variables},
+    lexer::{
+        keywords::{EXTERN, FUNCTION_DEFINE, IMPL, INCLUDE},
+        lexer::Lexer,
+        tokens::{Bracket, TokenEnum},
+        types::VarType,
+    },
+    lsp::analysis::source_type_name,
+    options::CompilerOptions,
+    parser::parser::Parser,
+    semantic_analyzer::{
+        semantic_analyzer::{ActivationRecord, ActivationRecordType, CallStack, SemanticAnalyzer},
+        warnings::WarningOptions,
+    },
+    types::ASTNode,
+};
+
+/// Inputs are parsed as if they were a file with this name
+const FILE_NAME: &str = "repl";
+
+const PROMPT: &str = "> ";
+/// While a block or a bracket is still open
+const CONTINUATION_PROMPT: &str = "... ";
+
+const HELP: &str = "\
+Declarations and statements are run, expressions are run and their value is printed
+    :type EXPR  the type of an expression
+    :ast EXPR   the syntax tree of an expression
+    :asm EXPR   the assembly an expression compiles to
+    :help       this message
+    :quit       leave, as does Ctrl-D
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Input {
+    /// Functions, methods and includes, which can only be outside a function
+    Definitions,
+    /// Anything that could be in a function, global variables and types can be either
+    Statements,
+    Expression,
+}
+
+/// What to do with an input once it's been analysed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Run,
+    Type,
+    Ast,
+    Asm,
+}
+
+/// What's been defined before an input, to go back to if the input has an error
+struct Checkpoint {
+    functions: Vec<String>,
+    user_defined_types: usize,
+    type_aliases: usize,
+    includes: usize,
+    generic_functions: usize,
+    generic_structs: usize,
+    globals: Vec<String>,
+    variables: Variables,
+}
+
+/// Reads inputs from `input` and runs them one after the other with the interpreter. Everything
+/// an input defines is there for the ones after it
+pub struct Repl<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    /// Parses every input, it keeps the functions and types defined so far
+    parser: Parser,
+    interpreter: Interpreter,
+    /// The global variables declared so far. Each input is analysed with a new call stack that
+    /// starts with these
+    globals: ActivationRecord,
+    options: CompilerOptions,
+}
+
+/// Starts the REPL on stdin and stdout, returns the exit code
+pub fn run() -> i32 {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    Repl::new(stdin.lock(), stdout.lock()).run()
+}
+
+/// The lexer expects the input to end with a line break, as files do
+fn lexer_input(input: &str) -> Vec<u8> {
+    format!("{input}\n").into_bytes()
+}
+
+fn empty_globals() -> ActivationRecord {
+    ActivationRecord::new("".into(), ActivationRecordType::Global)
+}
+
+/// How much deeper in brackets the input is at its end than at its start. Input that doesn't
+/// lex is complete, the parser reports what's wrong with it
+fn open_brackets(input: &str) -> i32 {
+    let tokens = catch_compile_error(|| Lexer::new(lexer_input(input), FILE_NAME.into()).lossless_tokens());
+
+    tokens.map_or(0, |tokens| {
+        tokens
+            .iter()
+            .map(|token| match token.token.token {
+                TokenEnum::Bracket(Bracket::LCurly | Bracket::LParen | Bracket::LSquare) => 1,
+                TokenEnum::Bracket(Bracket::RCurly | Bracket::RParen | Bracket::RSquare) => -1,
+                _ => 0,
+            })
+            .sum()
+    })
+}
+
+/// Functions can only be defined outside of functions, and only statements can be inside one.
+/// An input that's neither, and doesn't assign anything, is an expression
+fn input_kind(input: &str) -> Input {
+    let Ok(tokens) = catch_compile_error(|| Lexer::new(lexer_input(input), FILE_NAME.into()).lossless_tokens()) else {
+        return Input::Statements;
+    };
+
+    let mut depth = 0;
+    let mut assigns = false;
+
+    for token in &tokens {
+        match &token.token.token {
+            TokenEnum::Bracket(Bracket::LCurly | Bracket::LParen | Bracket::LSquare) => depth += 1,
+            TokenEnum::Bracket(Bracket::RCurly | Bracket::RParen | Bracket::RSquare) => depth -= 1,
+
+            TokenEnum::Keyword(keyword)
+                if depth == 0 && [FUNCTION_DEFINE, EXTERN, IMPL, INCLUDE].contains(&keyword.as_str()) =>
+            {
+                return Input::Definitions;
+            }
+
+            TokenEnum::Equals | TokenEnum::PlusEquals | TokenEnum::MinusEquals if depth == 0 => assigns = true,
+
+            _ => {}
+        }
+    }
+
+    let starts_with_keyword = matches!(
+        tokens.iter().find(|t| !matches!(t.token.token, TokenEnum::Comment(_))),
+        Some(token) if matches!(token.token.token, TokenEnum::Keyword(_))
+    );
+
+    if starts_with_keyword || assigns {
+        Input::Statements
+    } else {
+        Input::Expression
+    }
+}
+
+/// Analyses the statements of an input one by one. Declarations are of globals, anything else is
+/// analysed as if it were in a function, so loops and blocks can have variables of their own
+fn analyze(node: &ASTNode, call_stack: &mut CallStack, functions: &Rc<RefCell<Functions>>) {
+    let statements = match node.borrow().get_node() {
+        ASTNodeEnum::Program(program) => program.get_statements().clone(),
+        _ => vec![Rc::clone(node)],
+    };
+
+    for statement in statements {
+        let is_declaration = matches!(
+            statement.borrow().get_node(),
+            ASTNodeEnum::DeclarationStatement(_)
+                | ASTNodeEnum::MemoryAlloc(_)
+                | ASTNodeEnum::FunctionDef(_)
+                | ASTNodeEnum::GenericFunction(_)
+                // an included file
+                | ASTNodeEnum::Program(_)
+                | ASTNodeEnum::Void
+        );
+
+        if is_declaration {
+            statement.borrow_mut().semantic_visit(call_stack, Rc::clone(functions));
+            continue;
+        }
+
+        call_stack.push("".into(), ActivationRecordType::Function(0));
+        statement.borrow_mut().semantic_visit(call_stack, Rc::clone(functions));
+        call_stack.pop();
+    }
+}
+
+/// `"text"` for strings, the way they're written in a program
+fn value_literal(value: &TokenEnum) -> String {
+    match value {
+        TokenEnum::StringLiteral(s) => format!("\"{s}\""),
+        TokenEnum::Union(union_name, variant_name, _, payload) => {
+            format!("{union_name}.{variant_name}({})", value_literal(payload))
+        }
+        value => value_text(value),
+    }
+}
+
+impl<R: BufRead, W: Write> Repl<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        let parser = Parser::new(vec![], FILE_NAME.into());
+        let options = CompilerOptions::default();
+
+        let program: ASTNode = Rc::new(RefCell::new(Box::new(Program::new(vec![]))));
+        let interpreter = Interpreter::new(program, Rc::clone(&parser.functions), &options);
+
+        Self {
+            input,
+            output,
+            parser,
+            interpreter,
+            globals: empty_globals(),
+            options,
+        }
+    }
+
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Runs until `:quit` or the end of the input, returns the exit code
+    pub fn run(&mut self) -> i32 {
+        while let Some(input) = self.read_input() {
+            let input = input.trim();
+
+            let (action, source) = match input.split_once(char::is_whitespace).unwrap_or((input, "")) {
+                ("", _) => continue,
+                (":quit" | ":q", _) => break,
+
+                (":help", _) => {
+                    write!(self.output, "{HELP}");
+                    continue;
+                }
+
+                (":type", source) => (Action::Type, source),
+                (":ast", source) => (Action::Ast, source),
+                (":asm", source) => (Action::Asm, source),
+
+                (command, _) if command.starts_with(':') => {
+                    writeln!(self.output, "Unknown command '{command}', :help lists the commands");
+                    continue;
+                }
+
+                _ => (Action::Run, input),
+            };
+
+            let kind = input_kind(source);
+
+            if action != Action::Run && kind != Input::Expression {
+                writeln!(self.output, "'{source}' is not an expression");
+                continue;
+            }
+
+            let checkpoint = self.checkpoint();
+
+            match self.run_input(source, kind, action) {
+                Ok(result) => {
+                    if !result.is_empty() {
+                        writeln!(self.output, "{result}");
+                    }
+                }
+
+                Err(error) => {
+                    self.restore(checkpoint);
+                    self.report(source, &error);
+                }
+            }
+        }
+
+        self.output.flush();
+
+        0
+    }
+
+    /// A line, and the lines after it until every bracket in it is closed. None at the end of
+    /// the input
+    fn read_input(&mut self) -> Option<String> {
+        let mut input = String::new();
+        let mut prompt = PROMPT;
+
+        loop {
+            write!(self.output, "{prompt}");
+            self.output.flush();
+
+            let mut line = String::new();
+
+            match self.input.read_line(&mut line) {
+                Ok(0) | Err(_) if input.is_empty() => return None,
+                Ok(0) | Err(_) => return Some(input),
+                Ok(_) => input += &line,
+            }
+
+            if open_brackets(&input) <= 0 {
+                return Some(input);
+            }
+
+            prompt = CONTINUATION_PROMPT;
+        }
+    }
+
+    /// Parses and analyses `source`, then does `action` with it. Returns what to print
+    fn run_input(&mut self, source: &str, kind: Input, action: Action) -> Result<String, CompileError> {
+        let node = catch_compile_error(|| self.parse(source, kind))?;
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(
+            Rc::clone(&node),
+            Rc::clone(&self.parser.functions),
+            &self.parser.user_defined_types,
+            &self.parser.type_aliases,
+            &self.parser.includes,
+            WarningOptions::none(),
+        );
+
+        let call_stack = &mut semantic_analyzer.call_stack;
+        call_stack.replace_globals(std::mem::replace(&mut self.globals, empty_globals()));
+
+        let interpreter = &mut self.interpreter;
+        let options = &self.options;
+
+        let result = catch_compile_error(|| {
+            // not `SemanticAnalyzer::check`, the statements have to stay in the order they're in
+            analyze(&node, call_stack, &interpreter.functions);
+
+            let result_type = || source_type_name(&node.borrow().get_type().1);
+
+            match action {
+                Action::Run => {
+                    let value = interpreter.evaluate(&node, call_stack);
+
+                    match (kind, &*value.token) {
+                        (Input::Expression, TokenEnum::Unknown(_)) | (Input::Definitions | Input::Statements, _) => {
+                            String::new()
+                        }
+
+                        (Input::Expression, value) => format!("{}: {}", value_literal(value), result_type()),
+                    }
+                }
+
+                Action::Type => result_type(),
+                Action::Ast => format!("{:#?}", node.borrow().get_node()),
+
+                Action::Asm => {
+                    let mut asm = ASM::new(options);
+                    let data_start = asm.data.len();
+                    let code_start = asm.labels[0].code.len();
+
+                    node.borrow().visit_com(
+                        &mut interpreter.variables,
+                        Rc::clone(&interpreter.functions),
+                        &mut asm,
+                        call_stack,
+                    );
+
+                    let data = &asm.data[data_start..];
+                    let code = &asm.labels[0].code[code_start..];
+
+                    data.iter()
+                        .chain(code)
+                        .cloned()
+                        .collect::<Vec<String>>()
+                        .join("\n")
+                        .trim()
+                        .to_string()
+                }
+            }
+        });
+
+        self.globals = call_stack.replace_globals(empty_globals());
+
+        result
+    }
+
+    fn parse(&mut self, source: &str, kind: Input) -> ASTNode {
+        let lexer = Lexer::new(lexer_input(source), FILE_NAME.into());
+        self.parser.lexer = Rc::new(RefCell::new(Box::new(lexer)));
+
+        match kind {
+            Input::Definitions => self.parser.parse_program(),
+
+            Input::Statements => {
+                // as if they were in a function, so the parser allows them
+                self.parser.inside_function_depth += 1;
+                let program = self.parser.parse_program();
+                self.parser.inside_function_depth -= 1;
+
+                program
+            }
+
+            Input::Expression => {
+                let expression = self.parser.parse_logical_expression();
+
+                while let TokenEnum::SemiColon = self.parser.peek_next_token().token {
+                    self.parser.get_next_token();
+                }
+
+                let next = self.parser.peek_next_token();
+
+                if next.token != TokenEnum::EOF {
+                    unexpected_token(&next, None);
+                }
+
+                expression
+            }
+        }
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            functions: self.parser.functions.borrow().keys().cloned().collect(),
+            user_defined_types: self.parser.user_defined_types.len(),
+            type_aliases: self.parser.type_aliases.len(),
+            includes: self.parser.includes.len(),
+            generic_functions: self.parser.generic_functions.len(),
+            generic_structs: self.parser.generic_structs.len(),
+            globals: self.globals.variable_members.keys().cloned().collect(),
+            variables: self.interpreter.variables.clone(),
+        }
+    }
+
+    /// Forgets what an input that failed defined. The parser can be anywhere in the input, so
+    /// it's put back outside of any block
+    fn restore(&mut self, checkpoint: Checkpoint) {
+        let parser = &mut self.parser;
+
+        parser
+            .functions
+            .borrow_mut()
+            .retain(|name, _| checkpoint.functions.contains(name));
+
+        parser.user_defined_types.truncate(checkpoint.user_defined_types);
+        parser.type_aliases.truncate(checkpoint.type_aliases);
+        parser.includes.truncate(checkpoint.includes);
+        parser.generic_functions.truncate(checkpoint.generic_functions);
+        parser.generic_structs.truncate(checkpoint.generic_structs);
+
+        parser.bracket_stack.clear();
+        parser.inside_loop_depth = 0;
+        parser.inside_function_depth = 0;
+        parser.inside_if_else_depth = 0;
+        parser.inside_current_loop_number = -1;
+        parser.current_function_being_parsed = None;
+        parser.times_dereferenced = 0;
+        parser.type_arguments.clear();
+
+        self.globals
+            .variable_members
+            .retain(|name, _| checkpoint.globals.contains(name));
+
+        self.interpreter.variables = checkpoint.variables;
+    }
+
+    /// The error, and the line of the input it's on with the place marked
+    fn report(&mut self, source: &str, error: &CompileError) {
+        writeln!(self.output, "error: {}", error.message);
+
+        let Some(location) = &error.location else {
+            return;
+        };
+
+        if location.file != FILE_NAME {
+            writeln!(self.output, "  --> {}:{}", location.file, location.line_number);
+            return;
+        }
+
+        let Some(line) = source.lines().nth(location.line_number.saturating_sub(1)) else {
+            return;
+        };
+
+        let line_start = source[..location.start.min(source.len())]
+            .rfind('\n')
+            .map_or(0, |n| n + 1);
+        let column = location.start.saturating_sub(line_start).min(line.len());
+
+        writeln!(self.output, "  {line}");
+        writeln!(self.output, "  {}^", " ".repeat(column));
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::{
+    ast::{
+        abstract_syntax_tree::{add_to_cfg, used_names, Names, AST},
+        variable::Variable,
+    },
+    helpers::compiler_error,
+    lexer::{lexer::Token, types::VarType},
+    types::ASTNode,
+};
+
+use super::warnings::{Warning, Warnings};
+
+/// What happens to a local variable inside a basic block
+#[derive(Debug)]
+enum Event {
+    /// `def a: int;` declares a variable without a value, `def a: int = 5;` with one
+    Declare {
+        name: String,
+        has_value: bool,
+        token: Token,
+    },
+    Read {
+        name: String,
+        token: Token,
+    },
+    Write {
+        name: String,
+    },
+}
+
+/// Statements that always run one after the other
+#[derive(Debug, Default)]
+struct BasicBlock {
+    events: Vec<Event>,
+    successors: Vec<usize>,
+    /// The first statement in this block, warnings about unreachable code point here
+    first_token: Option<Token>,
+    /// The last statement in this block, errors about a path point here
+    last_token: Option<Token>,
+}
+
+/// Where `continue` and `break` go inside a loop
+#[derive(Debug)]
+struct LoopTargets {
+    continue_to: usize,
+    break_to: usize,
+}
+
+/// The control flow graph of a function. Block 0 is where the function starts, and every
+/// `return` goes to `exit`
+///
+/// Nodes add themselves with `add_to_cfg`, statements go into the current block and branches
+/// start new ones. A block nothing jumps to is unreachable, code after a `return` ends up in one
+#[derive(Debug)]
+pub struct ControlFlowGraph {
+    blocks: Vec<BasicBlock>,
+    current: usize,
+    exit: usize,
+    /// Where falling off the end of the function body goes
+    end: usize,
+    loops: Vec<LoopTargets>,
+    has_return: bool,
+}
+
+impl ControlFlowGraph {
+    pub fn new(body: &ASTNode) -> Self {
+        let mut cfg = Self {
+            blocks: vec![BasicBlock::default(), BasicBlock::default()],
+            current: 0,
+            exit: 1,
+            end: 0,
+            loops: vec![],
+            has_return: false,
+        };
+
+        add_to_cfg(body, &mut cfg);
+        cfg.end = cfg.current;
+
+        cfg
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn set_current(&mut self, block: usize) {
+        self.current = block;
+    }
+
+    pub fn new_block(&mut self) -> usize {
+        self.blocks.push(BasicBlock::default());
+        self.blocks.len() - 1
+    }
+
+    /// Starts a new block that follows every block in `predecessors`
+    pub fn start_block(&mut self, predecessors: &[usize]) -> usize {
+        let block = self.new_block();
+
+        for &predecessor in predecessors {
+            self.add_edge(predecessor, block);
+        }
+
+        self.current = block;
+        block
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.blocks[from].successors.push(to);
+    }
+
+    /// Records a statement at `token`, for errors about the path it's on
+    pub fn mark(&mut self, token: &Token) {
+        let block = &mut self.blocks[self.current];
+
+        block.first_token.get_or_insert_with(|| token.clone());
+        block.last_token = Some(token.clone());
+    }
+
+    /// Records the variables read by an expression
+    pub fn read(&mut self, node: &ASTNode) {
+        let mut names = Names::default();
+        used_names(node, &mut names);
+
+        self.add_names(names);
+    }
+
+    /// Passing `&a` to a function is how it gets a value, so taking the address counts as
+    /// assigning the variable
+    pub fn add_names(&mut self, names: Names) {
+        for name in names.addressed {
+            self.blocks[self.current].events.push(Event::Write { name });
+        }
+
+        for (name, token) in names.used {
+            self.mark(&token);
+            self.blocks[self.current].events.push(Event::Read { name, token });
+        }
+    }
+
+    /// Variables of types that aren't checked are treated as if they were declared with a value
+    pub fn declare(&mut self, variable: &Variable, has_value: bool) {
+        let has_value = has_value || !is_checked(&variable.var_type);
+        let token = variable.get_token().clone();
+
+        self.blocks[self.current].events.push(Event::Declare {
+            name: variable.var_name.clone(),
+            has_value,
+            token: token.clone(),
+        });
+
+        self.mark(&token);
+    }
+
+    pub fn write(&mut self, variable: &Variable) {
+        self.blocks[self.current].events.push(Event::Write {
+            name: variable.var_name.clone(),
+        });
+
+        self.mark(variable.get_token());
+    }
+
+    /// Whatever comes after a jump can only be reached by jumping to it
+    fn jump_to(&mut self, target: usize) {
+        self.add_edge(self.current, target);
+        self.current = self.new_block();
+    }
+
+    pub fn add_return(&mut self) {
+        self.has_return = true;
+        self.jump_to(self.exit);
+    }
+
+    pub fn add_break(&mut self) {
+        let target = self.loops.last().expect("'break' outside of a loop").break_to;
+        self.jump_to(target);
+    }
+
+    pub fn add_continue(&mut self) {
+        let target = self.loops.last().expect("'continue' outside of a loop").continue_to;
+        self.jump_to(target);
+    }
+
+    pub fn push_loop(&mut self, continue_to: usize, break_to: usize) {
+        self.loops.push(LoopTargets { continue_to, break_to });
+    }
+
+    pub fn pop_loop(&mut self) {
+        self.loops.pop();
+    }
+
+    pub fn has_return(&self) -> bool {
+        self.has_return
+    }
+
+    /// Breadth first search from the start of the function. Returns the block each reachable
+    /// block was first reached from, the start is its own parent
+    fn reachable(&self) -> Vec<Option<usize>> {
+        let mut parents = vec![None; self.blocks.len()];
+        parents[0] = Some(0);
+
+        let mut queue = VecDeque::from([0]);
+
+        while let Some(block) = queue.pop_front() {
+            for &successor in &self.blocks[block].successors {
+                if parents[successor].is_none() {
+                    parents[successor] = Some(block);
+                    queue.push_back(successor);
+                }
+            }
+        }
+
+        parents
+    }
+
+    /// If the end of the function body can be reached without a `return`, returns the last
+    /// statement on such a path. That's None if there are no statements on it
+    pub fn path_without_return(&self) -> Option<Option<Token>> {
+        let parents = self.reachable();
+
+        parents[self.end]?;
+
+        let mut block = self.end;
+
+        loop {
+            if let Some(token) = &self.blocks[block].last_token {
+                return Some(Some(token.clone()));
+            }
+
+            if block == 0 {
+                return Some(None);
+            }
+
+            block = parents[block].unwrap();
+        }
+    }
+
+    /// Warns once about every piece of code that can't be reached, at its first statement. Code
+    /// reachable from code that's already been warned about isn't warned about again
+    pub fn warn_unreachable(&self, warnings: &mut Warnings) {
+        let parents = self.reachable();
+        let mut covered = vec![false; self.blocks.len()];
+
+        for start in 0..self.blocks.len() {
+            if parents[start].is_some() || covered[start] {
+                continue;
+            }
+
+            let mut queue = VecDeque::from([start]);
+            covered[start] = true;
+
+            let mut warned = false;
+
+            while let Some(block) = queue.pop_front() {
+                if !warned {
+                    if let Some(token) = &self.blocks[block].first_token {
+                        warnings.warn(Warning::UnreachableCode, "Unreachable code", token);
+                        warned = true;
+                    }
+                }
+
+                for &successor in &self.blocks[block].successors {
+                    if parents[successor].is_none() && !covered[successor] {
+                        covered[successor] = true;
+                        queue.push_back(successor);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Variables that might not have a value when a block starts, for every reachable block
+    fn unassigned_at_start(&self) -> Vec<Option<BTreeSet<String>>> {
+        let mut states: Vec<Option<BTreeSet<String>>> = vec![None; self.blocks.len()];
+        states[0] = Some(BTreeSet::new());
+
+        let mut queue = VecDeque::from([0]);
+
+        while let Some(block) = queue.pop_front() {
+            let mut state = states[block].clone().unwrap();
+
+            for event in &self.blocks[block].events {
+                apply(event, &mut state);
+            }
+
+            for &successor in &self.blocks[block].successors {
+                let first_visit = states[successor].is_none();
+
+                let successor_state = states[successor].get_or_insert_with(BTreeSet::new);
+                let size = successor_state.len();
+
+                successor_state.extend(state.iter().cloned());
+
+                if first_visit || successor_state.len() != size {
+                    queue.push_back(successor);
+                }
+            }
+        }
+
+        states
+    }
+
+    /// A variable declared without a value has to be assigned one on every path that reads it
+    pub fn check_definite_assignment(&self) {
+        let states = self.unassigned_at_start();
+
+        for (block, state) in self.blocks.iter().zip(states) {
+            let Some(mut unassigned) = state else {
+                continue;
+            };
+
+            for event in &block.events {
+                if let Event::Read { name, token } = event {
+                    if unassigned.contains(name) {
+                        let declared_on = self.declaration_line(name);
+
+                        compiler_error(
+                            format!(
+                                "Variable '{name}' might be used before it's assigned a value. It's declared \
+                                without one on line {declared_on}"
+                            ),
+                            token,
+                        );
+                    }
+                }
+
+                apply(event, &mut unassigned);
+            }
+        }
+    }
+
+    fn declaration_line(&self, name: &str) -> usize {
+        self.blocks
+            .iter()
+            .flat_map(|block| &block.events)
+            .find_map(|event| match event {
+                Event::Declare {
+                    name: declared,
+                    has_value: false,
+                    token,
+                } if declared == name => Some(token.line_number),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Only variables that are assigned as a whole are checked. Arrays, structs and unions get their
+/// members assigned one at a time
+fn is_checked(var_type: &VarType) -> bool {
+    !matches!(var_type, VarType::Array(..) | VarType::Struct(..) | VarType::Union(..))
+}
+
+fn apply(event: &Event, unassigned: &mut BTreeSet<String>) {
+    match event {
+        Event::Declare {
+            name, has_value: true, ..
+        }
+        | Event::Write { name } => {
+            unassigned.remove(name);
+        }
+
+        Event::Declare {
+            name, has_value: false, ..
+        } => {
+            unassigned.insert(name.clone());
+        }
+
+        Event::Read { .. } => {}
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::abstract_syntax_tree::{used_names, ASTNodeEnum, Names, AST},
+    helpers::compiler_error,
+    interpreter::interpreter::Functions,
+    lexer::lexer::Token,
+    types::ASTNode,
+};
+
+/// A global variable and the globals its initialiser needs
+struct Global {
+    name: String,
+    token: Token,
+    depends_on: Vec<usize>,
+}
+
+/// Flattens the statements of included files into one list
+fn flatten(statements: &[ASTNode], flattened: &mut Vec<ASTNode>) {
+    for statement in statements {
+        if let ASTNodeEnum::Program(program) = statement.borrow().get_node() {
+            flatten(program.get_statements(), flattened);
+            continue;
+        }
+
+        flattened.push(statement.clone());
+    }
+}
+
+/// Names of the variables the initialiser of a global uses, including the ones used by the
+/// functions it calls and the functions they call
+fn names_used_by(statement: &ASTNode, functions: &Functions) -> Vec<String> {
+    let mut names = Names::default();
+    used_names(statement, &mut names);
+
+    let mut visited_functions = vec![];
+    let mut i = 0;
+
+    while i < names.used.len() {
+        let name = names.used[i].0.clone();
+        i += 1;
+
+        if visited_functions.contains(&name) {
+            continue;
+        }
+
+        if let Some(function) = functions.get(&name) {
+            visited_functions.push(name);
+            used_names(&function.func, &mut names);
+        }
+    }
+
+    names.used.into_iter().map(|(name, _)| name).collect()
+}
+
+/// Follows the globals that haven't been initialised yet, starting at `start`, until one repeats
+fn find_cycle(globals: &[Global], placed: &[bool], start: usize) -> Vec<usize> {
+    let mut path = vec![start];
+
+    loop {
+        let current = *path.last().unwrap();
+
+        // every global that hasn't been placed is waiting on another one that hasn't been placed
+        let next = *globals[current].depends_on.iter().find(|&&dep| !placed[dep]).unwrap();
+
+        if let Some(position) = path.iter().position(|&g| g == next) {
+            let mut cycle = path.split_off(position);
+            cycle.push(next);
+            return cycle;
+        }
+
+        path.push(next);
+    }
+}
+
+/// Global declarations are initialised in `_start` one after the other, so a global has to come
+/// after every global its initialiser reads, either directly or inside the functions it calls.
+///
+/// Returns the global declarations sorted that way, followed by all the other statements in the
+/// order they were written. Globals that don't depend on each other keep their order
+pub fn order_globals(statements: &[ASTNode], functions: &Functions) -> Vec<ASTNode> {
+    let mut flattened = vec![];
+    flatten(statements, &mut flattened);
+
+    let (declarations, rest): (Vec<ASTNode>, Vec<ASTNode>) = flattened.into_iter().partition(|statement| {
+        matches!(
+            statement.borrow().get_node(),
+            ASTNodeEnum::DeclarationStatement(_) | ASTNodeEnum::MemoryAlloc(_)
+        )
+    });
+
+    let mut globals: Vec<Global> = declarations
+        .iter()
+        .map(|declaration| {
+            let borrow = declaration.borrow();
+
+            let variable = match borrow.get_node() {
+                ASTNodeEnum::DeclarationStatement(d) => d.get_variable().clone(),
+                ASTNodeEnum::MemoryAlloc(m) => m.get_variable().clone(),
+                _ => unreachable!(),
+            };
+
+            let variable = variable.borrow();
+
+            Global {
+                name: variable.var_name.clone(),
+                token: variable.get_token().clone(),
+                depends_on: vec![],
+            }
+        })
+        .collect();
+
+    let mut indices: HashMap<&str, Vec<usize>> = HashMap::new();
+
+    for (i, global) in globals.iter().enumerate() {
+        indices.entry(global.name.as_str()).or_default().push(i);
+    }
+
+    let depends_on: Vec<Vec<usize>> = declarations
+        .iter()
+        .map(|declaration| {
+            let mut depends_on: Vec<usize> = names_used_by(declaration, functions)
+                .iter()
+                .filter_map(|name| indices.get(name.as_str()))
+                .flatten()
+                .copied()
+                .collect();
+
+            depends_on.sort();
+            depends_on.dedup();
+            depends_on
+        })
+        .collect();
+
+    for (global, depends_on) in globals.iter_mut().zip(depends_on) {
+        global.depends_on = depends_on;
+    }
+
+    let mut placed = vec![false; globals.len()];
+    let mut ordered = vec![];
+
+    while ordered.len() < globals.len() {
+        let ready = (0..globals.len()).find(|&i| !placed[i] && globals[i].depends_on.iter().all(|&dep| placed[dep]));
+
+        match ready {
+            Some(i) => {
+                placed[i] = true;
+                ordered.push(declarations[i].clone());
+            }
+
+            None => {
+                let start = placed.iter().position(|p| !p).unwrap();
+                let cycle = find_cycle(&globals, &placed, start);
+
+                let names: Vec<&str> = cycle.iter().map(|&g| globals[g].name.as_str()).collect();
+
+                compiler_error(
+                    format!(
+                        "The initialisers of these global variables depend on each other: {}",
+                        names.join(" -> ")
+                    ),
+                    &globals[cycle[0]].token,
+                );
+            }
+        }
+    }
+
+    ordered.extend(rest);
+    ordered
+}
@@ -1 +1,6 @@
+pub mod control_flow;
+pub mod global_order;
 pub mod semantic_analyzer;
+pub mod symbols;
+pub mod unused;
+pub mod warnings;
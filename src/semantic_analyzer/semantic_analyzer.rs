@@ -2,16 +2,22 @@ use crate::{
     ast::{typedef::Typedef, variable::Variable},
     helpers::compiler_error,
     lexer::types::VarType,
-    parser::parser::UserDefinedType,
+    parser::parser::{Include, UserDefinedType},
     trace,
     types::ASTNode,
 };
 
+use super::{
+    symbols::{SymbolKind, Symbols},
+    unused::warn_unused_functions_and_includes,
+    warnings::{Warning, WarningOptions, Warnings},
+};
+
 use core::panic;
 use std::{cell::RefCell, cmp::min, collections::HashMap, process::exit, rc::Rc, usize};
 
 use crate::{
-    ast::abstract_syntax_tree::AST,
+    ast::abstract_syntax_tree::{ASTNodeEnumMut, AST},
     interpreter::interpreter::{Functions, Variables},
     lexer::tokens::VariableEnum,
 };
@@ -50,6 +56,11 @@ pub struct ActivationRecord {
     pub variable_members: ActivationRecordVariables,
     var_size_sum: usize,
     current_offset: usize,
+    /// Largest `var_size_sum` of a function, the size of its stack frame. The variables of a block
+    /// are freed when it ends, so blocks that come one after the other share their stack slots
+    max_var_size: usize,
+    /// `var_size_sum` of the function when this block started
+    scope_start: usize,
 }
 
 impl ActivationRecord {
@@ -60,6 +71,8 @@ impl ActivationRecord {
             variable_members: HashMap::new(),
             var_size_sum: 0,
             current_offset: 0,
+            max_var_size: 0,
+            scope_start: 0,
         }
     }
 }
@@ -71,6 +84,11 @@ pub struct CallStack<'a> {
     loop_number: usize,
     pub user_defined_types: &'a Vec<UserDefinedType>,
     pub type_aliases: &'a Vec<Typedef>,
+    pub warnings: Warnings,
+    /// The parameters and locals of the function being visited, including the ones declared in
+    /// blocks that have ended
+    function_variables: Vec<ARVariable>,
+    pub symbols: Symbols,
 }
 
 impl<'a> CallStack<'a> {
@@ -82,9 +100,29 @@ impl<'a> CallStack<'a> {
         return self.loop_number;
     }
 
+    /// The record of the function being visited
+    fn current_function_record(&mut self) -> Option<&mut ActivationRecord> {
+        let function_name = self.current_function_name.as_ref()?;
+
+        self.call_stack.iter_mut().rev().find(|record| {
+            matches!(record.record_type, ActivationRecordType::Function(..)) && &record.name == function_name
+        })
+    }
+
+    fn is_block(record: &ActivationRecord) -> bool {
+        matches!(record.record_type, ActivationRecordType::IfElse | ActivationRecordType::Loop)
+    }
+
     fn push_record(&mut self, mut record: ActivationRecord) {
         if let ActivationRecordType::Function(stack_var_size) = record.record_type {
             self.current_function_name = Some(record.name.clone());
+            self.function_variables.clear();
+        }
+
+        if Self::is_block(&record) {
+            if let Some(function) = self.current_function_record() {
+                record.scope_start = function.var_size_sum;
+            }
         }
 
         if let ActivationRecordType::Loop = record.record_type {
@@ -101,6 +139,15 @@ impl<'a> CallStack<'a> {
     pub fn pop(&mut self) {
         match self.call_stack.pop() {
             Some(record) => {
+                self.warn_unused_variables(&record);
+
+                // the stack slots of the variables declared in the block can be used again
+                if Self::is_block(&record) {
+                    if let Some(function) = self.current_function_record() {
+                        function.var_size_sum = record.scope_start;
+                    }
+                }
+
                 if let ActivationRecordType::Function(..) = record.record_type {
                     self.current_function_name = None;
                 }
@@ -114,6 +161,45 @@ impl<'a> CallStack<'a> {
         };
     }
 
+    fn warn_unused_variables(&mut self, record: &ActivationRecord) {
+        if let ActivationRecordType::Global = record.record_type {
+            return;
+        }
+
+        for variable in record.variable_members.values() {
+            let variable = variable.borrow();
+
+            // Variables the compiler makes up, like the bounds of a loop, aren't in the source.
+            // Starting the name with an underscore marks a variable as unused on purpose
+            if variable.is_used || variable.get_token().line_number == 0 || variable.var_name.starts_with('_') {
+                continue;
+            }
+
+            let (warning, kind) = if variable.is_parameter {
+                // a method doesn't have to use the value it's called on
+                if variable.var_name == "self" {
+                    continue;
+                }
+
+                (Warning::UnusedParameter, "Parameter")
+            } else {
+                (Warning::UnusedVariable, "Variable")
+            };
+
+            self.warnings.warn(
+                warning,
+                format!("{kind} '{}' is never used", variable.var_name),
+                variable.get_token(),
+            );
+        }
+    }
+
+    /// Swaps the record of the global variables for `globals` and returns the one it had. The REPL
+    /// analyses each input with a new call stack, and carries the globals over from the last one
+    pub fn replace_globals(&mut self, globals: ActivationRecord) -> ActivationRecord {
+        std::mem::replace(&mut self.call_stack[0], globals)
+    }
+
     pub fn peek(&mut self) -> Option<&ActivationRecord> {
         self.call_stack.last()
     }
@@ -129,10 +215,16 @@ impl<'a> CallStack<'a> {
         return false;
     }
 
+    /// Finding a variable counts as using it. A variable being declared is borrowed while it's
+    /// looked up, so that doesn't count
     pub fn get_var_with_name(&self, var_name: &String) -> (Option<&Rc<RefCell<Variable>>>, &ActivationRecordType) {
         for record in self.call_stack.iter().rev() {
             match record.variable_members.get(var_name) {
                 Some(var) => {
+                    if let Ok(mut var) = var.try_borrow_mut() {
+                        var.is_used = true;
+                    }
+
                     return (Some(var), &record.record_type);
                 }
 
@@ -216,6 +308,7 @@ impl<'a> CallStack<'a> {
                             }
 
                             record.var_size_sum = offset;
+                            record.max_var_size = record.max_var_size.max(offset);
                         }
                     }
 
@@ -231,11 +324,44 @@ impl<'a> CallStack<'a> {
         return offset;
     }
 
+    fn warn_shadowed_global(&mut self, variable: &Variable) {
+        // globals themselves
+        if self.call_stack.len() == 1 {
+            return;
+        }
+
+        if let Some(global) = self.call_stack[0].variable_members.get(&variable.var_name) {
+            let line = global.borrow().get_token().line_number;
+
+            self.warnings.warn(
+                Warning::ShadowedGlobal,
+                format!(
+                    "Variable '{}' shadows the global variable declared on line {line}",
+                    variable.var_name
+                ),
+                variable.get_token(),
+            );
+        }
+    }
+
     pub fn insert_variable(&mut self, mut variable: ActivationRecordVariablesValue) {
         variable.borrow_mut().offset = self.update_function_variable_size_and_get_offset(&variable);
 
         let var_name = &variable.borrow().var_name;
 
+        self.warn_shadowed_global(&variable.borrow());
+
+        self.symbols.define(
+            var_name,
+            SymbolKind::Variable,
+            variable.borrow().get_token(),
+            &variable.borrow().var_type,
+        );
+
+        if self.current_function_name.is_some() {
+            self.function_variables.push(Rc::clone(&variable));
+        }
+
         match self.call_stack.last_mut() {
             Some(last_record) => {
                 match last_record.variable_members.get(var_name) {
@@ -252,7 +378,21 @@ impl<'a> CallStack<'a> {
                     }
 
                     None => {
-                        if let VarType::Struct(struct_name, struct_members) = &variable.borrow().result_type {
+                        // The members are shared with the struct definition. A pointer to a struct
+                        // needs the offsets as well, it might point to memory from alloc with no
+                        // struct variable declared anywhere
+                        let struct_members = match &variable.borrow().result_type {
+                            VarType::Struct(_, struct_members) => Some(Rc::clone(struct_members)),
+
+                            VarType::Ptr(inner) => match &**inner {
+                                VarType::Struct(_, struct_members) => Some(Rc::clone(struct_members)),
+                                _ => None,
+                            },
+
+                            _ => None,
+                        };
+
+                        if let Some(struct_members) = struct_members {
                             if struct_members.borrow().len() == 0 {
                                 last_record
                                     .variable_members
@@ -291,10 +431,10 @@ impl<'a> CallStack<'a> {
                 panic!("Call stack is empty");
             }
         };
+    }
 
-        if var_name == "bb" {
-            trace!("insert_variable: {:#?}", variable.borrow());
-        }
+    pub fn function_variables(&self) -> &[ARVariable] {
+        &self.function_variables
     }
 
     pub fn get_func_var_stack_size(&self, function_name: &String) -> usize {
@@ -304,10 +444,10 @@ impl<'a> CallStack<'a> {
                     // TODO: Implement a proper fix for this
                     // Since we set the initial offset to 8 (i.e. the largest size a variable can
                     // have) if we have int8 or int16, then the offset goes beyond the record.var_size_sum
-                    return if record.var_size_sum >= 8 {
-                        record.var_size_sum
+                    return if record.max_var_size >= 8 {
+                        record.max_var_size
                     } else {
-                        record.var_size_sum + 8
+                        record.max_var_size + 8
                     };
                 }
             }
@@ -321,6 +461,7 @@ pub struct SemanticAnalyzer<'a> {
     pub call_stack: CallStack<'a>,
     pub ast: ASTNode,
     pub functions: Rc<RefCell<Functions>>,
+    includes: &'a Vec<Include>,
 }
 
 impl<'a> SemanticAnalyzer<'a> {
@@ -329,6 +470,8 @@ impl<'a> SemanticAnalyzer<'a> {
         functions: Rc<RefCell<Functions>>,
         user_defined_types: &'a Vec<UserDefinedType>,
         type_aliases: &'a Vec<Typedef>,
+        includes: &'a Vec<Include>,
+        warning_options: WarningOptions,
     ) -> Self {
         Self {
             call_stack: CallStack {
@@ -337,15 +480,36 @@ impl<'a> SemanticAnalyzer<'a> {
                 loop_number: 0,
                 user_defined_types,
                 type_aliases,
+                warnings: Warnings::new(warning_options),
+                function_variables: vec![],
+                symbols: Symbols::default(),
             },
             ast,
             functions,
+            includes,
         }
     }
 
     pub fn analyze(&mut self) {
+        self.check();
+        self.call_stack.warnings.report();
+    }
+
+    /// Analyzes the program without printing the warnings, they're left in `call_stack.warnings`
+    pub fn check(&mut self) {
+        if let ASTNodeEnumMut::Program(program) = self.ast.borrow_mut().get_node_mut() {
+            program.order_globals(&self.functions.borrow());
+        }
+
         self.ast
             .borrow_mut()
             .semantic_visit(&mut self.call_stack, Rc::clone(&self.functions));
+
+        warn_unused_functions_and_includes(
+            &self.ast,
+            &self.functions.borrow(),
+            self.includes,
+            &mut self.call_stack.warnings,
+        );
     }
 }
@@ -0,0 +1,95 @@
+use crate::lexer::{lexer::Token, types::VarType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Variable,
+    Function,
+    /// Structs, enums, unions and typedefs
+    Type,
+}
+
+/// Where a variable, function or type is defined
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub token: Token,
+    pub var_type: VarType,
+}
+
+/// Every name in the program and what it refers to, along with the type of every expression. Only
+/// the language server reads these
+#[derive(Debug, Default)]
+pub struct Symbols {
+    pub definitions: Vec<Definition>,
+    /// (where a name is written, where what it refers to is defined). Definitions refer to
+    /// themselves
+    pub references: Vec<(Token, Token)>,
+    pub types: Vec<(Token, VarType)>,
+}
+
+/// Tokens made up by the compiler, like the bounds of a loop, aren't in any file
+fn is_in_source(token: &Token) -> bool {
+    token.line_number != 0
+}
+
+fn same_place(a: &Token, b: &Token) -> bool {
+    a.start == b.start && a.file == b.file
+}
+
+impl Symbols {
+    /// Generic functions are analysed once for every instance, so the same definition can be added
+    /// more than once
+    pub fn define(&mut self, name: &str, kind: SymbolKind, token: &Token, var_type: &VarType) {
+        if !is_in_source(token) || self.definitions.iter().any(|d| same_place(&d.token, token)) {
+            return;
+        }
+
+        self.definitions.push(Definition {
+            name: name.into(),
+            kind,
+            token: token.clone(),
+            var_type: var_type.clone(),
+        });
+
+        self.refer(token, token);
+        self.expression_type(token, var_type);
+    }
+
+    pub fn refer(&mut self, token: &Token, definition: &Token) {
+        if !is_in_source(token) || self.references.iter().any(|(used, _)| same_place(used, token)) {
+            return;
+        }
+
+        self.references.push((token.clone(), definition.clone()));
+    }
+
+    /// Names of types are only known by the parser, `token` refers to the type called `name` if
+    /// there is one
+    pub fn refer_to_type(&mut self, token: &Token, name: &str) {
+        let definition = self
+            .definitions
+            .iter()
+            .find(|d| d.kind == SymbolKind::Type && d.name == name)
+            .map(|d| d.token.clone());
+
+        if let Some(definition) = definition {
+            self.refer(token, &definition);
+        }
+    }
+
+    pub fn expression_type(&mut self, token: &Token, var_type: &VarType) {
+        if !is_in_source(token) || self.types.iter().any(|(t, _)| same_place(t, token)) {
+            return;
+        }
+
+        self.types.push((token.clone(), var_type.clone()));
+    }
+
+    /// Adds the symbols the parser found to the ones from semantic analysis
+    pub fn extend(&mut self, other: Symbols) {
+        self.definitions.extend(other.definitions);
+        self.references.extend(other.references);
+        self.types.extend(other.types);
+    }
+}
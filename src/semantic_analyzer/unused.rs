@@ -0,0 +1,120 @@
+use crate::{
+    ast::abstract_syntax_tree::{used_names, ASTNodeEnum, Names, AST},
+    interpreter::interpreter::Functions,
+    lexer::lexer::Token,
+    parser::{parse_test::is_test_function, parser::Include},
+    types::ASTNode,
+};
+
+use super::warnings::{Warning, Warnings};
+
+/// Everything used by `main`, the tests and the global initialisers, and by the functions they call
+fn used_by_program(statements: &[ASTNode], functions: &Functions) -> Vec<String> {
+    let mut names = Names::default();
+
+    for statement in statements {
+        if let ASTNodeEnum::DeclarationStatement(_) | ASTNodeEnum::MemoryAlloc(_) = statement.borrow().get_node() {
+            used_names(statement, &mut names);
+        }
+    }
+
+    let mut to_visit: Vec<String> = names.used.into_iter().map(|(name, _)| name).collect();
+    to_visit.push("main".into());
+    to_visit.extend(functions.keys().filter(|name| is_test_function(name)).cloned());
+
+    let mut used: Vec<String> = vec![];
+
+    while let Some(name) = to_visit.pop() {
+        if used.contains(&name) {
+            continue;
+        }
+
+        if let Some(function) = functions.get(&name) {
+            let mut names = Names::default();
+            used_names(&function.func, &mut names);
+
+            to_visit.extend(names.used.into_iter().map(|(name, _)| name));
+        }
+
+        used.push(name);
+    }
+
+    used
+}
+
+/// Warns about functions that are never called, and included files nothing is used from.
+/// Functions of included files are only reported as part of the include
+pub fn warn_unused_functions_and_includes(
+    program: &ASTNode,
+    functions: &Functions,
+    includes: &[Include],
+    warnings: &mut Warnings,
+) {
+    // no main is an error of its own, unless the file only has tests
+    if !functions.contains_key("main") && !functions.keys().any(|name| is_test_function(name)) {
+        return;
+    }
+
+    let program = program.borrow();
+
+    let ASTNodeEnum::Program(program) = program.get_node() else {
+        return;
+    };
+
+    let used = used_by_program(program.get_statements(), functions);
+
+    let is_included = |token: &Token| includes.iter().any(|include| include.file == token.file);
+
+    let mut unused_functions: Vec<(Token, &String)> = functions
+        .iter()
+        .filter(|(name, _)| !used.contains(name))
+        .filter_map(|(name, function)| match function.func.borrow().get_node() {
+            ASTNodeEnum::FunctionDef(_) => Some((function.func.borrow().get_token().clone(), name)),
+            _ => None,
+        })
+        .filter(|(token, _)| !is_included(token))
+        .collect();
+
+    unused_functions.sort_by_key(|(token, _)| (token.line_number, token.col_number));
+
+    for (token, name) in unused_functions {
+        warnings.warn(Warning::UnusedFunction, format!("Function '{name}' is never called"), &token);
+    }
+
+    let globals: Vec<Token> = program
+        .get_statements()
+        .iter()
+        .filter_map(|statement| {
+            let variable = match statement.borrow().get_node() {
+                ASTNodeEnum::DeclarationStatement(declaration) => declaration.get_variable().clone(),
+                ASTNodeEnum::MemoryAlloc(memory_alloc) => memory_alloc.get_variable().clone(),
+                _ => return None,
+            };
+
+            let variable = variable.borrow();
+
+            used.contains(&variable.var_name).then(|| variable.get_token().clone())
+        })
+        .collect();
+
+    for include in includes {
+        // types can be used anywhere, we don't keep track of them
+        if include.declares_types {
+            continue;
+        }
+
+        let uses_function = used.iter().filter_map(|name| functions.get(name)).any(|function| {
+            function.func.borrow().get_token().file == include.file
+        });
+
+        let uses_global = globals.iter().any(|token| token.file == include.file);
+
+        if !uses_function && !uses_global {
+            warnings.warn(
+                Warning::UnusedInclude,
+                format!("Nothing from '{}' is used", include.file),
+                &include.token,
+            );
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::{fmt::Display, process::exit};
+
+use crate::{helpers::SourceLocation, lexer::lexer::Token};
+
+/// Everything the compiler can warn about. Each one is turned on with `-W name` and off with
+/// `-A name`, and they're all on by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Warning {
+    UnusedVariable,
+    UnusedParameter,
+    UnusedFunction,
+    UnusedInclude,
+    ShadowedGlobal,
+    UnreachableCode,
+    /// A char is never negative, so comparing it with a negative number always has the same result
+    UnsignedNegativeComparison,
+    AlwaysTrueCondition,
+    AlwaysFalseCondition,
+}
+
+impl Warning {
+    pub const ALL: [Warning; 9] = [
+        Warning::UnusedVariable,
+        Warning::UnusedParameter,
+        Warning::UnusedFunction,
+        Warning::UnusedInclude,
+        Warning::ShadowedGlobal,
+        Warning::UnreachableCode,
+        Warning::UnsignedNegativeComparison,
+        Warning::AlwaysTrueCondition,
+        Warning::AlwaysFalseCondition,
+    ];
+
+    /// The name used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Warning::UnusedVariable => "unused-variable",
+            Warning::UnusedParameter => "unused-parameter",
+            Warning::UnusedFunction => "unused-function",
+            Warning::UnusedInclude => "unused-include",
+            Warning::ShadowedGlobal => "shadowed-global",
+            Warning::UnreachableCode => "unreachable-code",
+            Warning::UnsignedNegativeComparison => "unsigned-negative-comparison",
+            Warning::AlwaysTrueCondition => "always-true-condition",
+            Warning::AlwaysFalseCondition => "always-false-condition",
+        }
+    }
+}
+
+/// Which warnings are reported, set with -W, -A and -Werror
+#[derive(Debug, Clone)]
+pub struct WarningOptions {
+    enabled: Vec<Warning>,
+    /// -Werror, any warning fails the compilation
+    pub as_errors: bool,
+}
+
+impl Default for WarningOptions {
+    fn default() -> Self {
+        Self {
+            enabled: Warning::ALL.to_vec(),
+            as_errors: false,
+        }
+    }
+}
+
+impl WarningOptions {
+    /// Every warning turned off
+    pub fn none() -> Self {
+        Self {
+            enabled: vec![],
+            as_errors: false,
+        }
+    }
+
+    /// Turns the warning called `name` on or off, `all` means every warning. Returns false if
+    /// there's no warning with that name
+    pub fn set(&mut self, name: &str, enabled: bool) -> bool {
+        let warnings: Vec<Warning> = if name == "all" {
+            Warning::ALL.to_vec()
+        } else {
+            match Warning::ALL.iter().find(|w| w.name() == name) {
+                Some(warning) => vec![*warning],
+                None => return false,
+            }
+        };
+
+        self.enabled.retain(|w| !warnings.contains(w));
+
+        if enabled {
+            self.enabled.extend(warnings);
+        }
+
+        true
+    }
+
+    pub fn is_enabled(&self, warning: Warning) -> bool {
+        self.enabled.contains(&warning)
+    }
+}
+
+/// A warning that's enabled, and where it is
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WarningMessage {
+    pub location: SourceLocation,
+    pub message: String,
+}
+
+/// Warnings found during semantic analysis. They're printed once the analysis is done, so errors
+/// are always the first thing printed
+#[derive(Debug, Default)]
+pub struct Warnings {
+    options: WarningOptions,
+    /// Reported sorted by where they are
+    messages: Vec<WarningMessage>,
+}
+
+impl Warnings {
+    pub fn new(options: WarningOptions) -> Self {
+        Self {
+            options,
+            messages: vec![],
+        }
+    }
+
+    pub fn warn<S: AsRef<str> + Display>(&mut self, warning: Warning, message: S, tok: &Token) {
+        if !self.options.is_enabled(warning) {
+            return;
+        }
+
+        let message = WarningMessage {
+            location: tok.into(),
+            message: format!("{message} [{}]", warning.name()),
+        };
+
+        // generic functions are analysed once for every instance
+        if !self.messages.contains(&message) {
+            self.messages.push(message);
+        }
+    }
+
+    pub fn messages(&self) -> &[WarningMessage] {
+        &self.messages
+    }
+
+    pub fn report(&mut self) {
+        self.messages.sort();
+
+        for WarningMessage { location, message } in &self.messages {
+            eprintln!(
+                "{}:{}:{} warning: {message}",
+                location.file, location.line_number, location.col_number
+            );
+        }
+
+        if self.options.as_errors && !self.messages.is_empty() {
+            eprintln!(
+                "{} warning(s) treated as errors because of -Werror",
+                self.messages.len()
+            );
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::{env, fs, process::Command, rc::Rc};
+
+use crate::{
+    generate_asm,
+    interpreter::interpreter::Interpreter,
+    options::CompilerOptions,
+    parser::parser::Parser,
+    semantic_analyzer::{semantic_analyzer::SemanticAnalyzer, warnings::WarningOptions},
+};
+
+/// Where `build` puts the binary that runs the tests
+pub const TEST_BINARY: &str = "./generated/output";
+
+/// `lang test FILE` runs the tests of FILE and of the files it includes, each in a process of its
+/// own. Returns the exit code, which is 1 if any test didn't pass
+pub fn run(args: &[String]) -> i32 {
+    let [path] = args else {
+        eprintln!("Usage: lang test FILE");
+        return 1;
+    };
+
+    if !build(path) {
+        return 1;
+    }
+
+    match Command::new(TEST_BINARY).status() {
+        Ok(status) => status.code().unwrap_or(1),
+
+        Err(err) => {
+            eprintln!("Failed to run the tests: {err}");
+            1
+        }
+    }
+}
+
+/// Compiles the tests of the file at `path` into `TEST_BINARY`. The file doesn't need a main.
+/// Returns whether the binary was built
+pub fn build(path: &str) -> bool {
+    let file = match fs::read(path) {
+        Ok(file) => file,
+
+        Err(err) => {
+            eprintln!("Failed to read `{path}`: {err}");
+            return false;
+        }
+    };
+
+    let mut parser = Parser::new(file, path.into());
+    let ast = parser.parse_program();
+
+    let mut semantic_analyzer = SemanticAnalyzer::new(
+        Rc::clone(&ast),
+        Rc::clone(&parser.functions),
+        &parser.user_defined_types,
+        &parser.type_aliases,
+        &parser.includes,
+        WarningOptions::default(),
+    );
+    semantic_analyzer.analyze();
+
+    let mut interpreter = Interpreter::new(
+        Rc::clone(&ast),
+        Rc::clone(&parser.functions),
+        &CompilerOptions::default(),
+    );
+
+    // only used for its call stack, the warnings have been reported already
+    let mut semantic_analyzer = SemanticAnalyzer::new(
+        ast,
+        Rc::clone(&parser.functions),
+        &parser.user_defined_types,
+        &parser.type_aliases,
+        &parser.includes,
+        WarningOptions::default(),
+    );
+
+    interpreter.compile_tests(&mut semantic_analyzer.call_stack, &parser.tests);
+
+    let current_dir = env::current_dir().unwrap();
+    env::set_current_dir("./generated").unwrap();
+
+    let result = generate_asm(&vec![], false);
+
+    env::set_current_dir(current_dir).unwrap();
+
+    if let Err(err) = result {
+        eprintln!("Failed to generate asm: {:?}", err);
+        return false;
+    }
+
+    true
+}
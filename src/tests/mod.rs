@@ -1,2 +1,6 @@
 pub mod test_compiler;
+pub mod test_dump;
 pub mod test_errors;
+pub mod test_formatter;
+pub mod test_lsp;
+pub mod test_repl;
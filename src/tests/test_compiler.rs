@@ -1,9 +1,14 @@
 use std::{
     fs::{self},
-    io::Read, process::exit,
+    io::Read,
+    process::{exit, Command},
 };
 
-use crate::parse_input_file;
+use crate::{
+    options::CompilerOptions,
+    parse_input_file,
+    testing::testing::{build, TEST_BINARY},
+};
 
 pub fn get_file_result(file_name: &str) -> String {
     let file_name_wo_ext = file_name.split('.').collect::<Vec<&str>>();
@@ -19,15 +24,23 @@ pub fn get_file_result(file_name: &str) -> String {
 }
 
 pub fn get_stdout_and_actual_result(file_name: &str) -> (String, String, String) {
+    let (stdout_str, stderr_str) = get_stdout(file_name, &CompilerOptions::default());
+
+    return (stdout_str, stderr_str, get_file_result(file_name));
+}
+
+pub fn get_stdout(file_name: &str, options: &CompilerOptions) -> (String, String) {
     let mut stdout_str = String::new();
     let mut stderr_str = String::new();
 
-    if let Some(ref mut stdout) = parse_input_file(format!("./examples/{}", file_name), true, true, true, &vec![]) {
+    if let Some(ref mut stdout) =
+        parse_input_file(format!("./examples/{}", file_name), true, true, true, &vec![], options)
+    {
         stdout.0.read_to_string(&mut stdout_str);
         stdout.1.read_to_string(&mut stderr_str);
     }
 
-    return (stdout_str, stderr_str, get_file_result(file_name));
+    (stdout_str, stderr_str)
 }
 
 #[test]
@@ -221,3 +234,230 @@ fn decleration_only() {
     let (stdout_str, _, file_result) = get_stdout_and_actual_result("decleration_only.cy");
     assert_eq!(stdout_str, file_result);
 }
+
+#[test]
+fn enums() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("enums.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn unions() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("unions.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn generics() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("generics.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn methods() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("methods.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn type_inference() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("type_inference.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn print_numbers() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("print_numbers.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn float_precision() {
+    let options = CompilerOptions {
+        float_precision: 2,
+        ..Default::default()
+    };
+
+    let (stdout_str, _) = get_stdout("float_arithmetic.cy", &options);
+    assert_eq!(stdout_str, get_file_result("float_arithmetic_precision.cy"));
+}
+
+#[test]
+fn conversions() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("conversions.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn overflow() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("overflow.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn overflow_checked_casts() {
+    let options = CompilerOptions {
+        checked_casts: true,
+        ..Default::default()
+    };
+
+    let (stdout_str, stderr_str) = get_stdout("overflow.cy", &options);
+    assert_eq!(stdout_str, get_file_result("overflow_checked_casts.cy"));
+    assert_eq!(stderr_str, "Cast lost information, value does not fit in the new type\n");
+}
+
+#[test]
+fn float_types() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("float_types.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn heap_alloc() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("heap_alloc.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn heap_debug() {
+    let options = CompilerOptions {
+        debug_alloc: true,
+        ..Default::default()
+    };
+
+    let (stdout_str, stderr_str) = get_stdout("heap_debug.cy", &options);
+    assert_eq!(stdout_str, get_file_result("heap_debug.cy"));
+    assert_eq!(stderr_str, "Leaked 2 block(s), 10016 byte(s)\n");
+
+    let (stdout_str, stderr_str) = get_stdout("heap_double_free.cy", &options);
+    assert_eq!(stdout_str, get_file_result("heap_double_free.cy"));
+    assert_eq!(
+        stderr_str,
+        "Invalid free, the pointer was already freed or wasn't returned by alloc\n"
+    );
+}
+
+#[test]
+fn owned_string() {
+    let options = CompilerOptions {
+        debug_alloc: true,
+        ..Default::default()
+    };
+
+    // temporaries are freed, so nothing leaks
+    let (stdout_str, stderr_str) = get_stdout("owned_string.cy", &options);
+    assert_eq!(stdout_str, get_file_result("owned_string.cy"));
+    assert_eq!(stderr_str, "");
+}
+
+#[test]
+fn print_format() {
+    let (stdout_str, stderr_str) = get_stdout("print_format.cy", &CompilerOptions::default());
+    assert_eq!(stdout_str, get_file_result("print_format.cy"));
+    assert_eq!(stderr_str, "error: lang went wrong\n");
+}
+
+#[test]
+fn buffered_io() {
+    let options = CompilerOptions {
+        debug_alloc: true,
+        ..Default::default()
+    };
+
+    let (stdout_str, stderr_str) = get_stdout("buffered_io.cy", &options);
+    assert_eq!(stdout_str, get_file_result("buffered_io.cy"));
+    assert_eq!(stderr_str, "");
+}
+
+#[test]
+fn command_line_args() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("command_line_args.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn entry_point() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("entry_point.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn global_initialisers() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("global_initialisers.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn control_flow() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("control_flow.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn block_scope() {
+    let (stdout_str, _, file_result) = get_stdout_and_actual_result("block_scope.cy");
+    assert_eq!(stdout_str, file_result);
+}
+
+#[test]
+fn debug_info() {
+    let options = CompilerOptions {
+        debug_info: true,
+        ..Default::default()
+    };
+
+    // debug info doesn't change what the program does
+    let (stdout_str, _) = get_stdout("block_scope.cy", &options);
+    assert_eq!(stdout_str, get_file_result("block_scope.cy"));
+}
+
+#[test]
+fn emit_asm() {
+    let options = CompilerOptions {
+        emit_asm: true,
+        ..Default::default()
+    };
+
+    // only the assembly is written, nothing runs
+    let (stdout_str, _) = get_stdout("block_scope.cy", &options);
+    assert_eq!(stdout_str, "");
+
+    let asm = fs::read_to_string("./generated/output.asm").unwrap();
+    assert!(asm.contains(";; ./examples/block_scope.cy:11: break;\n\t;; --- break ----\n\tjmp .loop_end_0"));
+}
+
+#[test]
+fn debug_info_sections() {
+    let options = CompilerOptions {
+        debug_info: true,
+        emit_asm: true,
+        ..Default::default()
+    };
+
+    get_stdout("block_scope.cy", &options);
+
+    let asm = fs::read_to_string("./generated/output.asm").unwrap();
+    assert!(asm.contains("section .debug_info noalloc noexec nowrite progbits align=1\n\t..@debug_info:"));
+    assert!(asm.contains("section .debug_line noalloc noexec nowrite progbits align=1\n\t..@debug_line:"));
+
+    // the abbreviation of a DW_TAG_subprogram with children, and the entry of main using it
+    assert!(asm.contains("db 2, 46, 1\n"));
+    assert!(asm.contains(";; function main\n\tdb 2\n\tdb \"main\", 0\n\tdb 1\n\tdd 20\n\tdq _main\n\tdq ..@_main_end"));
+}
+
+#[test]
+fn test_blocks() {
+    assert!(build("./examples/testing/tests.cy"));
+
+    let output = Command::new(TEST_BINARY).output().unwrap();
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        get_file_result("testing/tests.cy")
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "./examples/testing/tests.cy:16: assertion failed: 2 squared is not 5\n"
+    );
+    assert_eq!(output.status.code(), Some(1));
+}
@@ -0,0 +1,60 @@
+use crate::{
+    dump::{ast_json, tokens_json},
+    json::Json,
+};
+
+const PATH: &str = "dump.cy";
+
+#[test]
+fn dump_tokens() {
+    let json = tokens_json("def x: int = 2 -- two\n", PATH).unwrap();
+
+    assert_eq!(
+        json.to_string(),
+        concat!(
+            r#"{"file":"dump.cy","tokens":["#,
+            r#"{"kind":"Keyword","text":"def","line":1,"start":0,"end":3},"#,
+            r#"{"kind":"Variable","text":"x","line":1,"start":4,"end":5},"#,
+            r#"{"kind":"Colon","text":":","line":1,"start":5,"end":6},"#,
+            r#"{"kind":"Type","text":"int","line":1,"start":7,"end":10},"#,
+            r#"{"kind":"Equals","text":"=","line":1,"start":11,"end":12},"#,
+            r#"{"kind":"Number","text":"2","line":1,"start":13,"end":14},"#,
+            r#"{"kind":"Comment","text":"-- two","line":1,"start":15,"end":21}"#,
+            "]}"
+        )
+    );
+}
+
+#[test]
+fn dump_ast() {
+    let source = "fun main() {\n    write(1.5 + 2.5)\n}\n";
+    let json = ast_json(source, PATH).unwrap();
+
+    let main = &json.get("statements").as_array()[0];
+    assert_eq!(main.get("kind").as_str(), Some("FunctionDef"));
+    assert_eq!(main.get("name").as_str(), Some("main"));
+
+    let call = &main.get("block").get("statements").as_array()[0];
+    assert_eq!(call.get("kind").as_str(), Some("FunctionCall"));
+
+    // from the name of the function to the last argument
+    let span = call.get("span");
+    let (start, end) = (span.get("start").as_u64().unwrap(), span.get("end").as_u64().unwrap());
+
+    assert_eq!(&source[start as usize..end as usize], "write(1.5 + 2.5");
+    assert_eq!(span.get("line").as_u64(), Some(2));
+
+    let sum = &call.get("arguments").as_array()[0];
+    assert_eq!(sum.get("kind").as_str(), Some("BinaryOp"));
+    assert_eq!(sum.get("operator").as_str(), Some("+"));
+    assert_eq!(sum.get("type").as_str(), Some("float"));
+    assert_eq!(sum.get("right").get("value"), &Json::Number(2.5));
+}
+
+#[test]
+fn dump_ast_error() {
+    let error = ast_json("fun main() {\n    write(y)\n}\n", PATH).unwrap_err();
+
+    assert_eq!(error.message, "Variable with name 'y' not found in current scope");
+    assert_eq!(error.location.unwrap().line_number, 2);
+}
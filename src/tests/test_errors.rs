@@ -18,8 +18,13 @@ fn compile_binary() {
 }
 
 fn try_to_compile_example(file_name: &str) -> (String, String) {
+    try_to_compile_example_with_args(file_name, &[])
+}
+
+fn try_to_compile_example_with_args(file_name: &str, args: &[&str]) -> (String, String) {
     let command = Command::new("./target/debug/lang")
         .args(["-f".into(), format!("./examples/{}", file_name)])
+        .args(args)
         .stderr(Stdio::piped())
         .spawn();
 
@@ -75,3 +80,179 @@ fn function_no_statements() {
 
     assert_eq!(stderr, file_result);
 }
+
+#[test]
+fn match_not_exhaustive() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/match_not_exhaustive.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn union_wrong_payload() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/union_wrong_payload.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn generic_type_arguments() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/generic_type_arguments.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn method_not_found() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/method_not_found.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn infer_memory_block() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/infer_memory_block.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn invalid_cast() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/invalid_cast.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn math_int_argument() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/math_int_argument.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn free_non_pointer() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/free_non_pointer.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn slice_non_string() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/slice_non_string.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn print_wrong_arg_count() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/print_wrong_arg_count.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn read_line_non_int() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/read_line_non_int.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn main_wrong_signature() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/main_wrong_signature.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn statement_outside_function() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/statement_outside_function.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn global_initialiser_cycle() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/global_initialiser_cycle.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn func_path_no_ret() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/func_path_no_ret.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn use_before_assignment() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/use_before_assignment.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn warnings_as_errors() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example_with_args("errors/warnings_as_errors.cy", &["-Werror"]);
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn assert_non_literal_message() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/assert_non_literal_message.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn test_inside_function() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/test_inside_function.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn builtin_redefined() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/builtin_redefined.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn union_struct_member() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/union_struct_member.cy");
+
+    assert_eq!(stderr, file_result);
+}
+
+#[test]
+fn slice_of_structs() {
+    compile_binary();
+    let (stderr, file_result) = try_to_compile_example("errors/slice_of_structs.cy");
+
+    assert_eq!(stderr, file_result);
+}
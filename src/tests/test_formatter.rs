@@ -0,0 +1,59 @@
+use std::fs;
+
+use crate::{
+    formatter::formatter::{format_source, run},
+    lsp::analysis::{analyze, Severity},
+};
+
+const UNFORMATTED: &str = "./examples/fmt/unformatted.cy";
+const FORMATTED: &str = "./examples/output/fmt/unformatted.cy";
+
+fn examples() -> Vec<String> {
+    let mut examples: Vec<String> = fs::read_dir("./examples")
+        .unwrap()
+        .map(|entry| entry.unwrap().path().to_string_lossy().into_owned())
+        .filter(|path| path.ends_with(".cy"))
+        .collect();
+
+    examples.sort();
+    examples
+}
+
+fn has_errors(text: &str, path: &str) -> bool {
+    analyze(text, path)
+        .diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+}
+
+#[test]
+fn fmt_unformatted() {
+    let source = fs::read_to_string(UNFORMATTED).unwrap();
+    let expected = fs::read_to_string(FORMATTED).unwrap();
+
+    assert_eq!(format_source(&source, UNFORMATTED).unwrap(), expected);
+}
+
+#[test]
+fn fmt_examples() {
+    for path in examples() {
+        let source = fs::read_to_string(&path).unwrap();
+        let formatted = format_source(&source, &path).unwrap();
+
+        // formatting twice changes nothing
+        assert_eq!(format_source(&formatted, &path).unwrap(), formatted, "{path}");
+
+        if !has_errors(&source, &path) {
+            assert!(!has_errors(&formatted, &path), "{path}");
+        }
+    }
+}
+
+#[test]
+fn fmt_check() {
+    let args = |path: &str| vec!["--check".to_string(), path.to_string()];
+
+    assert_eq!(run(&args(UNFORMATTED)), 1);
+    assert_eq!(run(&args(FORMATTED)), 0);
+    assert_eq!(run(&[]), 1);
+}
@@ -0,0 +1,196 @@
+use std::io::Cursor;
+
+use crate::{
+    json::Json,
+    lsp::server::{path_to_uri, Server},
+};
+
+const PATH: &str = "./examples/ptr_to_struct.cy";
+
+fn message(id: Option<usize>, method: &str, params: Json) -> String {
+    let mut members = vec![("jsonrpc".into(), "2.0".into())];
+
+    if let Some(id) = id {
+        members.push(("id".into(), id.into()));
+    }
+
+    members.push(("method".into(), method.into()));
+    members.push(("params".into(), params));
+
+    let content = Json::Object(members).to_string();
+
+    format!("Content-Length: {}\r\n\r\n{content}", content.len())
+}
+
+fn position(line: usize, character: usize) -> Json {
+    Json::object([
+        ("textDocument", Json::object([("uri", path_to_uri(PATH).into())])),
+        (
+            "position",
+            Json::object([("line", line.into()), ("character", character.into())]),
+        ),
+    ])
+}
+
+fn references(line: usize, character: usize) -> Json {
+    let Json::Object(mut params) = position(line, character) else {
+        unreachable!()
+    };
+
+    let context = Json::object([("includeDeclaration", true.into())]);
+    params.push(("context".into(), context));
+
+    Json::Object(params)
+}
+
+fn did_open(text: &str) -> String {
+    let document = Json::object([
+        ("uri", path_to_uri(PATH).into()),
+        ("languageId", "cberk".into()),
+        ("version", 1.into()),
+        ("text", text.into()),
+    ]);
+
+    message(None, "textDocument/didOpen", Json::object([("textDocument", document)]))
+}
+
+fn did_change(text: &str) -> String {
+    let params = Json::object([
+        ("textDocument", Json::object([("uri", path_to_uri(PATH).into())])),
+        ("contentChanges", vec![Json::object([("text", text.into())])].into()),
+    ]);
+
+    message(None, "textDocument/didChange", params)
+}
+
+/// Plays the messages to a server the way an editor would, starting with `initialize` and ending
+/// with `shutdown` and `exit`. Returns everything the server sent back
+fn session(messages: &[String]) -> Vec<Json> {
+    let mut input = message(Some(0), "initialize", Json::object([]));
+    input += &message(None, "initialized", Json::object([]));
+
+    for message in messages {
+        input += message;
+    }
+
+    input += &message(Some(1000), "shutdown", Json::Null);
+    input += &message(None, "exit", Json::Null);
+
+    let mut server = Server::new(Cursor::new(input.into_bytes()), vec![]);
+    assert_eq!(server.run(), 0);
+
+    let output = String::from_utf8(server.output().clone()).unwrap();
+
+    output
+        .split("Content-Length: ")
+        .skip(1)
+        .map(|message| Json::parse(message.split_once("\r\n\r\n").unwrap().1).unwrap())
+        .collect()
+}
+
+fn response(messages: &[Json], id: usize) -> &Json {
+    messages
+        .iter()
+        .find(|message| message.get("id").as_u64() == Some(id as u64))
+        .map(|message| message.get("result"))
+        .unwrap()
+}
+
+fn diagnostics(messages: &[Json]) -> Vec<&Json> {
+    messages
+        .iter()
+        .filter(|message| message.get("method").as_str() == Some("textDocument/publishDiagnostics"))
+        .map(|message| message.get("params").get("diagnostics"))
+        .collect()
+}
+
+/// (line, character) where a range starts
+fn start(range: &Json) -> (u64, u64) {
+    let start = range.get("start");
+
+    (
+        start.get("line").as_u64().unwrap(),
+        start.get("character").as_u64().unwrap(),
+    )
+}
+
+#[test]
+fn lsp_definition_and_references() {
+    let text = std::fs::read_to_string(PATH).unwrap();
+
+    let messages = session(&[
+        did_open(&text),
+        // `a` in `&a`
+        message(Some(1), "textDocument/definition", position(11, 18)),
+        // `A` in `def b: *A`
+        message(Some(2), "textDocument/definition", position(11, 12)),
+        // every use of the `b` declared on line 12
+        message(Some(3), "textDocument/references", references(11, 8)),
+    ]);
+
+    // the program is fine
+    assert_eq!(diagnostics(&messages), vec![&Json::Array(vec![])]);
+
+    assert_eq!(start(response(&messages, 1).get("range")), (6, 8));
+    assert_eq!(start(response(&messages, 2).get("range")), (0, 7));
+
+    let references: Vec<(u64, u64)> = response(&messages, 3)
+        .as_array()
+        .iter()
+        .map(|location| start(location.get("range")))
+        .collect();
+
+    assert_eq!(references, vec![(11, 8), (13, 10), (14, 10)]);
+}
+
+#[test]
+fn lsp_hover() {
+    let text = std::fs::read_to_string(PATH).unwrap();
+
+    let messages = session(&[
+        did_open(&text),
+        message(Some(1), "textDocument/hover", position(11, 8)),
+        // `val2` in `b.val2`
+        message(Some(2), "textDocument/hover", position(14, 13)),
+    ]);
+
+    let hover = |id| response(&messages, id).get("contents").get("value").as_str().unwrap();
+
+    assert_eq!(hover(1), "b: *A");
+    assert_eq!(hover(2), "val2: str");
+}
+
+#[test]
+fn lsp_diagnostics_and_completion() {
+    let text = std::fs::read_to_string(PATH).unwrap();
+    let unfinished = text.replace("write(b.val2)", "write(b.val2)\n    b.");
+
+    let messages = session(&[
+        did_open(&text),
+        did_change(&unfinished),
+        message(Some(1), "textDocument/completion", position(15, 6)),
+        message(Some(2), "textDocument/unknown", Json::object([])),
+    ]);
+
+    // the half written line is an error, it doesn't stop the server
+    let errors = diagnostics(&messages)[1].as_array();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].get("severity").as_u64(), Some(1));
+    assert_eq!(start(errors[0].get("range")), (16, 0));
+
+    let completions: Vec<(&str, &str)> = response(&messages, 1)
+        .as_array()
+        .iter()
+        .map(|item| {
+            (
+                item.get("label").as_str().unwrap(),
+                item.get("detail").as_str().unwrap(),
+            )
+        })
+        .collect();
+
+    assert_eq!(completions, vec![("val", "int"), ("val2", "str")]);
+
+    let unknown = messages.iter().find(|m| m.get("id").as_u64() == Some(2)).unwrap();
+    assert_eq!(unknown.get("error").get("code"), &Json::Number(-32601.0));
+}
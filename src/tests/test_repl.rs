@@ -0,0 +1,86 @@
+use std::io::Cursor;
+
+use crate::repl::repl::Repl;
+
+/// Everything the REPL writes for `input`, prompts included
+fn repl(input: &str) -> String {
+    let mut repl = Repl::new(Cursor::new(input.to_string()), vec![]);
+
+    assert_eq!(repl.run(), 0);
+    String::from_utf8(repl.output().clone()).unwrap()
+}
+
+#[test]
+fn repl_keeps_definitions() {
+    let input = "\
+def x: int = 5
+x += 3
+fun square(n: int) -> int {
+    return n * n
+}
+square(x)
+\"text\"
+";
+
+    assert_eq!(repl(input), "> > > ... ... > 64: int\n> \"text\": str\n> ");
+}
+
+#[test]
+fn repl_commands() {
+    let output = repl(":type 2.5\n:asm 1 + 2\n:ast 1\n:nope\n:quit\n1\n");
+
+    assert!(output.contains("float\n"), "{output}");
+    assert!(output.contains("add rax, rbx"), "{output}");
+    assert!(output.contains("Factor"), "{output}");
+    assert!(output.contains("Unknown command ':nope'"), "{output}");
+    // nothing is run after `:quit`
+    assert!(!output.contains("1: int"), "{output}");
+}
+
+#[test]
+fn repl_errors_are_rolled_back() {
+    let output = repl("def y: int = missing\ndef y: int = 2\ny\n");
+
+    assert!(
+        output.contains("error: Variable with name 'missing' not found in current scope\n"),
+        "{output}"
+    );
+    assert!(
+        output.contains("  def y: int = missing\n               ^\n"),
+        "{output}"
+    );
+    assert!(output.contains("2: int"), "{output}");
+}
+
+#[test]
+fn repl_casts() {
+    let input = "\
+def x: float = 2.75
+x as int
+300 as int8
+def y: float = 7 as float
+y / 2.0
+";
+
+    assert_eq!(repl(input), "> > 2: int\n> 44: int8\n> > 3.5: float\n> ");
+}
+
+#[test]
+fn repl_unions() {
+    let input = "\
+union Result { Ok(int), Err(str), }
+Result.Ok(1)
+def r: Result = Result.Err(\"no\")
+r
+def n: int = 0
+match Result.Ok(7) { Result.Ok(value) { n = value + 1 } _ { n = 1 } }
+n
+match r { Result.Ok(value) { n = value } Result.Err(message) { n = 2 } }
+n
+";
+
+    assert_eq!(
+        repl(input),
+        "> > Result.Ok(1): Result\n> > Result.Err(\"no\"): Result\n> > > 8: int\n> > 2: int\n> "
+    );
+}